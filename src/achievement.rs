@@ -0,0 +1,108 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    board::PerfectClearEvent,
+    brick::{FullLineRemoveEvent, LevelUpEvent},
+    score::ComboEvent,
+    storage,
+};
+
+pub struct AchievementPlugin;
+
+impl Plugin for AchievementPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(storage::load::<Achievements>(ACHIEVEMENTS_FILE).unwrap_or_default())
+            .add_event::<AchievementUnlockEvent>()
+            .add_systems(Update, achievement_check);
+    }
+}
+
+const ACHIEVEMENTS_FILE: &str = "achievements.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Achievement {
+    FirstTetris,
+    PerfectClear,
+    Combo5,
+    Level10,
+    TotalLines100,
+}
+
+impl Achievement {
+    pub const ALL: [Achievement; 5] = [
+        Achievement::FirstTetris,
+        Achievement::PerfectClear,
+        Achievement::Combo5,
+        Achievement::Level10,
+        Achievement::TotalLines100,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Achievement::FirstTetris => "First Tetris",
+            Achievement::PerfectClear => "Perfect Clear",
+            Achievement::Combo5 => "Combo x5",
+            Achievement::Level10 => "Reach Level 10",
+            Achievement::TotalLines100 => "100 Lines Total",
+        }
+    }
+}
+
+/// unlocked achievements and the progress they need across games, saved on every change
+#[derive(Debug, Resource, Default, Serialize, Deserialize)]
+pub struct Achievements {
+    pub unlocked: HashSet<Achievement>,
+    pub total_lines: u32,
+}
+
+#[derive(Event)]
+pub struct AchievementUnlockEvent(pub Achievement);
+
+fn achievement_check(
+    mut achievements: ResMut<Achievements>,
+    mut full_line_remove_event_reader: EventReader<FullLineRemoveEvent>,
+    mut level_up_event_reader: EventReader<LevelUpEvent>,
+    mut combo_event_reader: EventReader<ComboEvent>,
+    mut perfect_clear_event_reader: EventReader<PerfectClearEvent>,
+    mut event_writer_unlock: EventWriter<AchievementUnlockEvent>,
+) {
+    let mut reached_arr = vec![];
+    let mut is_changed = false;
+
+    for full_line_remove_event in full_line_remove_event_reader.iter() {
+        achievements.total_lines += full_line_remove_event.0 as u32;
+        is_changed = true;
+
+        if full_line_remove_event.0 == 4 {
+            reached_arr.push(Achievement::FirstTetris);
+        }
+    }
+    if achievements.total_lines >= 100 {
+        reached_arr.push(Achievement::TotalLines100);
+    }
+    if level_up_event_reader.iter().any(|e| e.0 >= 10) {
+        reached_arr.push(Achievement::Level10);
+    }
+    if combo_event_reader.iter().any(|e| e.0 >= 5) {
+        reached_arr.push(Achievement::Combo5);
+    }
+    if !perfect_clear_event_reader.is_empty() {
+        perfect_clear_event_reader.clear();
+        reached_arr.push(Achievement::PerfectClear);
+    }
+
+    for achievement in reached_arr {
+        // already unlocked ones are not announced again
+        if achievements.unlocked.insert(achievement) {
+            is_changed = true;
+            event_writer_unlock.send(AchievementUnlockEvent(achievement));
+        }
+    }
+
+    if is_changed {
+        storage::save(ACHIEVEMENTS_FILE, achievements.as_ref());
+    }
+}
@@ -0,0 +1,380 @@
+use bevy::{
+    input::touch::Touches,
+    prelude::*,
+    utils::{HashMap, HashSet},
+    window::PrimaryWindow,
+};
+
+use crate::config::GameConfig;
+
+pub struct ActionPlugin;
+
+impl Plugin for ActionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActionState>()
+            .init_resource::<KeyBindings>()
+            .init_resource::<GamepadBindings>()
+            .add_systems(
+                PreUpdate,
+                (
+                    action_clear,
+                    action_mirror,
+                    action_keyboard,
+                    action_gamepad,
+                    action_touch,
+                    action_mouse,
+                )
+                    .chain()
+                    .after(bevy::input::InputSystem),
+            );
+    }
+}
+
+/// a tap moves less than this, in logical pixels
+const TAP_DISTANCE: f32 = 20.0;
+/// a swipe down must travel at least this far
+const SWIPE_DISTANCE: f32 = 60.0;
+/// swipes down faster than this, in logical pixels per second, hard drop
+const SWIPE_FAST_SPEED: f32 = 1500.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    SoftDrop,
+    HardDrop,
+    /// drop to the floor like a hard drop but without locking, only with `sonic_drop`
+    SonicDrop,
+    RotateCw,
+    RotateCcw,
+    Hold,
+    /// lock a resting brick right away, only with `soft_lock`
+    Lock,
+    Pause,
+    /// a new game with new pieces
+    Restart,
+    /// a new game with the pieces of this one, in the same order
+    SoftReset,
+}
+
+/// what the player asked for this frame, whatever the input device
+#[derive(Debug, Resource, Default)]
+pub struct ActionState {
+    pressed: HashSet<Action>,
+    just_pressed: HashSet<Action>,
+    just_released: HashSet<Action>,
+    /// on a mirrored board left and right, and with them the rotation directions, swap
+    mirrored: bool,
+}
+
+impl ActionState {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    pub fn just_released(&self, action: Action) -> bool {
+        self.just_released.contains(&action)
+    }
+
+    /// the action as the game sees it, after the mirror
+    fn map(&self, action: Action) -> Action {
+        if !self.mirrored {
+            return action;
+        }
+        match action {
+            Action::MoveLeft => Action::MoveRight,
+            Action::MoveRight => Action::MoveLeft,
+            Action::RotateCw => Action::RotateCcw,
+            Action::RotateCcw => Action::RotateCw,
+            _ => action,
+        }
+    }
+
+    pub fn press(&mut self, action: Action) {
+        let action = self.map(action);
+        if self.pressed.insert(action) {
+            self.just_pressed.insert(action);
+        }
+    }
+
+    pub fn release(&mut self, action: Action) {
+        let action = self.map(action);
+        if self.pressed.remove(&action) {
+            self.just_released.insert(action);
+        }
+    }
+
+    /// a press and release within one frame, e.g. from a tap
+    pub fn tap(&mut self, action: Action) {
+        let action = self.map(action);
+        self.just_pressed.insert(action);
+        self.just_released.insert(action);
+    }
+
+    pub fn get_just_pressed(&self) -> impl Iterator<Item = &Action> {
+        self.just_pressed.iter()
+    }
+
+    fn clear(&mut self) {
+        self.just_pressed.clear();
+        self.just_released.clear();
+    }
+}
+
+#[derive(Debug, Resource)]
+pub struct KeyBindings {
+    pub binding_arr: Vec<(KeyCode, Action)>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            binding_arr: vec![
+                (KeyCode::A, Action::MoveLeft),
+                (KeyCode::D, Action::MoveRight),
+                (KeyCode::S, Action::SoftDrop),
+                (KeyCode::Space, Action::HardDrop),
+                (KeyCode::ShiftLeft, Action::SonicDrop),
+                (KeyCode::W, Action::RotateCw),
+                (KeyCode::Q, Action::RotateCcw),
+                (KeyCode::C, Action::Hold),
+                (KeyCode::E, Action::Lock),
+                (KeyCode::P, Action::Pause),
+                (KeyCode::R, Action::Restart),
+                (KeyCode::Back, Action::SoftReset),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Resource)]
+pub struct GamepadBindings {
+    pub binding_arr: Vec<(GamepadButtonType, Action)>,
+}
+
+impl Default for GamepadBindings {
+    fn default() -> Self {
+        Self {
+            binding_arr: vec![
+                (GamepadButtonType::DPadLeft, Action::MoveLeft),
+                (GamepadButtonType::DPadRight, Action::MoveRight),
+                (GamepadButtonType::DPadDown, Action::SoftDrop),
+                (GamepadButtonType::DPadUp, Action::HardDrop),
+                (GamepadButtonType::North, Action::SonicDrop),
+                (GamepadButtonType::South, Action::RotateCw),
+                (GamepadButtonType::East, Action::RotateCcw),
+                (GamepadButtonType::LeftTrigger, Action::Hold),
+                (GamepadButtonType::RightTrigger, Action::Lock),
+                (GamepadButtonType::Start, Action::Pause),
+                (GamepadButtonType::Select, Action::Restart),
+                (GamepadButtonType::West, Action::SoftReset),
+            ],
+        }
+    }
+}
+
+/// runs in PreUpdate before the devices fill in the frame, the brick tests inject actions
+/// and run it at the end of the frame instead
+pub fn action_clear(mut action_state: ResMut<ActionState>) {
+    action_state.clear();
+}
+
+/// the mode only changes between games, so no key is held across the switch
+fn action_mirror(config: Res<GameConfig>, mut action_state: ResMut<ActionState>) {
+    if config.is_changed() {
+        action_state.mirrored = config.is_mirrored();
+    }
+}
+
+fn action_keyboard(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut action_state: ResMut<ActionState>,
+) {
+    for &(key, action) in key_bindings.binding_arr.iter() {
+        if keys.just_pressed(key) {
+            action_state.press(action);
+        }
+        if keys.just_released(key) {
+            action_state.release(action);
+        }
+    }
+}
+
+/// every connected gamepad drives the same actions
+fn action_gamepad(
+    gamepads: Res<Gamepads>,
+    buttons: Res<Input<GamepadButton>>,
+    gamepad_bindings: Res<GamepadBindings>,
+    mut action_state: ResMut<ActionState>,
+) {
+    for gamepad in gamepads.iter() {
+        for &(button_type, action) in gamepad_bindings.binding_arr.iter() {
+            let button = GamepadButton::new(gamepad, button_type);
+            if buttons.just_pressed(button) {
+                action_state.press(action);
+            }
+            if buttons.just_released(button) {
+                action_state.release(action);
+            }
+        }
+    }
+}
+
+/// turn a finished pointer gesture into an action, positions have y pointing down
+fn pointer_gesture(start: Vec2, end: Vec2, duration: f32, window_size: Vec2) -> Option<Action> {
+    let delta = end - start;
+
+    if delta.length() < TAP_DISTANCE {
+        return Some(if start.y < window_size.y / 3.0 {
+            Action::RotateCw
+        } else if start.x < window_size.x / 2.0 {
+            Action::MoveLeft
+        } else {
+            Action::MoveRight
+        });
+    }
+
+    if delta.y >= SWIPE_DISTANCE && delta.y > delta.x.abs() {
+        let speed = delta.y / duration.max(f32::EPSILON);
+        return Some(if speed >= SWIPE_FAST_SPEED {
+            Action::HardDrop
+        } else {
+            Action::SoftDrop
+        });
+    }
+
+    None
+}
+
+/// every finger is tracked on its own, so multi-touch gestures don't mix
+fn action_touch(
+    touches: Res<Touches>,
+    query_window: Query<&Window, With<PrimaryWindow>>,
+    config: Res<GameConfig>,
+    time: Res<Time>,
+    mut action_state: ResMut<ActionState>,
+    mut touch_start_time: Local<HashMap<u64, f32>>,
+) {
+    if !config.pointer_controls {
+        return;
+    }
+    let Ok(window) = query_window.get_single() else {
+        return;
+    };
+    let window_size = Vec2::new(window.width(), window.height());
+
+    for touch in touches.iter_just_pressed() {
+        touch_start_time.insert(touch.id(), time.elapsed_seconds());
+    }
+
+    for touch in touches.iter_just_released() {
+        let start_time = touch_start_time
+            .remove(&touch.id())
+            .unwrap_or_else(|| time.elapsed_seconds());
+        let duration = time.elapsed_seconds() - start_time;
+        if let Some(action) = pointer_gesture(
+            touch.start_position(),
+            touch.position(),
+            duration,
+            window_size,
+        ) {
+            action_state.tap(action);
+        }
+    }
+
+    for touch in touches.iter_just_canceled() {
+        touch_start_time.remove(&touch.id());
+    }
+}
+
+fn action_mouse(
+    buttons: Res<Input<MouseButton>>,
+    query_window: Query<&Window, With<PrimaryWindow>>,
+    config: Res<GameConfig>,
+    time: Res<Time>,
+    mut action_state: ResMut<ActionState>,
+    mut mouse_start: Local<Option<(Vec2, f32)>>,
+) {
+    if !config.pointer_controls {
+        return;
+    }
+    let Ok(window) = query_window.get_single() else {
+        return;
+    };
+    let Some(cursor_position) = window.cursor_position() else {
+        return;
+    };
+    let window_size = Vec2::new(window.width(), window.height());
+
+    if buttons.just_pressed(MouseButton::Left) {
+        *mouse_start = Some((cursor_position, time.elapsed_seconds()));
+    }
+
+    if buttons.just_released(MouseButton::Left) {
+        let Some((start, start_time)) = mouse_start.take() else {
+            return;
+        };
+        let duration = time.elapsed_seconds() - start_time;
+        if let Some(action) = pointer_gesture(start, cursor_position, duration, window_size) {
+            action_state.tap(action);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn press_is_just_pressed_for_one_frame() {
+        let mut action_state = ActionState::default();
+
+        action_state.press(Action::HardDrop);
+        assert!(action_state.pressed(Action::HardDrop));
+        assert!(action_state.just_pressed(Action::HardDrop));
+
+        action_state.clear();
+        action_state.press(Action::HardDrop);
+        assert!(action_state.pressed(Action::HardDrop));
+        assert!(!action_state.just_pressed(Action::HardDrop));
+
+        action_state.release(Action::HardDrop);
+        assert!(!action_state.pressed(Action::HardDrop));
+        assert!(action_state.just_released(Action::HardDrop));
+    }
+
+    #[test]
+    fn tap_presses_and_releases_in_one_frame() {
+        let mut action_state = ActionState::default();
+
+        action_state.tap(Action::RotateCw);
+        assert!(action_state.just_pressed(Action::RotateCw));
+        assert!(action_state.just_released(Action::RotateCw));
+        assert!(!action_state.pressed(Action::RotateCw));
+    }
+
+    #[test]
+    fn mirror_swaps_the_directions_only() {
+        let mut action_state = ActionState {
+            mirrored: true,
+            ..ActionState::default()
+        };
+
+        action_state.press(Action::MoveLeft);
+        action_state.press(Action::RotateCcw);
+        action_state.press(Action::Hold);
+        assert!(action_state.pressed(Action::MoveRight));
+        assert!(action_state.pressed(Action::RotateCw));
+        assert!(action_state.pressed(Action::Hold));
+        assert!(!action_state.pressed(Action::MoveLeft));
+        assert!(!action_state.pressed(Action::RotateCcw));
+
+        action_state.release(Action::MoveLeft);
+        assert!(!action_state.pressed(Action::MoveRight));
+    }
+}
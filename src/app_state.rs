@@ -3,6 +3,7 @@ use bevy::prelude::*;
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
 pub enum AppState {
     #[default]
+    Menu,
     Gaming,
     GameOver,
 }
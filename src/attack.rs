@@ -0,0 +1,83 @@
+use bevy::prelude::*;
+
+use crate::{
+    app_state::AppState,
+    brick::{PieceLockEvent, RestartEvent},
+    score::{combo_update, BackToBack, Combo},
+    transition::in_transition,
+};
+
+pub struct AttackPlugin;
+
+impl Plugin for AttackPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AttackStat>()
+            .add_systems(Update, attack_reset)
+            .add_systems(
+                Update,
+                (attack_time, attack_update.after(combo_update))
+                    .run_if(in_state(AppState::Gaming).and_then(not(in_transition))),
+            );
+    }
+}
+
+/// garbage sent by a 0, 1, 2, 3 and 4 line clear
+const ATTACK_LINES_ARR: [u32; 5] = [0, 0, 1, 2, 4];
+const ATTACK_BACK_TO_BACK: u32 = 1;
+/// extra garbage by combo count, the last value holds for longer combos
+const ATTACK_COMBO_ARR: [u32; 12] = [0, 1, 1, 2, 2, 3, 3, 4, 4, 4, 5, 5];
+
+/// garbage lines the clears of this game would have sent to an opponent
+#[derive(Debug, Resource, Default)]
+pub struct AttackStat {
+    pub lines: u32,
+    /// seconds spent playing, for the per minute rate
+    pub elapsed: f32,
+}
+
+impl AttackStat {
+    pub fn per_minute(&self) -> f32 {
+        if self.elapsed <= 0.0 {
+            return 0.0;
+        }
+        self.lines as f32 * 60.0 / self.elapsed
+    }
+}
+
+/// garbage a lock sends, `combo` and `back_to_back` already include this lock
+pub fn attack_for_clear(lines: u8, combo: u32, back_to_back: u32) -> u32 {
+    if lines == 0 {
+        return 0;
+    }
+
+    let mut attack = ATTACK_LINES_ARR[(lines as usize).min(ATTACK_LINES_ARR.len() - 1)];
+    if back_to_back > 1 {
+        attack += ATTACK_BACK_TO_BACK;
+    }
+    let combo_index = (combo.saturating_sub(1) as usize).min(ATTACK_COMBO_ARR.len() - 1);
+    attack + ATTACK_COMBO_ARR[combo_index]
+}
+
+fn attack_update(
+    mut event_reader: EventReader<PieceLockEvent>,
+    combo: Res<Combo>,
+    back_to_back: Res<BackToBack>,
+    mut attack_stat: ResMut<AttackStat>,
+) {
+    for piece_lock_event in event_reader.iter() {
+        attack_stat.lines += attack_for_clear(piece_lock_event.lines, combo.0, back_to_back.0);
+    }
+}
+
+fn attack_time(time: Res<Time>, mut attack_stat: ResMut<AttackStat>) {
+    attack_stat.elapsed += time.delta_seconds();
+}
+
+fn attack_reset(mut attack_stat: ResMut<AttackStat>, mut event_reader: EventReader<RestartEvent>) {
+    if event_reader.is_empty() {
+        return;
+    }
+    event_reader.clear();
+
+    *attack_stat = AttackStat::default();
+}
@@ -0,0 +1,114 @@
+use bevy::prelude::*;
+
+use crate::{
+    config::GameConfig,
+    randomizer::PieceRandomizer,
+    texture::{brick_sprite_bundle, BrickTexture},
+    theme::Theme,
+};
+
+pub struct BagPlugin;
+
+impl Plugin for BagPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_panel)
+            .add_systems(Update, (bag_preview_toggle, bag_preview_update).chain());
+    }
+}
+
+/// right of the next panel, clear of it in both layouts
+const PANEL_X: f32 = 400.0;
+const PANEL_TOP: f32 = 320.0;
+const PANEL_WIDTH: f32 = 80.0;
+/// vertical space of each brick in the panel, one per bag slot
+const SLOT_HEIGHT: f32 = 40.0;
+const SLOT_COUNT: usize = 7;
+const PANEL_CELL_WIDTH: f32 = 12.0;
+const PANEL_CELL_PADDING: f32 = 1.0;
+
+#[derive(Debug, Component)]
+pub struct BagPanel;
+
+#[derive(Debug, Component)]
+pub struct BagCell;
+
+fn setup_panel(mut commands: Commands, theme: Res<Theme>) {
+    let panel_height = SLOT_HEIGHT * SLOT_COUNT as f32;
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: theme.board_background,
+                custom_size: Some(Vec2::new(PANEL_WIDTH, panel_height)),
+                ..default()
+            },
+            transform: Transform::from_xyz(PANEL_X, PANEL_TOP - panel_height / 2.0, 0.1),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        BagPanel,
+    ));
+}
+
+fn bag_preview_toggle(keys: Res<Input<KeyCode>>, mut config: ResMut<GameConfig>) {
+    if keys.just_pressed(KeyCode::B) {
+        config.show_bag_preview = !config.show_bag_preview;
+    }
+}
+
+/// redraw the bricks left in the current bag, top one is dealt first
+fn bag_preview_update(
+    mut commands: Commands,
+    query_cell: Query<Entity, With<BagCell>>,
+    mut query_panel: Query<(&mut Sprite, &mut Visibility), With<BagPanel>>,
+    piece_randomizer: Res<PieceRandomizer>,
+    config: Res<GameConfig>,
+    theme: Res<Theme>,
+    brick_texture: Res<BrickTexture>,
+) {
+    if !piece_randomizer.is_changed() && !config.is_changed() && !theme.is_changed() {
+        return;
+    }
+
+    for entity in query_cell.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    // randomizers without a bag have nothing to show
+    let bag_arr = piece_randomizer
+        .bag_remaining()
+        .filter(|_| config.show_bag_preview);
+
+    let (mut sprite, mut visibility) = query_panel.single_mut();
+    sprite.color = theme.board_background;
+    *visibility = if bag_arr.is_some() {
+        Visibility::Visible
+    } else {
+        Visibility::Hidden
+    };
+
+    let Some(bag_arr) = bag_arr else {
+        return;
+    };
+
+    // shown the way they look on the board
+    let mirror_sign = if config.is_mirrored() { -1.0 } else { 1.0 };
+    for (slot, &brick_type_index) in bag_arr.iter().enumerate() {
+        let brick_shape =
+            &config.rotation_system.brick_type_arr()[brick_type_index].brick_shape_arr[0];
+        let slot_y = PANEL_TOP - SLOT_HEIGHT * (slot as f32 + 0.5);
+
+        for offset in brick_shape.centered_arr() {
+            let x = PANEL_X + offset.x * mirror_sign * PANEL_CELL_WIDTH;
+            let y = slot_y + offset.y * PANEL_CELL_WIDTH;
+            commands.spawn((
+                brick_sprite_bundle(
+                    &brick_texture,
+                    theme.brick_arr[brick_type_index],
+                    PANEL_CELL_WIDTH - PANEL_CELL_PADDING * 2.0,
+                    Transform::from_xyz(x, y, 0.2),
+                ),
+                BagCell,
+            ));
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use bevy::prelude::*;
+
+use crate::{
+    achievement::AchievementUnlockEvent, brick::LevelUpEvent, finesse::FinesseFaultEvent,
+    grace::TopOutGraceEvent, score::ComboEvent, theme::Theme,
+};
+
+pub struct BannerPlugin;
+
+impl Plugin for BannerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (banner_spawn, banner_animate).chain());
+    }
+}
+
+const BANNER_LIFETIME: f32 = 1.2;
+const BANNER_SCALE_IN: f32 = 0.15;
+const BANNER_FADE_OUT: f32 = 0.4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BannerKind {
+    LevelUp,
+    Combo,
+    Finesse,
+    Achievement,
+    Saved,
+}
+
+impl BannerKind {
+    /// banners of different kinds stack vertically instead of overlapping
+    fn offset_y(self) -> f32 {
+        match self {
+            BannerKind::LevelUp => 60.0,
+            BannerKind::Combo => 0.0,
+            BannerKind::Finesse => -60.0,
+            BannerKind::Achievement => 120.0,
+            BannerKind::Saved => -120.0,
+        }
+    }
+}
+
+#[derive(Debug, Component)]
+pub struct Banner {
+    pub kind: BannerKind,
+    pub timer: Timer,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn banner_spawn(
+    mut commands: Commands,
+    query_banner: Query<(Entity, &Banner)>,
+    mut event_reader_level_up: EventReader<LevelUpEvent>,
+    mut event_reader_combo: EventReader<ComboEvent>,
+    mut event_reader_finesse: EventReader<FinesseFaultEvent>,
+    mut event_reader_achievement: EventReader<AchievementUnlockEvent>,
+    mut event_reader_grace: EventReader<TopOutGraceEvent>,
+    theme: Res<Theme>,
+) {
+    let mut banner_arr = vec![];
+    if let Some(level_up_event) = event_reader_level_up.iter().last() {
+        banner_arr.push((BannerKind::LevelUp, format!("Level {}!", level_up_event.0)));
+    }
+    if let Some(combo_event) = event_reader_combo.iter().last() {
+        banner_arr.push((BannerKind::Combo, format!("Combo x{}", combo_event.0)));
+    }
+    if let Some(finesse_fault_event) = event_reader_finesse.iter().last() {
+        banner_arr.push((
+            BannerKind::Finesse,
+            format!("Finesse +{}", finesse_fault_event.0),
+        ));
+    }
+    if let Some(achievement_unlock_event) = event_reader_achievement.iter().last() {
+        banner_arr.push((
+            BannerKind::Achievement,
+            format!("Unlocked: {}", achievement_unlock_event.0.name()),
+        ));
+    }
+    if event_reader_grace.iter().last().is_some() {
+        banner_arr.push((BannerKind::Saved, "Saved!".to_string()));
+    }
+
+    for (kind, value) in banner_arr {
+        // a new banner replaces the old one of the same kind
+        for (entity, banner) in query_banner.iter() {
+            if banner.kind == kind {
+                commands.entity(entity).despawn();
+            }
+        }
+
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    value,
+                    TextStyle {
+                        font_size: 60.0,
+                        color: theme.gameover_text,
+                        ..default()
+                    },
+                )
+                .with_alignment(TextAlignment::Center),
+                transform: Transform::from_xyz(0.0, kind.offset_y(), 10.0)
+                    .with_scale(Vec3::splat(0.0)),
+                ..default()
+            },
+            Banner {
+                kind,
+                timer: Timer::from_seconds(BANNER_LIFETIME, TimerMode::Once),
+            },
+        ));
+    }
+}
+
+fn banner_animate(
+    mut commands: Commands,
+    mut query_banner: Query<(Entity, &mut Banner, &mut Transform, &mut Text)>,
+    time: Res<Time>,
+) {
+    for (entity, mut banner, mut transform, mut text) in query_banner.iter_mut() {
+        banner.timer.tick(time.delta());
+        if banner.timer.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let elapsed = banner.timer.elapsed_secs();
+        let scale = (elapsed / BANNER_SCALE_IN).min(1.0);
+        transform.scale = Vec3::splat(scale);
+
+        let left = BANNER_LIFETIME - elapsed;
+        let alpha = (left / BANNER_FADE_OUT).min(1.0);
+        for section in text.sections.iter_mut() {
+            section.style.color.set_a(alpha);
+        }
+    }
+}
@@ -0,0 +1,477 @@
+use bevy::prelude::*;
+
+use crate::{
+    app_state::AppState,
+    brick::{
+        BrickKind, BrickMoveable, BrickPos, FullLineRemoveEvent, GarbageRiseEvent, PieceLockEvent,
+        RestartEvent,
+    },
+    config::{BoardConfig, GameConfig, GameMode},
+    grace::TopOutGraceEvent,
+    transition::Transition,
+};
+
+pub struct BoardPlugin;
+
+impl Plugin for BoardPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Board>()
+            .init_resource::<BoardMetrics>()
+            .init_resource::<StackHeightMax>()
+            .add_event::<PerfectClearEvent>()
+            .add_systems(
+                Update,
+                setup_board.run_if(resource_changed::<BoardConfig>()),
+            )
+            // runs after PostUpdate so line clear despawns are applied
+            .add_systems(
+                Last,
+                (board_sync, ceiling_check, stack_height_max_update).chain(),
+            );
+    }
+}
+
+/// a lock cleared lines and left the board empty
+#[derive(Event)]
+pub struct PerfectClearEvent;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    /// stable brick of a type in `RotationSystem::brick_type_arr`
+    Brick(usize),
+    /// a cell of a garbage row, it has no brick type
+    Garbage,
+}
+
+/// stable cells of the board, row 0 at the bottom
+#[derive(Debug, Clone, PartialEq, Eq, Resource)]
+pub struct Board {
+    width: i8,
+    height: i8,
+    cell_arr: Vec<Option<Cell>>,
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        let board_config = BoardConfig::default();
+        Self::new(board_config.width, board_config.total_height())
+    }
+}
+
+impl Board {
+    pub fn new(width: i8, height: i8) -> Self {
+        Self {
+            width,
+            height,
+            cell_arr: vec![None; width as usize * height as usize],
+        }
+    }
+
+    pub fn width(&self) -> i8 {
+        self.width
+    }
+
+    pub fn height(&self) -> i8 {
+        self.height
+    }
+
+    pub fn is_inside(&self, x: i8, y: i8) -> bool {
+        x >= 0 && x < self.width && y >= 0 && y < self.height
+    }
+
+    fn index(&self, x: i8, y: i8) -> Option<usize> {
+        if !self.is_inside(x, y) {
+            return None;
+        }
+        Some(y as usize * self.width as usize + x as usize)
+    }
+
+    /// the cell at x, y, None for empty or out of the board
+    pub fn get(&self, x: i8, y: i8) -> Option<Cell> {
+        self.index(x, y).and_then(|index| self.cell_arr[index])
+    }
+
+    pub fn set(&mut self, x: i8, y: i8, cell: Option<Cell>) {
+        if let Some(index) = self.index(x, y) {
+            self.cell_arr[index] = cell;
+        }
+    }
+
+    pub fn is_filled(&self, x: i8, y: i8) -> bool {
+        self.get(x, y).is_some()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cell_arr.iter().all(|cell| cell.is_none())
+    }
+
+    pub fn clear(&mut self) {
+        self.cell_arr.fill(None);
+    }
+
+    /// remove every full row, dropping the rows above, returns the number removed
+    pub fn clear_full_lines(&mut self) -> u8 {
+        let width = self.width as usize;
+        let row_arr = self
+            .cell_arr
+            .chunks(width)
+            .filter(|row| row.iter().any(|cell| cell.is_none()))
+            .flatten()
+            .copied()
+            .collect::<Vec<Option<Cell>>>();
+        let lines = (self.cell_arr.len() - row_arr.len()) / width;
+
+        self.cell_arr[..row_arr.len()].copy_from_slice(&row_arr);
+        self.cell_arr[row_arr.len()..].fill(None);
+        lines as u8
+    }
+
+    /// one above the highest filled cell of the column, 0 for an empty column
+    pub fn column_height(&self, x: i8) -> i8 {
+        (0..self.height)
+            .rev()
+            .find(|&y| self.is_filled(x, y))
+            .map_or(0, |y| y + 1)
+    }
+
+    pub fn aggregate_height(&self) -> i32 {
+        (0..self.width).map(|x| self.column_height(x) as i32).sum()
+    }
+
+    /// empty cells with a filled cell somewhere above them in the same column
+    pub fn holes(&self) -> u32 {
+        (0..self.width)
+            .map(|x| {
+                (0..self.column_height(x))
+                    .filter(|&y| !self.is_filled(x, y))
+                    .count() as u32
+            })
+            .sum()
+    }
+
+    /// sum of height differences between neighbouring columns
+    pub fn bumpiness(&self) -> u32 {
+        (1..self.width)
+            .map(|x| self.column_height(x).abs_diff(self.column_height(x - 1)) as u32)
+            .sum()
+    }
+
+    /// one line per row from the top down, `#` for a filled cell and `.` for an empty one
+    pub fn to_ascii(&self) -> String {
+        (0..self.height)
+            .rev()
+            .map(|y| {
+                (0..self.width)
+                    .map(|x| if self.is_filled(x, y) { '#' } else { '.' })
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// the reverse of `to_ascii`, filled cells become garbage, blank lines and indentation
+    /// are skipped, and the last line is row 0 so a position only needs its lowest rows
+    pub fn from_ascii(ascii: &str, board_config: &BoardConfig) -> Result<Self, String> {
+        let line_arr = ascii
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<&str>>();
+        if line_arr.len() > board_config.total_height() as usize {
+            return Err(format!(
+                "{} rows don't fit on a board of {}",
+                line_arr.len(),
+                board_config.total_height()
+            ));
+        }
+
+        let mut board = Board::new(board_config.width, board_config.total_height());
+        for (y, line) in line_arr.iter().rev().enumerate() {
+            if line.chars().count() != board_config.width as usize {
+                return Err(format!(
+                    "row {} is {} wide, the board is {}",
+                    y,
+                    line.chars().count(),
+                    board_config.width
+                ));
+            }
+            for (x, char) in line.chars().enumerate() {
+                let cell = match char {
+                    '#' => Some(Cell::Garbage),
+                    '.' => None,
+                    _ => return Err(format!("unknown cell '{}' at ({}, {})", char, x, y)),
+                };
+                board.set(x as i8, y as i8, cell);
+            }
+        }
+        Ok(board)
+    }
+}
+
+#[derive(Debug, Clone, Default, Resource)]
+pub struct BoardMetrics {
+    pub column_height_arr: Vec<i8>,
+    pub aggregate_height: i32,
+    pub holes: u32,
+    pub bumpiness: u32,
+}
+
+impl BoardMetrics {
+    pub fn from_board(board: &Board) -> Self {
+        Self {
+            column_height_arr: (0..board.width()).map(|x| board.column_height(x)).collect(),
+            aggregate_height: board.aggregate_height(),
+            holes: board.holes(),
+            bumpiness: board.bumpiness(),
+        }
+    }
+}
+
+/// highest column of the game so far, shown on the summary of the stack trainer
+#[derive(Debug, Resource, Default)]
+pub struct StackHeightMax(pub i8);
+
+/// on the first frame and again whenever the menu resizes the board
+fn setup_board(
+    board_config: Res<BoardConfig>,
+    mut board: ResMut<Board>,
+    mut board_metrics: ResMut<BoardMetrics>,
+) {
+    *board = Board::new(board_config.width, board_config.total_height());
+    *board_metrics = BoardMetrics::from_board(&board);
+}
+
+/// rebuild the board from the stable bricks after anything changed the stack, a lock that
+/// completes lines waits for them to be removed after the clear delay
+#[allow(clippy::too_many_arguments)]
+pub fn board_sync(
+    query_brick_stable: Query<(&BrickPos, Option<&BrickKind>), Without<BrickMoveable>>,
+    mut board: ResMut<Board>,
+    mut board_metrics: ResMut<BoardMetrics>,
+    mut piece_lock_event_reader: EventReader<PieceLockEvent>,
+    mut full_line_remove_event_reader: EventReader<FullLineRemoveEvent>,
+    mut garbage_rise_event_reader: EventReader<GarbageRiseEvent>,
+    mut restart_event_reader: EventReader<RestartEvent>,
+    mut grace_event_reader: EventReader<TopOutGraceEvent>,
+    mut perfect_clear_event_writer: EventWriter<PerfectClearEvent>,
+) {
+    let lock_lines_arr = piece_lock_event_reader
+        .iter()
+        .map(|e| e.lines)
+        .collect::<Vec<u8>>();
+    let is_line_cleared = full_line_remove_event_reader.iter().count() > 0;
+    let is_clear_pending = lock_lines_arr.iter().any(|&lines| lines > 0) && !is_line_cleared;
+    let is_changed = !lock_lines_arr.is_empty()
+        || is_line_cleared
+        || !garbage_rise_event_reader.is_empty()
+        || !restart_event_reader.is_empty()
+        || !grace_event_reader.is_empty();
+    if is_clear_pending || !is_changed {
+        return;
+    }
+    garbage_rise_event_reader.clear();
+    restart_event_reader.clear();
+    grace_event_reader.clear();
+
+    board.clear();
+    for (brick_pos, brick_kind) in query_brick_stable.iter() {
+        let cell = match brick_kind {
+            Some(brick_kind) => Cell::Brick(brick_kind.0),
+            None => Cell::Garbage,
+        };
+        board.set(brick_pos.x, brick_pos.y, Some(cell));
+    }
+
+    *board_metrics = BoardMetrics::from_board(&board);
+
+    if is_line_cleared && board.is_empty() {
+        perfect_clear_event_writer.send(PerfectClearEvent);
+    }
+}
+
+/// in ceiling mode a lock that leaves the stack above the ceiling ends the game, once, the
+/// metrics may change again while the game over fades in
+fn ceiling_check(
+    board_metrics: Res<BoardMetrics>,
+    board_config: Res<BoardConfig>,
+    config: Res<GameConfig>,
+    state: Res<State<AppState>>,
+    mut transition: ResMut<Transition>,
+) {
+    if !board_metrics.is_changed()
+        || config.mode != GameMode::Ceiling
+        || *state.get() != AppState::Gaming
+        || transition.is_active()
+    {
+        return;
+    }
+
+    if board_metrics
+        .column_height_arr
+        .iter()
+        .any(|&height| height > board_config.ceiling_row())
+    {
+        transition.start(AppState::GameOver, "above the ceiling");
+    }
+}
+
+/// a restart starts over from the empty board it rebuilds
+fn stack_height_max_update(
+    board_metrics: Res<BoardMetrics>,
+    mut stack_height_max: ResMut<StackHeightMax>,
+    mut restart_event_reader: EventReader<RestartEvent>,
+) {
+    if !restart_event_reader.is_empty() {
+        restart_event_reader.clear();
+        stack_height_max.0 = 0;
+    }
+    if !board_metrics.is_changed() {
+        return;
+    }
+
+    let height = board_metrics
+        .column_height_arr
+        .iter()
+        .copied()
+        .max()
+        .unwrap_or(0);
+    if height > stack_height_max.0 {
+        stack_height_max.0 = height;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a default sized board with the rows drawn bottom aligned, `#` for a filled cell
+    fn board_from(ascii: &str) -> Board {
+        let mut board = Board::default();
+        let row_arr = ascii
+            .lines()
+            .map(str::trim)
+            .filter(|row| !row.is_empty())
+            .collect::<Vec<&str>>();
+        for (y, row) in row_arr.iter().rev().enumerate() {
+            for (x, cell) in row.chars().enumerate() {
+                if cell == '#' {
+                    board.set(x as i8, y as i8, Some(Cell::Brick(0)));
+                }
+            }
+        }
+        board
+    }
+
+    #[test]
+    fn metrics_of_an_empty_board() {
+        let board = Board::default();
+
+        assert!((0..board.width()).all(|x| board.column_height(x) == 0));
+        assert_eq!(board.aggregate_height(), 0);
+        assert_eq!(board.holes(), 0);
+        assert_eq!(board.bumpiness(), 0);
+    }
+
+    #[test]
+    fn column_height_is_one_above_the_highest_filled_cell() {
+        let board = board_from(
+            "
+            #.........
+            ..........
+            #.#.......
+            ##.#......
+            ",
+        );
+
+        assert_eq!(board.column_height(0), 4);
+        assert_eq!(board.column_height(1), 1);
+        assert_eq!(board.column_height(2), 2);
+        assert_eq!(board.column_height(3), 1);
+        assert!((4..board.width()).all(|x| board.column_height(x) == 0));
+        assert_eq!(board.aggregate_height(), 8);
+    }
+
+    #[test]
+    fn holes_count_empty_cells_under_a_filled_one() {
+        let board = board_from(
+            "
+            #.........
+            ..........
+            #.#.......
+            ##.#......
+            ",
+        );
+
+        // column 0 row 2 and column 2 row 0
+        assert_eq!(board.holes(), 2);
+    }
+
+    #[test]
+    fn bumpiness_sums_neighbouring_height_differences() {
+        let board = board_from(
+            "
+            #.........
+            ..........
+            #.#.......
+            ##.#......
+            ",
+        );
+
+        // 4 1 2 1 0 0 0 0 0 0
+        assert_eq!(board.bumpiness(), 3 + 1 + 1 + 1);
+    }
+
+    #[test]
+    fn flat_full_width_rows_have_no_bumpiness_or_holes() {
+        let board = board_from(
+            "
+            #########.
+            #########.
+            ",
+        );
+
+        assert_eq!(board.aggregate_height(), 18);
+        assert_eq!(board.holes(), 0);
+        assert_eq!(board.bumpiness(), 2);
+    }
+
+    #[test]
+    fn ascii_round_trip_keeps_the_board() {
+        let board = board_from(
+            "
+            ..#.......
+            #.##...#..
+            ##########
+            ",
+        );
+
+        let ascii = board.to_ascii();
+        assert_eq!(
+            ascii.lines().count(),
+            BoardConfig::default().total_height() as usize
+        );
+        assert!(ascii.ends_with("..#.......\n#.##...#..\n##########"));
+        assert_eq!(board_from(&ascii), board);
+    }
+
+    #[test]
+    fn from_ascii_refuses_more_rows_than_the_board() {
+        let board_config = BoardConfig::default();
+        let ascii = vec![".........."; board_config.total_height() as usize + 1].join("\n");
+
+        let err = Board::from_ascii(&ascii, &board_config).unwrap_err();
+        assert!(err.contains("don't fit"), "{}", err);
+    }
+
+    #[test]
+    fn from_ascii_refuses_a_row_of_another_width() {
+        let err = Board::from_ascii("..........\n.........", &BoardConfig::default()).unwrap_err();
+        assert!(err.starts_with("row 0 is 9 wide"), "{}", err);
+    }
+
+    #[test]
+    fn from_ascii_refuses_an_unknown_cell() {
+        let err = Board::from_ascii("....x.....", &BoardConfig::default()).unwrap_err();
+        assert!(err.starts_with("unknown cell 'x' at (4, 0)"), "{}", err);
+    }
+}
@@ -1,56 +1,218 @@
-use std::ops::{Add, AddAssign, Sub, SubAssign};
+use std::{
+    ops::{Add, AddAssign, Sub, SubAssign},
+    time::Duration,
+};
 
-use bevy::{prelude::*, utils::HashMap};
-use lazy_static::*;
+use bevy::{asset::LoadState, prelude::*, utils::HashMap};
 use rand::Rng;
 
-use crate::app_state::AppState;
+use crate::{
+    action::{Action, ActionState},
+    app_state::AppState,
+    board::{BoardMetrics, PerfectClearEvent},
+    config::{BoardConfig, GameConfig, GameMode, GarbageGaps, LockBehavior},
+    hold::{Hold, HoldSwapIn, HoldSwapOut},
+    letterbox::BarCamera,
+    randomizer::{GameRng, GameSeed, PieceRandomizer},
+    root::OnBoard,
+    rotation::Pivot,
+    score::Level,
+    texture::{brick_sprite_bundle, BrickTexture},
+    theme::Theme,
+    transition::{in_transition, Transition},
+};
 
 pub struct BrickPlugin;
 
 impl Plugin for BrickPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.init_resource::<BrickState>()
-            .add_event::<SpawnEvent>()
-            .add_event::<StableEvent>()
-            .add_event::<NewPosEvent>()
-            .add_event::<FullLineCheckEvent>()
-            .add_event::<FullLineRemoveEvent>()
-            .add_event::<GameOverEvent>()
-            .add_event::<RestartEvent>()
-            .add_systems(Startup, (setup_board, setup_spawn, setup_fall_timer))
+        add_gameplay(app);
+        app.init_resource::<BorderPulse>()
+            .add_systems(Startup, setup_board)
+            .add_systems(Update, (grid_line_toggle, grid_line_visibility).chain())
+            .add_systems(Update, strict_rotation_toggle)
+            .add_systems(Update, random_spawn_rotation_toggle)
+            .add_systems(Update, rotate_repeat_toggle)
+            .add_systems(Update, background_layout)
+            .add_systems(
+                Update,
+                (background_brick_toggle, background_brick_sync).chain(),
+            )
+            .add_systems(
+                Update,
+                (background_image_check, background_image_fit).chain(),
+            )
+            .add_systems(Update, (theme_apply, ceiling_line_update).chain())
             .add_systems(
                 Update,
                 (
-                    brick_gen,
-                    brick_auto_fall,
-                    input.after(brick_auto_fall),
-                    brick_apply_new_pos.after(input),
-                    brick_stable.after(brick_apply_new_pos),
+                    trajectory_toggle,
+                    trajectory_update
+                        .after(theme_apply)
+                        .after(brick_apply_new_pos),
                 )
-                    .run_if(in_state(AppState::Gaming)),
+                    .chain(),
             )
-            .add_systems(Update, restart.run_if(in_state(AppState::GameOver)))
+            .add_systems(Update, lock_flash_update.after(theme_apply))
             .add_systems(
-                PostUpdate,
-                brick_fullline_clear.run_if(in_state(AppState::Gaming)),
-            );
+                Update,
+                resting_update.after(brick_lock).after(theme_apply).run_if(
+                    in_state(AppState::Gaming)
+                        .and_then(not(in_transition))
+                        .and_then(not(is_paused)),
+                ),
+            )
+            .add_systems(
+                Update,
+                board_layout
+                    .before(background_brick_sync)
+                    .run_if(resource_changed::<BoardConfig>()),
+            )
+            .add_systems(Update, spawning_update)
+            .add_systems(Update, garbage_preview_update)
+            .add_systems(Update, border_pulse.after(theme_apply))
+            .add_systems(Last, vanish_zone_clip);
     }
 }
 
-const BOARD_WIDTH: i8 = 10;
-const BOARD_HEIGHT: i8 = 20;
+/// the resources, events and systems that play the game, without anything drawn; the
+/// plugin and the tests both build their app through this, so their schedules stay the same
+pub(crate) fn add_gameplay(app: &mut App) {
+    app.init_resource::<BrickState>()
+        .init_resource::<LockTimer>()
+        .init_resource::<SoftDropTimer>()
+        .init_resource::<SpawnDropGuard>()
+        .init_resource::<AutoRepeat>()
+        .init_resource::<RotateRepeat>()
+        .init_resource::<Paused>()
+        .init_resource::<ClearDelay>()
+        .init_resource::<RestartPending>()
+        .add_event::<SpawnEvent>()
+        .add_event::<StableEvent>()
+        .add_event::<NewPosEvent>()
+        .add_event::<FullLineCheckEvent>()
+        .add_event::<FullLineRemoveEvent>()
+        .add_event::<PieceLockEvent>()
+        .add_event::<SoftDropEvent>()
+        .add_event::<LevelUpEvent>()
+        .add_event::<GameOverEvent>()
+        .add_event::<ResetEvent>()
+        .add_event::<GarbageEvent>()
+        .add_event::<GarbageRiseEvent>()
+        .init_resource::<GarbageQueue>()
+        .add_event::<RestartEvent>()
+        .init_resource::<Gravity>()
+        .add_systems(
+            Update,
+            (
+                brick_gen.before(gravity_update),
+                gravity_update,
+                // nothing to move while lines clear, and `brick_gen` starts gravity over
+                brick_auto_fall
+                    .after(gravity_update)
+                    .run_if(not(is_clearing)),
+                input.after(brick_auto_fall).run_if(not(is_clearing)),
+                brick_apply_new_pos.after(input),
+                brick_lock.after(brick_apply_new_pos),
+                brick_stable.after(brick_lock),
+                garbage_rising,
+                garbage_queue.after(garbage_rising),
+                brick_garbage_rise.after(brick_stable).after(garbage_queue),
+                brick_hold.after(brick_stable),
+            )
+                .run_if(
+                    in_state(AppState::Gaming)
+                        .and_then(not(in_transition))
+                        .and_then(not(is_paused)),
+                ),
+        )
+        .add_systems(OnEnter(AppState::GameOver), gameover_freeze)
+        .add_systems(OnEnter(AppState::Gaming), restart_pending_clear)
+        .add_systems(
+            Update,
+            restart_input.run_if(
+                in_state(AppState::GameOver)
+                    .or_else(in_state(AppState::Gaming).and_then(not(is_paused)))
+                    .and_then(not(in_transition)),
+            ),
+        )
+        // a game still running starts over without leaving Gaming, once everything of the
+        // frame has run, so nothing acts on the despawned bricks
+        .add_systems(
+            Update,
+            restart
+                .after(restart_input)
+                .after(brick_hold)
+                .after(brick_garbage_rise)
+                .run_if(on_event::<ResetEvent>()),
+        )
+        .add_systems(
+            OnTransition {
+                from: AppState::Menu,
+                to: AppState::Gaming,
+            },
+            restart,
+        )
+        .add_systems(
+            OnTransition {
+                from: AppState::GameOver,
+                to: AppState::Gaming,
+            },
+            restart,
+        )
+        .add_systems(Update, falling_update)
+        .add_systems(
+            PostUpdate,
+            brick_fullline_clear.run_if(in_state(AppState::Gaming).and_then(not(in_transition))),
+        );
+}
+
 const BOARD_BORDER: i8 = 5;
 
-const GRID_WIDTH: i8 = 32;
+pub const GRID_WIDTH: i8 = 32;
 const GRID_PADDING: i8 = 2;
-const BRICK_WIDTH: i8 = GRID_WIDTH - GRID_PADDING * 2;
-
-const START_X: i8 = -BOARD_WIDTH / 2;
-const START_Y: i8 = -BOARD_HEIGHT / 2;
-
-const SPAWN_X: i8 = BOARD_WIDTH / 2 - 2;
-const SPAWN_Y: i8 = BOARD_HEIGHT - 2;
+pub const BRICK_WIDTH: i8 = GRID_WIDTH - GRID_PADDING * 2;
+const GRID_LINE_WIDTH: f32 = 1.0;
+const CEILING_LINE_WIDTH: f32 = 2.0;
+
+/// gravity is defined in frames per cell at this rate, independent of the real frame rate
+const GRAVITY_FPS: f32 = 60.0;
+/// frames per cell for each level, starting at level 1
+const GRAVITY_FRAMES_PER_CELL: [u8; 29] = [
+    48, 43, 38, 33, 28, 23, 18, 13, 8, 6, 5, 5, 5, 4, 4, 4, 3, 3, 3, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2,
+];
+/// past the table gravity keeps growing up to this many cells per frame (20G)
+const GRAVITY_MAX: f32 = 20.0;
+
+/// seconds a brick must rest before a soft drop press locks it, so tapping down onto the
+/// stack doesn't skip the lock delay
+const SOFT_DROP_LOCK_GUARD: f32 = 0.1;
+/// seconds after a spawn that a soft drop held from the last brick doesn't repeat
+const SPAWN_DROP_GUARD: f32 = 0.1;
+
+const LOCK_DELAY: f32 = 0.5;
+const LOCK_RESET_MAX: u32 = 15;
+/// color factor of a resting brick with soft lock
+const RESTING_DIM: f32 = 0.6;
+
+const LOCK_FLASH_DURATION: f32 = 0.15;
+const SPAWN_ANIMATION_DURATION: f32 = 0.1;
+/// scale a spawning brick grows from
+const SPAWN_ANIMATION_SCALE: f32 = 0.4;
+
+const BORDER_PULSE_DURATION: f32 = 1.0;
+const BORDER_PULSE_COUNT: f32 = 3.0;
+
+/// seconds the cells above cleared lines take to fall into place
+const LINE_CLEAR_FALL_DURATION: f32 = 0.15;
+
+/// seconds incoming garbage is shown under the board before it rises
+const GARBAGE_TELEGRAPH_DELAY: f32 = 1.0;
+const GARBAGE_PREVIEW_HEIGHT: f32 = 6.0;
+/// training garbage leaves room for the spawn and a few moves
+const TRAINING_GARBAGE_MAX: u8 = 16;
+/// rows of a board kept clear of training garbage
+const BOARD_HEIGHT_MARGIN: i8 = 4;
 
 #[derive(Debug, Default, Component, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct BrickPos {
@@ -110,7 +272,114 @@ impl SubAssign<BrickPos> for BrickPos {
 }
 
 #[derive(Component)]
-struct BrickMoveable;
+pub struct BrickMoveable;
+
+/// index into `RotationSystem::brick_type_arr`, used to pick the brick color
+#[derive(Debug, Component, Clone, Copy)]
+pub struct BrickKind(pub usize);
+
+/// stable brick that came in as a garbage row
+#[derive(Debug, Component)]
+pub struct GarbageBrick;
+
+/// just locked brick, its color fades from white back to the brick color
+#[derive(Debug, Component)]
+pub struct LockFlash {
+    pub timer: Timer,
+}
+
+impl Default for LockFlash {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(LOCK_FLASH_DURATION, TimerMode::Once),
+        }
+    }
+}
+
+/// just spawned brick, grows and fades in, it moves and collides as usual meanwhile
+#[derive(Debug, Component)]
+pub struct Spawning {
+    pub timer: Timer,
+}
+
+impl Default for Spawning {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(SPAWN_ANIMATION_DURATION, TimerMode::Once),
+        }
+    }
+}
+
+/// stable brick falling after a line clear, its `BrickPos` is already final, the
+/// transform eases from `from_y` down to it
+#[derive(Debug, Component)]
+pub struct Falling {
+    pub from_y: f32,
+    pub timer: Timer,
+}
+
+/// the brick whose spawn ended the game, shown even in the vanish zone until restart
+#[derive(Debug, Component)]
+pub struct ToppedOut;
+
+/// garbage rows waiting to rise together, by the column of their gap, the first ends up on top
+#[derive(Debug, Resource)]
+pub struct GarbageQueue {
+    pub gap_x_arr: Vec<i8>,
+    pub timer: Timer,
+    /// time to the next row of rising mode
+    pub rising_timer: Timer,
+}
+
+impl Default for GarbageQueue {
+    fn default() -> Self {
+        Self {
+            gap_x_arr: vec![],
+            timer: Timer::from_seconds(GARBAGE_TELEGRAPH_DELAY, TimerMode::Once),
+            rising_timer: Timer::from_seconds(
+                GameConfig::default().rising_garbage_interval,
+                TimerMode::Repeating,
+            ),
+        }
+    }
+}
+
+/// a cell of the next garbage row, drawn under the board
+#[derive(Component)]
+struct GarbagePreview;
+
+#[derive(Component)]
+struct BoardBorder;
+
+/// runs after a perfect clear, the border pulses toward the accent color meanwhile
+#[derive(Debug, Resource)]
+pub struct BorderPulse(Timer);
+
+impl Default for BorderPulse {
+    fn default() -> Self {
+        let mut timer = Timer::from_seconds(BORDER_PULSE_DURATION, TimerMode::Once);
+        timer.tick(timer.duration());
+        Self(timer)
+    }
+}
+
+#[derive(Component)]
+struct BoardBackground;
+
+/// picture behind the whole playfield, sized to the window
+#[derive(Component)]
+struct BackgroundImage {
+    handle: Handle<Image>,
+}
+
+#[derive(Component)]
+struct BackgroundBrick(BrickPos);
+
+#[derive(Component)]
+struct GridLine;
+
+#[derive(Component)]
+struct CeilingLine;
 
 #[derive(Debug)]
 pub struct BrickShape {
@@ -121,6 +390,18 @@ impl BrickShape {
     pub fn new(brick_pos_arr: [BrickPos; 4]) -> Self {
         Self { brick_pos_arr }
     }
+
+    /// the cells relative to the middle of their bounding box, in cells, so a panel can
+    /// draw the shape centered
+    pub fn centered_arr(&self) -> [Vec2; 4] {
+        let min_x = self.brick_pos_arr.iter().map(|pos| pos.x).min().unwrap();
+        let max_x = self.brick_pos_arr.iter().map(|pos| pos.x).max().unwrap();
+        let min_y = self.brick_pos_arr.iter().map(|pos| pos.y).min().unwrap();
+        let max_y = self.brick_pos_arr.iter().map(|pos| pos.y).max().unwrap();
+        let center = Vec2::new((min_x + max_x) as f32, (min_y + max_y) as f32) / 2.0;
+        self.brick_pos_arr
+            .map(|pos| Vec2::new(pos.x as f32, pos.y as f32) - center)
+    }
 }
 
 pub struct BrickType {
@@ -142,423 +423,1862 @@ pub struct BrickState {
     pub brick_pos_origin: BrickPos,
 }
 
-#[derive(Event)]
-pub struct SpawnEvent;
+/// spawn a new moveable brick, of the given type or a random one
+#[derive(Event, Default)]
+pub struct SpawnEvent {
+    pub brick_type_index: Option<usize>,
+    /// the brick comes out of the hold panel
+    pub from_hold: bool,
+}
 #[derive(Event)]
 pub struct StableEvent;
 #[derive(Event)]
-pub struct NewPosEvent([BrickPos; 4]);
+pub struct NewPosEvent(pub [BrickPos; 4]);
 #[derive(Event)]
 pub struct FullLineCheckEvent;
 #[derive(Event)]
 pub struct FullLineRemoveEvent(pub u8);
+/// sent after every lock once full lines are checked, with the number of lines cleared
+#[derive(Event)]
+pub struct PieceLockEvent {
+    pub lines: u8,
+}
+#[derive(Event)]
+pub struct LevelUpEvent(pub u32);
+/// cells the player moved the brick down with soft drop, auto-fall isn't counted
+#[derive(Event)]
+pub struct SoftDropEvent(pub u8);
+/// the next brick can't spawn, `topout_grace` decides whether the game ends
 #[derive(Event)]
 pub struct GameOverEvent;
+/// restart or soft reset pressed while playing, the game starts over in place
+#[derive(Event)]
+pub struct ResetEvent;
+/// queue this many garbage rows, each with one random gap, they rise after a short telegraph
+#[derive(Event)]
+pub struct GarbageEvent(pub u8);
+/// sent once queued garbage rows pushed the stack up
+#[derive(Event)]
+pub struct GarbageRiseEvent(pub u8);
 #[derive(Event)]
 pub struct RestartEvent;
 
-#[derive(Debug, Resource, Default)]
-pub struct FallTimer(Timer);
-
-fn setup_board(mut commands: Commands) {
-    let board_inner_width = BOARD_WIDTH as i32 * GRID_WIDTH as i32;
-    let board_inner_height = BOARD_HEIGHT as i32 * GRID_WIDTH as i32;
-    let board_outer_width = board_inner_width + (BOARD_BORDER as i32 * 2);
-    let board_outer_height = board_inner_height + (BOARD_BORDER as i32 * 2);
-
-    // outer board
-    commands.spawn(SpriteBundle {
-        sprite: Sprite {
-            color: Color::rgb(0.8, 0.8, 0.8),
-            custom_size: Some(Vec2::new(
-                board_outer_width as f32,
-                board_outer_height as f32,
-            )),
-            ..default()
-        },
-        transform: Transform::from_xyz(0.0, 0.0, 0.0),
-        ..default()
-    });
-
-    // inner board
-    commands.spawn(SpriteBundle {
-        sprite: Sprite {
-            color: Color::rgb(0.2, 0.2, 0.2),
-            custom_size: Some(Vec2::new(
-                board_inner_width as f32,
-                board_inner_height as f32,
-            )),
-            ..default()
-        },
-        transform: Transform::from_xyz(0.0, 0.0, 0.1),
-        ..default()
-    });
+/// cells per frame the moveable brick falls, `accumulated` keeps the fractional part
+#[derive(Debug, Resource)]
+pub struct Gravity {
+    pub cells_per_frame: f32,
+    pub accumulated: f32,
+}
 
-    // background brick
-    let brick_size = Vec2::new(BRICK_WIDTH as f32, BRICK_WIDTH as f32);
-    for y in 0..BOARD_HEIGHT {
-        for x in 0..BOARD_WIDTH {
-            commands.spawn(SpriteBundle {
-                sprite: Sprite {
-                    color: Color::rgba(0.2, 0.8, 0.1, 0.1),
-                    custom_size: Some(brick_size),
-                    ..default()
-                },
-                transform: get_brick_pos(x, y, 0.2),
-                ..default()
-            });
+impl Default for Gravity {
+    fn default() -> Self {
+        Self {
+            cells_per_frame: gravity_for_level(1),
+            accumulated: 0.0,
         }
     }
 }
 
-fn setup_spawn(mut event_writer: EventWriter<SpawnEvent>) {
-    event_writer.send(SpawnEvent);
-}
-
-fn setup_fall_timer(mut commands: Commands) {
-    commands.insert_resource(FallTimer(Timer::from_seconds(0.8, TimerMode::Repeating)));
+/// cells per frame for a level, following the frames per cell table,
+/// then one more cell per frame each level up to 20G
+pub fn gravity_for_level(level: u32) -> f32 {
+    let level = level.max(1) as usize;
+    match GRAVITY_FRAMES_PER_CELL.get(level - 1) {
+        Some(&frames) => 1.0 / frames as f32,
+        None => ((level - GRAVITY_FRAMES_PER_CELL.len()) as f32).min(GRAVITY_MAX),
+    }
 }
 
-fn get_brick_pos(x: i8, y: i8, z: f32) -> Transform {
-    let xy = get_brick_pos_xy(x, y);
-    Transform::from_xyz(xy.0 as f32, xy.1 as f32, z)
-}
+/// repeats soft drop while S is held, at `soft_drop_speed`
+#[derive(Debug, Resource, Default)]
+pub struct SoftDropTimer(Timer);
 
-fn get_brick_pos_xy(x: i8, y: i8) -> (i32, i32) {
-    (
-        ((START_X + x) as i32 * GRID_WIDTH as i32 + BRICK_WIDTH as i32 / 2 + GRID_PADDING as i32),
-        ((START_Y + y) as i32 * GRID_WIDTH as i32 + BRICK_WIDTH as i32 / 2 + GRID_PADDING as i32),
-    )
+/// full lines found on a lock, left on the board until the clear delay runs out
+#[derive(Debug, Resource, Default)]
+pub struct ClearDelay {
+    y_arr: Vec<i8>,
+    timer: Timer,
+    /// a lock was sent to `brick_fullline_clear` and not checked yet, it runs in PostUpdate
+    /// so without this a spawn could slip in before the lines are even found
+    is_check_pending: bool,
 }
 
-fn restart(
-    mut commands: Commands,
-    query_brick: Query<Entity, With<BrickPos>>,
-    mut state: ResMut<NextState<AppState>>,
-    mut event_writer_spawn: EventWriter<SpawnEvent>,
-    keys: Res<Input<KeyCode>>,
-) {
-    if keys.just_pressed(KeyCode::R) {
-        for entity in query_brick.iter() {
-            commands.entity(entity).despawn();
-        }
-
-        state.set(AppState::Gaming);
-        event_writer_spawn.send(SpawnEvent);
+impl ClearDelay {
+    /// lines of the last lock are still being checked or cleared
+    pub fn is_active(&self) -> bool {
+        self.is_check_pending || !self.y_arr.is_empty()
     }
 }
 
-fn brick_gen(
-    mut commands: Commands,
-    query_brick_stable: Query<&BrickPos, Without<BrickMoveable>>,
-    mut brick_state: ResMut<BrickState>,
-    mut game_state: ResMut<NextState<AppState>>,
-    mut event_reader: EventReader<SpawnEvent>,
-) {
-    if event_reader.is_empty() {
-        return;
-    }
-    event_reader.clear();
-
-    let mut rng = rand::thread_rng();
-    let brick_type_idx = rng.gen_range(0..BRICK_TYPE_ARRAY.len());
-    let brick_shape_idx = 0;
-    let brick_type = &BRICK_TYPE_ARRAY[brick_type_idx];
-    let brick_shape = &brick_type.brick_shape_arr[brick_shape_idx];
+/// run condition, true from a lock with full lines until the rows above them have fallen,
+/// the same window `brick_gen` waits out before the next spawn
+pub fn is_clearing(clear_delay: Res<ClearDelay>, query_falling: Query<(), With<Falling>>) -> bool {
+    clear_delay.is_active() || !query_falling.is_empty()
+}
 
-    brick_state.brick_type_index = brick_type_idx;
-    brick_state.brick_shape_index = brick_shape_idx;
-    brick_state.brick_pos_origin = BrickPos::new(SPAWN_X, SPAWN_Y);
+/// gameplay systems don't run and game time stands still while set
+#[derive(Debug, Resource, Default)]
+pub struct Paused(pub bool);
 
-    let brick_pos_stable_arr = query_brick_stable.iter().collect::<Vec<&BrickPos>>();
+pub fn is_paused(paused: Res<Paused>) -> bool {
+    paused.0
+}
 
-    let brick_pos_spawn_arr = brick_shape
-        .brick_pos_arr
-        .iter()
-        .map(|pos| BrickPos::new(SPAWN_X + pos.x, SPAWN_Y + pos.y))
-        .collect::<Vec<BrickPos>>();
+/// a restart out of GameOver was asked for and Gaming isn't entered yet, restart keys held
+/// or mashed meanwhile are ignored so the game starts over once
+#[derive(Debug, Resource, Default)]
+pub struct RestartPending(bool);
 
-    let mut is_game_over = false;
-    for brick_pos_spawn in brick_pos_spawn_arr.iter() {
-        if brick_pos_stable_arr.contains(&brick_pos_spawn) {
-            is_game_over = true;
-        }
-    }
+/// restarts on every spawn, a fresh press still drops right away
+#[derive(Debug, Resource)]
+pub struct SpawnDropGuard(Timer);
 
-    for brick_pos_spawn in brick_pos_spawn_arr {
-        commands.spawn((
-            SpriteBundle {
-                sprite: Sprite {
-                    color: Color::rgb(0.5, 1.0, 0.2),
-                    custom_size: Some(Vec2::new(BRICK_WIDTH as f32, BRICK_WIDTH as f32)),
-                    ..default()
-                },
-                transform: get_brick_pos(brick_pos_spawn.x, brick_pos_spawn.y, 1.0),
-                ..default()
-            },
-            brick_pos_spawn,
-            BrickMoveable,
-        ));
+impl Default for SpawnDropGuard {
+    fn default() -> Self {
+        Self(Timer::from_seconds(SPAWN_DROP_GUARD, TimerMode::Once))
     }
+}
 
-    if is_game_over {
-        game_state.set(AppState::GameOver);
-    }
+/// delayed auto shift: a held direction moves once, waits `das_delay`,
+/// then repeats every `arr_interval`
+#[derive(Debug, Resource, Default)]
+pub struct AutoRepeat {
+    /// -1 left, 1 right, 0 none held
+    pub direction: i8,
+    das: Timer,
+    arr: Timer,
 }
 
-fn input(
-    query_brick_movable: Query<&mut BrickPos, With<BrickMoveable>>,
-    query_brick_stable: Query<&BrickPos, Without<BrickMoveable>>,
-    keys: Res<Input<KeyCode>>,
-    mut brick_state: ResMut<BrickState>,
-    mut event_writer_stable: EventWriter<StableEvent>,
-    mut event_writer_move: EventWriter<NewPosEvent>,
-) {
-    if query_brick_movable.is_empty() {
-        return;
+impl AutoRepeat {
+    fn restart(&mut self, direction: i8, config: &GameConfig) {
+        self.direction = direction;
+        self.das = Timer::from_seconds(config.das_delay, TimerMode::Once);
+        self.arr = Timer::from_seconds(config.arr_interval, TimerMode::Repeating);
     }
 
-    let brick_move_arr = query_brick_movable.iter().collect::<Vec<&BrickPos>>();
-    let brick_stable_arr = query_brick_stable.iter().collect::<Vec<&BrickPos>>();
-    let mut brick_pos_move = BrickPos::default();
+    fn is_held(action_state: &ActionState, direction: i8) -> bool {
+        if direction < 0 {
+            action_state.pressed(Action::MoveLeft)
+        } else {
+            action_state.pressed(Action::MoveRight)
+        }
+    }
 
-    // shift
-    if keys.just_pressed(KeyCode::W) {
-        let brick_shape_idx_new = (brick_state.brick_shape_index + 1)
-            % BRICK_TYPE_ARRAY[brick_state.brick_type_index]
-                .brick_shape_arr
-                .len();
-        let brick_shape =
-            &BRICK_TYPE_ARRAY[brick_state.brick_type_index].brick_shape_arr[brick_shape_idx_new];
+    /// the horizontal move for this frame, -1, 0 or 1
+    fn update(&mut self, action_state: &ActionState, config: &GameConfig, delta: Duration) -> i8 {
+        let press_arr = [
+            (action_state.just_pressed(Action::MoveLeft), -1),
+            (action_state.just_pressed(Action::MoveRight), 1),
+        ];
+        for (press, direction) in press_arr {
+            if !press {
+                continue;
+            }
 
-        let brick_pos_new_arr = brick_shape
-            .brick_pos_arr
-            .iter()
-            .map(|&pos| pos + brick_state.brick_pos_origin)
-            .collect::<Vec<BrickPos>>();
-        if !is_legal(&brick_pos_new_arr, &brick_stable_arr) {
-            return;
+            // a direction change either moves right away or waits for the old key
+            if self.direction == 0
+                || self.direction == direction
+                || config.das_direction_change_immediate
+                || !Self::is_held(action_state, self.direction)
+            {
+                self.restart(direction, config);
+                return direction;
+            }
         }
 
-        brick_state.brick_shape_index = brick_shape_idx_new;
-        event_writer_move.send(NewPosEvent(brick_pos_new_arr.try_into().unwrap()));
-        return;
-    }
+        if self.direction == 0 {
+            return 0;
+        }
 
-    // move
-    if keys.just_pressed(KeyCode::S) {
-        brick_pos_move.y = -1;
-    } else if keys.just_pressed(KeyCode::A) {
-        brick_pos_move.x = -1;
-    } else if keys.just_pressed(KeyCode::D) {
-        brick_pos_move.x = 1;
-    } else if keys.just_pressed(KeyCode::Space) {
-        let mut max_down = 0;
-        loop {
-            let down = max_down + 1;
-            let brick_pos_move = BrickPos::new(0, -down);
-            let brick_pos_new_arr = brick_move_arr
-                .iter()
-                .map(|&&pos| pos + brick_pos_move)
-                .collect::<Vec<BrickPos>>();
-            if !is_legal(&brick_pos_new_arr, &brick_stable_arr) {
-                break;
+        // releasing the repeating direction hands over to the other one if still held
+        if !Self::is_held(action_state, self.direction) {
+            let other = -self.direction;
+            if Self::is_held(action_state, other) {
+                self.restart(other, config);
+            } else {
+                self.direction = 0;
             }
-            max_down = down;
+            return 0;
         }
-        brick_pos_move.y = -max_down;
-    } else {
-        return;
-    }
 
-    let brick_pos_new_arr = brick_move_arr
-        .iter()
-        .map(|&&pos| pos + brick_pos_move)
-        .collect::<Vec<BrickPos>>();
+        if !self.das.finished() {
+            self.das.tick(delta);
+            return if self.das.just_finished() {
+                self.direction
+            } else {
+                0
+            };
+        }
 
-    if !is_legal(&brick_pos_new_arr, &brick_stable_arr) {
-        // force down when can't move, stable all brick
-        if brick_pos_move.y == -1 {
-            event_writer_stable.send(StableEvent);
+        self.arr.tick(delta);
+        if self.arr.just_finished() {
+            self.direction
+        } else {
+            0
         }
-        return;
     }
+}
 
-    brick_state.brick_pos_origin += brick_pos_move;
-
-    event_writer_move.send(NewPosEvent(brick_pos_new_arr.try_into().unwrap()));
+/// a held rotate key rotates once, waits `rotate_repeat_delay`, then rotates again every
+/// `rotate_repeat_interval` when `rotate_repeat` is on
+#[derive(Debug, Resource, Default)]
+pub struct RotateRepeat {
+    /// -1 counterclockwise, 1 clockwise, 0 none held
+    pub direction: i8,
+    delay: Timer,
+    interval: Timer,
 }
 
-fn brick_auto_fall(
-    query_brick_movable: Query<&BrickPos, With<BrickMoveable>>,
-    query_brick_stable: Query<&BrickPos, Without<BrickMoveable>>,
-    mut fall_timer: ResMut<FallTimer>,
-    time: Res<Time>,
-    mut brick_state: ResMut<BrickState>,
-    mut event_writer_stable: EventWriter<StableEvent>,
-    mut event_writer_new_pos: EventWriter<NewPosEvent>,
-) {
-    fall_timer.0.tick(time.delta());
+impl RotateRepeat {
+    fn restart(&mut self, direction: i8, config: &GameConfig) {
+        self.direction = direction;
+        self.delay = Timer::from_seconds(config.rotate_repeat_delay, TimerMode::Once);
+        self.interval = Timer::from_seconds(config.rotate_repeat_interval, TimerMode::Repeating);
+    }
 
-    if fall_timer.0.finished() {
-        if query_brick_movable.is_empty() {
-            return;
+    fn is_held(action_state: &ActionState, direction: i8) -> bool {
+        if direction < 0 {
+            action_state.pressed(Action::RotateCcw)
+        } else {
+            action_state.pressed(Action::RotateCw)
         }
+    }
 
-        let brick_move_arr = query_brick_movable.iter().collect::<Vec<&BrickPos>>();
-        let brick_stable_arr = query_brick_stable.iter().collect::<Vec<&BrickPos>>();
-
-        let brick_pos_move = BrickPos::new(0, -1);
-
-        let brick_pos_new_arr = brick_move_arr
-            .iter()
-            .map(|&&pos| pos + brick_pos_move)
-            .collect::<Vec<BrickPos>>();
+    /// the rotation for this frame, -1, 0 or 1, a press always rotates
+    fn update(&mut self, action_state: &ActionState, config: &GameConfig, delta: Duration) -> i8 {
+        let press_arr = [
+            (action_state.just_pressed(Action::RotateCw), 1),
+            (action_state.just_pressed(Action::RotateCcw), -1),
+        ];
+        for (press, direction) in press_arr {
+            if press {
+                self.restart(direction, config);
+                return direction;
+            }
+        }
 
-        if !is_legal(&brick_pos_new_arr, &brick_stable_arr) {
-            event_writer_stable.send(StableEvent);
-            return;
+        if !config.rotate_repeat
+            || self.direction == 0
+            || !Self::is_held(action_state, self.direction)
+        {
+            self.direction = 0;
+            return 0;
         }
 
-        brick_state.brick_pos_origin += brick_pos_move;
+        if !self.delay.finished() {
+            self.delay.tick(delta);
+            return if self.delay.just_finished() {
+                self.direction
+            } else {
+                0
+            };
+        }
 
-        event_writer_new_pos.send(NewPosEvent(brick_pos_new_arr.try_into().unwrap()));
+        self.interval.tick(delta);
+        if self.interval.just_finished() {
+            self.direction
+        } else {
+            0
+        }
     }
 }
 
-fn brick_apply_new_pos(
-    mut query_brick_movable: Query<(&mut Transform, &mut BrickPos), With<BrickMoveable>>,
-    mut shift_event: EventReader<NewPosEvent>,
-) {
-    if query_brick_movable.is_empty() || shift_event.is_empty() {
-        return;
-    }
-
-    for shift_event in shift_event.iter() {
-        let brick_pos_new_arr = shift_event.0;
-
-        for (idx, (mut transform, mut brick_pos)) in query_brick_movable.iter_mut().enumerate() {
-            brick_pos.x = brick_pos_new_arr[idx].x;
-            brick_pos.y = brick_pos_new_arr[idx].y;
-
-            let xy = get_brick_pos_xy(brick_pos.x, brick_pos.y);
+/// counts down while the moveable brick rests on something, locks it when finished
+#[derive(Debug, Resource)]
+pub struct LockTimer {
+    pub timer: Timer,
+    /// resets used by moves and rotations since the brick last reached a new lowest row
+    pub resets: u32,
+    /// lowest origin y the brick has reached
+    pub lowest_y: i8,
+    /// the brick can't fall, the timer is running
+    pub resting: bool,
+}
 
-            transform.translation.x = xy.0 as f32;
-            transform.translation.y = xy.1 as f32;
+impl Default for LockTimer {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(LOCK_DELAY, TimerMode::Once),
+            resets: 0,
+            // above any board, the spawn sets the real row
+            lowest_y: i8::MAX,
+            resting: false,
         }
     }
 }
 
-fn brick_stable(
+fn setup_board(mut commands: Commands, asset_server: Res<AssetServer>, config: Res<GameConfig>) {
+    // behind everything else, `background_image_fit` sizes it
+    if let Some(path) = &config.background_image {
+        let handle: Handle<Image> = asset_server.load(path.as_str());
+        commands.spawn((
+            SpriteBundle {
+                texture: handle.clone(),
+                transform: Transform::from_xyz(0.0, 0.0, -1.0),
+                ..default()
+            },
+            BackgroundImage { handle },
+        ));
+    }
+}
+
+/// draw the board for the current size, on the first frame and again whenever the menu
+/// resizes it, the bricks of an earlier game don't fit the new size and go with the old board
+#[allow(clippy::type_complexity)]
+fn board_layout(
     mut commands: Commands,
-    query_movable: Query<Entity, With<BrickMoveable>>,
-    mut stable_event_reader: EventReader<StableEvent>,
-    mut spawn_event_writer: EventWriter<SpawnEvent>,
-    mut full_line_check_event_writer: EventWriter<FullLineCheckEvent>,
+    query_board: Query<
+        Entity,
+        Or<(
+            With<BoardBorder>,
+            With<BoardBackground>,
+            With<GridLine>,
+            With<CeilingLine>,
+            With<BackgroundBrick>,
+            With<BrickPos>,
+        )>,
+    >,
+    board_config: Res<BoardConfig>,
+    config: Res<GameConfig>,
+    theme: Res<Theme>,
 ) {
-    if stable_event_reader.is_empty() {
-        return;
+    for entity in query_board.iter() {
+        commands.entity(entity).despawn_recursive();
     }
-    stable_event_reader.clear();
 
-    for entity in query_movable.iter() {
-        commands.entity(entity).remove::<BrickMoveable>();
+    let board_inner_width = board_config.width as i32 * GRID_WIDTH as i32;
+    let board_inner_height = board_config.height as i32 * GRID_WIDTH as i32;
+    let board_outer_width = board_inner_width + (BOARD_BORDER as i32 * 2);
+    let board_outer_height = board_inner_height + (BOARD_BORDER as i32 * 2);
+
+    // outer board
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: theme.board_border,
+                custom_size: Some(Vec2::new(
+                    board_outer_width as f32,
+                    board_outer_height as f32,
+                )),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 0.0, 0.0),
+            ..default()
+        },
+        BoardBorder,
+        OnBoard,
+    ));
+
+    // inner board
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: theme.board_background,
+                custom_size: Some(Vec2::new(
+                    board_inner_width as f32,
+                    board_inner_height as f32,
+                )),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 0.0, 0.1),
+            ..default()
+        },
+        BoardBackground,
+        OnBoard,
+    ));
+
+    // grid lines at inner cell boundaries
+    let grid_line_color = theme.grid_line;
+    let grid_line_visibility = if config.show_grid_lines {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    for x in 1..board_config.width {
+        let line_x = -board_inner_width as f32 / 2.0 + x as f32 * GRID_WIDTH as f32;
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: grid_line_color,
+                    custom_size: Some(Vec2::new(GRID_LINE_WIDTH, board_inner_height as f32)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(line_x, 0.0, 0.15),
+                visibility: grid_line_visibility,
+                ..default()
+            },
+            GridLine,
+            OnBoard,
+        ));
+    }
+    for y in 1..board_config.height {
+        let line_y = -board_inner_height as f32 / 2.0 + y as f32 * GRID_WIDTH as f32;
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: grid_line_color,
+                    custom_size: Some(Vec2::new(board_inner_width as f32, GRID_LINE_WIDTH)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(0.0, line_y, 0.15),
+                visibility: grid_line_visibility,
+                ..default()
+            },
+            GridLine,
+            OnBoard,
+        ));
+    }
+
+    // ceiling line, a warning for every mode and the top-out line in ceiling mode
+    let ceiling_row = board_config.ceiling_row();
+    if ceiling_row < board_config.height {
+        let line_y = -board_inner_height as f32 / 2.0 + ceiling_row as f32 * GRID_WIDTH as f32;
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: theme.ceiling,
+                    custom_size: Some(Vec2::new(board_inner_width as f32, CEILING_LINE_WIDTH)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(0.0, line_y, 0.3),
+                ..default()
+            },
+            CeilingLine,
+            OnBoard,
+        ));
+    }
+
+    if config.show_background_bricks {
+        spawn_background_bricks(&mut commands, &board_config, &config, &theme);
+    }
+}
+
+fn spawn_background_bricks(
+    commands: &mut Commands,
+    board_config: &BoardConfig,
+    config: &GameConfig,
+    theme: &Theme,
+) {
+    let brick_size = Vec2::new(BRICK_WIDTH as f32, BRICK_WIDTH as f32);
+    for y in 0..board_config.height {
+        for x in 0..board_config.width {
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: theme.background_brick,
+                        custom_size: Some(brick_size),
+                        ..default()
+                    },
+                    transform: get_brick_pos(x, y, 0.2, board_config, config.is_mirrored()),
+                    ..default()
+                },
+                BackgroundBrick(BrickPos::new(x, y)),
+                OnBoard,
+            ));
+        }
+    }
+}
+
+fn background_brick_toggle(keys: Res<Input<KeyCode>>, mut config: ResMut<GameConfig>) {
+    if keys.just_pressed(KeyCode::X) {
+        config.show_background_bricks = !config.show_background_bricks;
+    }
+}
+
+/// the cells are spawned and despawned rather than hidden, the trajectory tint goes with them
+fn background_brick_sync(
+    mut commands: Commands,
+    query_background: Query<Entity, With<BackgroundBrick>>,
+    board_config: Res<BoardConfig>,
+    config: Res<GameConfig>,
+    theme: Res<Theme>,
+) {
+    if !config.is_changed() {
+        return;
+    }
+
+    if !config.show_background_bricks {
+        for entity in query_background.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+    } else if query_background.is_empty() {
+        spawn_background_bricks(&mut commands, &board_config, &config, &theme);
+    }
+}
+
+fn grid_line_toggle(keys: Res<Input<KeyCode>>, mut config: ResMut<GameConfig>) {
+    if keys.just_pressed(KeyCode::G) {
+        config.show_grid_lines = !config.show_grid_lines;
+    }
+}
+
+/// only touches the border color, so it can run together with anything moving the board
+fn border_pulse(
+    mut query_border: Query<&mut Sprite, With<BoardBorder>>,
+    mut border_pulse: ResMut<BorderPulse>,
+    mut perfect_clear_event_reader: EventReader<PerfectClearEvent>,
+    theme: Res<Theme>,
+    time: Res<Time>,
+) {
+    if perfect_clear_event_reader.iter().count() > 0 {
+        border_pulse.0.reset();
+    }
+    if border_pulse.0.finished() {
+        return;
+    }
+    border_pulse.0.tick(time.delta());
+
+    let from = theme.board_border;
+    let to = theme.accent;
+    let t = (border_pulse.0.percent() * BORDER_PULSE_COUNT * std::f32::consts::PI)
+        .sin()
+        .abs();
+    for mut sprite in query_border.iter_mut() {
+        // land exactly on the theme color
+        sprite.color = if border_pulse.0.finished() {
+            from
+        } else {
+            Color::rgb(
+                from.r() + (to.r() - from.r()) * t,
+                from.g() + (to.g() - from.g()) * t,
+                from.b() + (to.b() - from.b()) * t,
+            )
+        };
+    }
+}
+
+fn strict_rotation_toggle(keys: Res<Input<KeyCode>>, mut config: ResMut<GameConfig>) {
+    if keys.just_pressed(KeyCode::K) {
+        config.strict_rotation = !config.strict_rotation;
+    }
+}
+
+fn rotate_repeat_toggle(keys: Res<Input<KeyCode>>, mut config: ResMut<GameConfig>) {
+    if keys.just_pressed(KeyCode::F10) {
+        config.rotate_repeat = !config.rotate_repeat;
+    }
+}
+
+fn random_spawn_rotation_toggle(keys: Res<Input<KeyCode>>, mut config: ResMut<GameConfig>) {
+    if keys.just_pressed(KeyCode::F8) {
+        config.random_spawn_rotation = !config.random_spawn_rotation;
+    }
+}
+
+fn trajectory_toggle(keys: Res<Input<KeyCode>>, mut config: ResMut<GameConfig>) {
+    if keys.just_pressed(KeyCode::H) {
+        config.show_trajectory = !config.show_trajectory;
+    }
+}
+
+/// tint the background cells below each column of the moveable brick, cleared on lock
+fn trajectory_update(
+    query_brick_movable: Query<&BrickPos, With<BrickMoveable>>,
+    query_brick_changed: Query<(), (With<BrickMoveable>, Changed<BrickPos>)>,
+    mut query_background: Query<(&BackgroundBrick, &mut Sprite)>,
+    mut removed_movable: RemovedComponents<BrickMoveable>,
+    config: Res<GameConfig>,
+    theme: Res<Theme>,
+) {
+    let is_lock = removed_movable.iter().count() > 0;
+    if query_brick_changed.is_empty() && !is_lock && !config.is_changed() && !theme.is_changed() {
+        return;
+    }
+
+    // lowest cell of the brick in each column it covers
+    let mut column_bottom_map = HashMap::new();
+    if config.show_trajectory {
+        for brick_pos in query_brick_movable.iter() {
+            let bottom = column_bottom_map.entry(brick_pos.x).or_insert(brick_pos.y);
+            *bottom = (*bottom).min(brick_pos.y);
+        }
+    }
+
+    for (background_brick, mut sprite) in query_background.iter_mut() {
+        let brick_pos = background_brick.0;
+        let is_below = column_bottom_map
+            .get(&brick_pos.x)
+            .is_some_and(|&bottom| brick_pos.y < bottom);
+        sprite.color = if is_below {
+            theme.trajectory
+        } else {
+            theme.background_brick
+        };
+    }
+}
+
+/// a background image that can't be loaded is dropped, the solid colors stay
+fn background_image_check(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    query_image: Query<(Entity, &BackgroundImage)>,
+) {
+    for (entity, background_image) in query_image.iter() {
+        if asset_server.get_load_state(&background_image.handle) == LoadState::Failed {
+            warn!("background image failed to load");
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// cover what the camera shows in world units, so resizing, zooming and the letterbox keep
+/// it filling the screen
+fn background_image_fit(
+    query_projection: Query<&OrthographicProjection, (With<Camera2d>, Without<BarCamera>)>,
+    mut query_image: Query<&mut Sprite, With<BackgroundImage>>,
+) {
+    let Ok(projection) = query_projection.get_single() else {
+        return;
+    };
+    let size = projection.area.size();
+
+    for mut sprite in query_image.iter_mut() {
+        if sprite.custom_size != Some(size) {
+            sprite.custom_size = Some(size);
+        }
+    }
+}
+
+/// the background cells follow the board when mirror mode is switched
+fn background_layout(
+    board_config: Res<BoardConfig>,
+    config: Res<GameConfig>,
+    mut query_background: Query<(&BackgroundBrick, &mut Transform)>,
+) {
+    if !config.is_changed() {
+        return;
+    }
+
+    for (background_brick, mut transform) in query_background.iter_mut() {
+        let brick_pos = background_brick.0;
+        let xy = get_brick_pos_xy(
+            brick_pos.x,
+            brick_pos.y,
+            &board_config,
+            config.is_mirrored(),
+        );
+        transform.translation.x = xy.0 as f32;
+        transform.translation.y = xy.1 as f32;
+    }
+}
+
+fn grid_line_visibility(
+    config: Res<GameConfig>,
+    mut query_grid_line: Query<&mut Visibility, With<GridLine>>,
+) {
+    if !config.is_changed() {
+        return;
+    }
+
+    for mut visibility in query_grid_line.iter_mut() {
+        *visibility = if config.show_grid_lines {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn theme_apply(
+    theme: Res<Theme>,
+    mut query_sprite: ParamSet<(
+        Query<&mut Sprite, With<BoardBorder>>,
+        Query<&mut Sprite, With<BoardBackground>>,
+        Query<&mut Sprite, With<GridLine>>,
+        Query<&mut Sprite, With<BackgroundBrick>>,
+        Query<(&mut Sprite, &BrickKind), Without<ToppedOut>>,
+        Query<&mut Sprite, With<GarbageBrick>>,
+    )>,
+) {
+    if !theme.is_changed() {
+        return;
+    }
+
+    for mut sprite in query_sprite.p0().iter_mut() {
+        sprite.color = theme.board_border;
+    }
+    for mut sprite in query_sprite.p1().iter_mut() {
+        sprite.color = theme.board_background;
+    }
+    for mut sprite in query_sprite.p2().iter_mut() {
+        sprite.color = theme.grid_line;
+    }
+    for mut sprite in query_sprite.p3().iter_mut() {
+        sprite.color = theme.background_brick;
+    }
+    for (mut sprite, brick_kind) in query_sprite.p4().iter_mut() {
+        sprite.color = theme.brick_arr[brick_kind.0];
+    }
+    for mut sprite in query_sprite.p5().iter_mut() {
+        sprite.color = theme.garbage;
+    }
+}
+
+/// the ceiling line turns to the danger color while the stack is above it
+fn ceiling_line_update(
+    mut query_ceiling_line: Query<&mut Sprite, With<CeilingLine>>,
+    board_metrics: Res<BoardMetrics>,
+    board_config: Res<BoardConfig>,
+    theme: Res<Theme>,
+) {
+    if !board_metrics.is_changed() && !theme.is_changed() {
+        return;
+    }
+
+    let is_over = board_metrics
+        .column_height_arr
+        .iter()
+        .any(|&height| height > board_config.ceiling_row());
+    for mut sprite in query_ceiling_line.iter_mut() {
+        sprite.color = if is_over { theme.danger } else { theme.ceiling };
+    }
+}
+
+fn gravity_update(level: Res<Level>, config: Res<GameConfig>, mut gravity: ResMut<Gravity>) {
+    if level.is_changed() || config.is_changed() {
+        gravity.cells_per_frame =
+            (gravity_for_level(level.0) * config.gravity_scale).min(GRAVITY_MAX);
+    }
+}
+
+fn get_brick_pos(x: i8, y: i8, z: f32, board_config: &BoardConfig, is_mirrored: bool) -> Transform {
+    let xy = get_brick_pos_xy(x, y, board_config, is_mirrored);
+    Transform::from_xyz(xy.0 as f32, xy.1 as f32, z)
+}
+
+/// in world units, `CellPixelSize` zooms the camera to turn them into pixels;
+/// the board is centered on the origin, odd sizes included;
+/// rows at and above the visible height map above the board and are hidden by
+/// `vanish_zone_clip`;
+/// mirrored, column 0 is drawn on the right, only the drawing flips, collisions don't
+pub fn get_brick_pos_xy(x: i8, y: i8, board_config: &BoardConfig, is_mirrored: bool) -> (i32, i32) {
+    let x = if is_mirrored {
+        board_config.width - 1 - x
+    } else {
+        x
+    };
+    let half_grid = GRID_WIDTH as i32 / 2;
+    (
+        (x as i32 * 2 - board_config.width as i32) * half_grid + half_grid,
+        (y as i32 * 2 - board_config.height as i32) * half_grid + half_grid,
+    )
+}
+
+/// restart deals new pieces, a soft reset deals the ones of this game again; on game over
+/// both fade into the next game
+pub fn restart_input(
+    action_state: Res<ActionState>,
+    state: Res<State<AppState>>,
+    mut game_seed: ResMut<GameSeed>,
+    mut transition: ResMut<Transition>,
+    mut restart_pending: ResMut<RestartPending>,
+    mut reset_event_writer: EventWriter<ResetEvent>,
+) {
+    let is_soft_reset = action_state.just_pressed(Action::SoftReset);
+    if restart_pending.0 || (!is_soft_reset && !action_state.just_pressed(Action::Restart)) {
+        return;
+    }
+
+    if is_soft_reset {
+        game_seed.replay = Some(game_seed.current);
+    }
+    if *state.get() == AppState::Gaming {
+        reset_event_writer.send(ResetEvent);
+        return;
+    }
+
+    restart_pending.0 = true;
+    if is_soft_reset {
+        transition.start(AppState::Gaming, "soft reset");
+    } else {
+        transition.start(AppState::Gaming, "restart");
+    }
+}
+
+fn restart_pending_clear(mut restart_pending: ResMut<RestartPending>) {
+    restart_pending.0 = false;
+}
+
+/// whatever way the game ended, stop every gameplay timer and drop moves and locks still
+/// queued from the last frames, so nothing changes behind the game over screen, `restart`
+/// and the next spawn set them up fresh
+fn gameover_freeze(
+    mut lock_timer: ResMut<LockTimer>,
+    mut soft_drop_timer: ResMut<SoftDropTimer>,
+    mut gravity: ResMut<Gravity>,
+    mut clear_delay: ResMut<ClearDelay>,
+    mut new_pos_events: ResMut<Events<NewPosEvent>>,
+    mut stable_events: ResMut<Events<StableEvent>>,
+) {
+    lock_timer.timer.pause();
+    soft_drop_timer.0.pause();
+    clear_delay.timer.pause();
+    gravity.accumulated = 0.0;
+    new_pos_events.clear();
+    stable_events.clear();
+}
+
+/// runs hidden behind the transition, when the menu or GameOver switches to Gaming, or on a
+/// `ResetEvent`; moves and locks still queued belong to the bricks despawned here
+#[allow(clippy::too_many_arguments)]
+pub fn restart(
+    mut commands: Commands,
+    query_brick: Query<Entity, With<BrickPos>>,
+    mut new_pos_events: ResMut<Events<NewPosEvent>>,
+    mut stable_events: ResMut<Events<StableEvent>>,
+    mut event_writer_spawn: EventWriter<SpawnEvent>,
+    mut event_writer_restart: EventWriter<RestartEvent>,
+    mut gravity: ResMut<Gravity>,
+    mut garbage_queue: ResMut<GarbageQueue>,
+    mut clear_delay: ResMut<ClearDelay>,
+    mut piece_randomizer: ResMut<PieceRandomizer>,
+    mut game_rng: ResMut<GameRng>,
+    mut game_seed: ResMut<GameSeed>,
+    brick_texture: Res<BrickTexture>,
+    theme: Res<Theme>,
+    board_config: Res<BoardConfig>,
+    config: Res<GameConfig>,
+) {
+    for entity in query_brick.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    new_pos_events.clear();
+    stable_events.clear();
+
+    // every game has its own seed, so it can be played again with the same pieces
+    game_seed.current = game_seed.replay.take().unwrap_or_else(rand::random);
+    *game_rng = GameRng::from_seed(game_seed.current);
+
+    *gravity = Gravity::default();
+    *garbage_queue = GarbageQueue::default();
+    *clear_delay = ClearDelay::default();
+    *piece_randomizer = PieceRandomizer::new(config.randomizer, game_seed.current);
+
+    // the board picks it up with the restart event, before the first brick spawns
+    let mut gap_x = game_rng.0.gen_range(0..board_config.width);
+    // leaves the same room above the garbage on a short board
+    let rows_max = TRAINING_GARBAGE_MAX.min((board_config.height - BOARD_HEIGHT_MARGIN) as u8);
+    for y in 0..config.training_garbage_rows.min(rows_max) as i8 {
+        if config.training_garbage_gaps == GarbageGaps::Scattered {
+            gap_x = game_rng.0.gen_range(0..board_config.width);
+        }
+        spawn_garbage_row(
+            &mut commands,
+            &brick_texture,
+            &theme,
+            &board_config,
+            &config,
+            y,
+            gap_x,
+        );
+    }
+
+    event_writer_spawn.send(SpawnEvent::default());
+    event_writer_restart.send(RestartEvent);
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn brick_gen(
+    mut commands: Commands,
+    query_brick_stable: Query<&BrickPos, Without<BrickMoveable>>,
+    mut brick_state: ResMut<BrickState>,
+    mut game_over_event_writer: EventWriter<GameOverEvent>,
+    mut event_reader: EventReader<SpawnEvent>,
+    // grouped to stay within the parameter limit of a system
+    (brick_texture, theme): (Res<BrickTexture>, Res<Theme>),
+    board_config: Res<BoardConfig>,
+    mut lock_timer: ResMut<LockTimer>,
+    mut gravity: ResMut<Gravity>,
+    mut spawn_drop_guard: ResMut<SpawnDropGuard>,
+    config: Res<GameConfig>,
+    query_falling: Query<(), With<Falling>>,
+    clear_delay: Res<ClearDelay>,
+    mut piece_randomizer: ResMut<PieceRandomizer>,
+    mut game_rng: ResMut<GameRng>,
+    mut spawn_pending: Local<Option<SpawnEvent>>,
+) {
+    if let Some(spawn_event) = event_reader.iter().last() {
+        *spawn_pending = Some(SpawnEvent {
+            brick_type_index: spawn_event.brick_type_index,
+            from_hold: spawn_event.from_hold,
+        });
+    }
+
+    // the next brick waits out the clear delay and then the cleared lines falling, the
+    // two follow each other so neither is counted twice
+    if clear_delay.is_active() || !query_falling.is_empty() {
+        return;
+    }
+    let Some(spawn_event) = spawn_pending.take() else {
+        return;
+    };
+
+    let brick_type_arr = config.rotation_system.brick_type_arr();
+
+    let brick_type_idx = spawn_event
+        .brick_type_index
+        .unwrap_or_else(|| piece_randomizer.next());
+    piece_randomizer.fill(config.preview_count);
+    let brick_type = &brick_type_arr[brick_type_idx];
+
+    let brick_pos_stable_arr = query_brick_stable.iter().collect::<Vec<&BrickPos>>();
+
+    let get_brick_pos_spawn_arr = |brick_shape_idx: usize, y: i8| {
+        brick_type.brick_shape_arr[brick_shape_idx]
+            .brick_pos_arr
+            .iter()
+            .map(|pos| BrickPos::new(board_config.spawn_x() + pos.x, y + pos.y))
+            .collect::<Vec<BrickPos>>()
+    };
+
+    let mut spawn_y = board_config.spawn_y(config.rotation_system);
+
+    // drawn from the game rng so a seed replays the same orientations, one that doesn't
+    // fit where the brick spawns falls back to the usual orientation
+    let brick_shape_idx = if config.random_spawn_rotation {
+        let brick_shape_idx = game_rng.0.gen_range(0..brick_type.brick_shape_arr.len());
+        if is_legal(
+            &get_brick_pos_spawn_arr(brick_shape_idx, spawn_y),
+            &brick_pos_stable_arr,
+            &board_config,
+        ) {
+            brick_shape_idx
+        } else {
+            0
+        }
+    } else {
+        0
+    };
+
+    // spawn reaching into the vanish zone, then drop one row right away if there's room
+    if is_legal(
+        &get_brick_pos_spawn_arr(brick_shape_idx, spawn_y - 1),
+        &brick_pos_stable_arr,
+        &board_config,
+    ) {
+        spawn_y -= 1;
+    }
+    let brick_pos_spawn_arr = get_brick_pos_spawn_arr(brick_shape_idx, spawn_y);
+
+    brick_state.brick_type_index = brick_type_idx;
+    brick_state.brick_shape_index = brick_shape_idx;
+    brick_state.brick_pos_origin = BrickPos::new(board_config.spawn_x(), spawn_y);
+    *lock_timer = LockTimer::default();
+    lock_timer.timer = Timer::from_seconds(config.lock_delay, TimerMode::Once);
+    lock_timer.lowest_y = spawn_y;
+    gravity.accumulated = 0.0;
+    spawn_drop_guard.0.reset();
+
+    let is_game_over = brick_pos_spawn_arr
+        .iter()
+        .any(|brick_pos_spawn| brick_pos_stable_arr.contains(&brick_pos_spawn));
+
+    for brick_pos_spawn in brick_pos_spawn_arr {
+        if !is_game_over {
+            let mut bundle = brick_sprite_bundle(
+                &brick_texture,
+                theme.brick_arr[brick_type_idx],
+                BRICK_WIDTH as f32,
+                get_brick_pos(
+                    brick_pos_spawn.x,
+                    brick_pos_spawn.y,
+                    1.0,
+                    &board_config,
+                    config.is_mirrored(),
+                ),
+            );
+            let is_hold_swap = spawn_event.from_hold && config.hold_animation;
+            // starts small and clear on the first drawn frame, `spawning_update` grows it
+            if config.spawn_animation && !is_hold_swap {
+                bundle.0.transform.scale = Vec3::splat(SPAWN_ANIMATION_SCALE);
+                bundle.0.sprite.color.set_a(0.0);
+            }
+            let mut entity_commands = commands.spawn((
+                bundle,
+                brick_pos_spawn,
+                BrickKind(brick_type_idx),
+                BrickMoveable,
+            ));
+            if is_hold_swap {
+                entity_commands.insert(HoldSwapIn::default());
+            } else if config.spawn_animation {
+                entity_commands.insert(Spawning::default());
+            }
+            continue;
+        }
+
+        // the topped out brick is drawn over the stack, cells that collide in red
+        let color = if brick_pos_stable_arr.contains(&&brick_pos_spawn) {
+            theme.danger
+        } else {
+            theme.brick_arr[brick_type_idx]
+        };
+        commands.spawn((
+            brick_sprite_bundle(
+                &brick_texture,
+                color,
+                BRICK_WIDTH as f32,
+                get_brick_pos(
+                    brick_pos_spawn.x,
+                    brick_pos_spawn.y,
+                    2.0,
+                    &board_config,
+                    config.is_mirrored(),
+                ),
+            ),
+            brick_pos_spawn,
+            BrickKind(brick_type_idx),
+            BrickMoveable,
+            ToppedOut,
+        ));
+    }
+
+    if is_game_over {
+        game_over_event_writer.send(GameOverEvent);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn input(
+    query_brick_movable: Query<&mut BrickPos, With<BrickMoveable>>,
+    query_brick_stable: Query<&BrickPos, Without<BrickMoveable>>,
+    action_state: Res<ActionState>,
+    time: Res<Time>,
+    mut brick_state: ResMut<BrickState>,
+    mut soft_drop_timer: ResMut<SoftDropTimer>,
+    lock_timer: Res<LockTimer>,
+    mut spawn_drop_guard: ResMut<SpawnDropGuard>,
+    mut auto_repeat: ResMut<AutoRepeat>,
+    mut rotate_repeat: ResMut<RotateRepeat>,
+    board_config: Res<BoardConfig>,
+    config: Res<GameConfig>,
+    mut event_writer_stable: EventWriter<StableEvent>,
+    mut event_writer_move: EventWriter<NewPosEvent>,
+    mut event_writer_soft_drop: EventWriter<SoftDropEvent>,
+) {
+    if query_brick_movable.is_empty() {
+        return;
+    }
+
+    let brick_move_arr = query_brick_movable.iter().collect::<Vec<&BrickPos>>();
+    let brick_stable_arr = query_brick_stable.iter().collect::<Vec<&BrickPos>>();
+    let mut brick_pos_move = BrickPos::default();
+
+    // shift, trying each kick of the rotation system in order, a repeated rotation sends a
+    // move event like a press, so it spends the same lock reset budget
+    let rotate = rotate_repeat.update(&action_state, &config, time.delta());
+    if rotate != 0 {
+        let is_rotate_cw = rotate > 0;
+        let rotation_system = config.rotation_system;
+        // no move event, so a quad spin doesn't buy a lock reset
+        if rotation_system.pivot(brick_state.brick_type_index) == Pivot::Fixed {
+            return;
+        }
+        let brick_shape_arr =
+            &rotation_system.brick_type_arr()[brick_state.brick_type_index].brick_shape_arr;
+        let shape_count = brick_shape_arr.len();
+        let (brick_shape_idx_new, kick_arr) = if is_rotate_cw {
+            (
+                (brick_state.brick_shape_index + 1) % shape_count,
+                rotation_system
+                    .kick_arr(brick_state.brick_type_index, brick_state.brick_shape_index)
+                    .to_vec(),
+            )
+        } else {
+            let brick_shape_idx_new =
+                (brick_state.brick_shape_index + shape_count - 1) % shape_count;
+            (
+                brick_shape_idx_new,
+                rotation_system
+                    .kick_ccw_arr(brick_state.brick_type_index, brick_shape_idx_new)
+                    .collect(),
+            )
+        };
+        let brick_shape = &brick_shape_arr[brick_shape_idx_new];
+        // the first kick of every table is the rotation in place
+        let kick_count = if config.strict_rotation {
+            1
+        } else {
+            kick_arr.len()
+        };
+
+        for &kick in kick_arr.iter().take(kick_count) {
+            let brick_pos_origin_new = brick_state.brick_pos_origin + kick;
+            let brick_pos_new_arr = brick_shape
+                .brick_pos_arr
+                .iter()
+                .map(|&pos| pos + brick_pos_origin_new)
+                .collect::<Vec<BrickPos>>();
+            if !is_legal(&brick_pos_new_arr, &brick_stable_arr, &board_config) {
+                continue;
+            }
+
+            brick_state.brick_shape_index = brick_shape_idx_new;
+            brick_state.brick_pos_origin = brick_pos_origin_new;
+            event_writer_move.send(NewPosEvent(brick_pos_new_arr.try_into().unwrap()));
+            return;
+        }
+        return;
+    }
+
+    // soft drop, moves once on press then repeats while held, but a key still held from
+    // the last brick waits out the spawn guard; hard and sonic drop need a fresh press
+    // unless `hard_drop_auto_repeat` is on
+    let is_spawn_guarded = !spawn_drop_guard.0.tick(time.delta()).finished();
+    let soft_drop_press = action_state.just_pressed(Action::SoftDrop);
+    let soft_drop_repeat = !soft_drop_press
+        && !is_spawn_guarded
+        && action_state.pressed(Action::SoftDrop)
+        && soft_drop_timer.0.tick(time.delta()).just_finished();
+    if soft_drop_press {
+        soft_drop_timer.0 = Timer::from_seconds(1.0 / config.soft_drop_speed, TimerMode::Repeating);
+    }
+
+    let auto_repeat_move = auto_repeat.update(&action_state, &config, time.delta());
+    let mut is_hard_drop = false;
+    // a drop key held from the last brick drops the new one once the spawn guard passed
+    let is_drop_repeat = |action: Action| {
+        config.hard_drop_auto_repeat
+            && !is_spawn_guarded
+            && action_state.pressed(action)
+            && !action_state.just_pressed(action)
+    };
+
+    // rows the brick can fall, for the hard and the sonic drop
+    let drop_distance = || {
+        let mut max_down = 0;
+        loop {
+            let down = max_down + 1;
+            let brick_pos_move = BrickPos::new(0, -down);
+            let brick_pos_new_arr = brick_move_arr
+                .iter()
+                .map(|&&pos| pos + brick_pos_move)
+                .collect::<Vec<BrickPos>>();
+            if !is_legal(&brick_pos_new_arr, &brick_stable_arr, &board_config) {
+                return max_down;
+            }
+            max_down = down;
+        }
+    };
+
+    // move
+    if soft_drop_press || soft_drop_repeat {
+        brick_pos_move.y = -1;
+    } else if auto_repeat_move != 0 {
+        brick_pos_move.x = auto_repeat_move;
+    } else if action_state.just_pressed(Action::HardDrop)
+        // a repeat of a brick that is already down would lock it on the spot
+        || (is_drop_repeat(Action::HardDrop) && drop_distance() > 0)
+    {
+        brick_pos_move.y = -drop_distance();
+        is_hard_drop = true;
+    } else if config.sonic_drop
+        && (action_state.just_pressed(Action::SonicDrop) || is_drop_repeat(Action::SonicDrop))
+    {
+        // down to the floor without locking, so the brick can still slide, a brick already
+        // there doesn't move and so doesn't get a lock reset out of it
+        let max_down = drop_distance();
+        if max_down == 0 {
+            return;
+        }
+        brick_pos_move.y = -max_down;
+    } else {
+        return;
+    }
+
+    let brick_pos_new_arr = brick_move_arr
+        .iter()
+        .map(|&&pos| pos + brick_pos_move)
+        .collect::<Vec<BrickPos>>();
+
+    if !is_legal(&brick_pos_new_arr, &brick_stable_arr, &board_config) {
+        // force down when can't move, stable all brick, once it rested a moment, otherwise
+        // the lock delay decides like for any resting brick
+        let is_soft_drop = soft_drop_press || soft_drop_repeat;
+        if is_soft_drop
+            && config.soft_drop_locks_immediately
+            && lock_timer.timer.elapsed_secs() >= SOFT_DROP_LOCK_GUARD
+        {
+            event_writer_stable.send(StableEvent);
+        }
+        return;
+    }
+
+    brick_state.brick_pos_origin += brick_pos_move;
+
+    if soft_drop_press || soft_drop_repeat {
+        event_writer_soft_drop.send(SoftDropEvent(1));
+    }
+
+    event_writer_move.send(NewPosEvent(brick_pos_new_arr.try_into().unwrap()));
+
+    // the move is applied before `brick_stable`, so the brick locks where it landed and
+    // scores like any other lock
+    if is_hard_drop && config.hard_drop_locks {
+        event_writer_stable.send(StableEvent);
+    }
+}
+
+fn brick_auto_fall(
+    mut query_brick_movable: Query<&mut BrickPos, With<BrickMoveable>>,
+    query_brick_stable: Query<&BrickPos, Without<BrickMoveable>>,
+    mut gravity: ResMut<Gravity>,
+    time: Res<Time>,
+    mut brick_state: ResMut<BrickState>,
+    board_config: Res<BoardConfig>,
+    mut event_writer_new_pos: EventWriter<NewPosEvent>,
+) {
+    if query_brick_movable.is_empty() {
+        return;
+    }
+
+    gravity.accumulated += gravity.cells_per_frame * time.delta_seconds() * GRAVITY_FPS;
+    let cells = gravity.accumulated.floor() as i8;
+    if cells == 0 {
+        return;
+    }
+    gravity.accumulated -= cells as f32;
+
+    let brick_move_arr = query_brick_movable
+        .iter()
+        .copied()
+        .collect::<Vec<BrickPos>>();
+    let brick_stable_arr = query_brick_stable.iter().collect::<Vec<&BrickPos>>();
+
+    // step one cell at a time so high gravity never skips over the stack
+    let mut max_down = 0;
+    while max_down < cells {
+        let brick_pos_move = BrickPos::new(0, -(max_down + 1));
+        let brick_pos_new_arr = brick_move_arr
+            .iter()
+            .map(|&pos| pos + brick_pos_move)
+            .collect::<Vec<BrickPos>>();
+        if !is_legal(&brick_pos_new_arr, &brick_stable_arr, &board_config) {
+            break;
+        }
+        max_down += 1;
+    }
+
+    // resting on something, brick_lock decides when it locks
+    if max_down == 0 {
+        gravity.accumulated = 0.0;
+        return;
+    }
+
+    let brick_pos_move = BrickPos::new(0, -max_down);
+    let brick_pos_new_arr = brick_move_arr
+        .iter()
+        .map(|&pos| pos + brick_pos_move)
+        .collect::<Vec<BrickPos>>();
+
+    brick_state.brick_pos_origin += brick_pos_move;
+
+    // the cells move right away, so `input` moves on from where the brick fell to in the same
+    // frame and the origin stays on the cells, the event only updates the transforms
+    for (mut brick_pos, &brick_pos_new) in query_brick_movable.iter_mut().zip(&brick_pos_new_arr) {
+        *brick_pos = brick_pos_new;
+    }
+
+    event_writer_new_pos.send(NewPosEvent(brick_pos_new_arr.try_into().unwrap()));
+}
+
+pub fn brick_apply_new_pos(
+    mut query_brick_movable: Query<(&mut Transform, &mut BrickPos), With<BrickMoveable>>,
+    mut shift_event: EventReader<NewPosEvent>,
+    board_config: Res<BoardConfig>,
+    config: Res<GameConfig>,
+) {
+    if query_brick_movable.is_empty() || shift_event.is_empty() {
+        return;
+    }
+
+    for shift_event in shift_event.iter() {
+        let brick_pos_new_arr = shift_event.0;
+
+        for (idx, (mut transform, mut brick_pos)) in query_brick_movable.iter_mut().enumerate() {
+            brick_pos.x = brick_pos_new_arr[idx].x;
+            brick_pos.y = brick_pos_new_arr[idx].y;
+
+            let xy = get_brick_pos_xy(
+                brick_pos.x,
+                brick_pos.y,
+                &board_config,
+                config.is_mirrored(),
+            );
+
+            transform.translation.x = xy.0 as f32;
+            transform.translation.y = xy.1 as f32;
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn brick_lock(
+    query_brick_movable: Query<&BrickPos, With<BrickMoveable>>,
+    query_brick_stable: Query<&BrickPos, Without<BrickMoveable>>,
+    brick_state: Res<BrickState>,
+    action_state: Res<ActionState>,
+    board_config: Res<BoardConfig>,
+    config: Res<GameConfig>,
+    time: Res<Time>,
+    mut lock_timer: ResMut<LockTimer>,
+    mut new_pos_event_reader: EventReader<NewPosEvent>,
+    mut stable_event_writer: EventWriter<StableEvent>,
+) {
+    if query_brick_movable.is_empty() {
+        return;
+    }
+
+    if !new_pos_event_reader.is_empty() {
+        new_pos_event_reader.clear();
+
+        if brick_state.brick_pos_origin.y < lock_timer.lowest_y {
+            // reaching a new lowest row always resets, and restores the reset budget
+            lock_timer.lowest_y = brick_state.brick_pos_origin.y;
+            lock_timer.resets = 0;
+            lock_timer.timer.reset();
+        } else if config.lock_behavior == LockBehavior::MoveReset
+            && lock_timer.resets < LOCK_RESET_MAX
+        {
+            lock_timer.resets += 1;
+            lock_timer.timer.reset();
+        }
+    }
+
+    let brick_stable_arr = query_brick_stable.iter().collect::<Vec<&BrickPos>>();
+    let brick_pos_down_arr = query_brick_movable
+        .iter()
+        .map(|&pos| pos + BrickPos::new(0, -1))
+        .collect::<Vec<BrickPos>>();
+    // able to fall again, a move off a ledge cancels the resting state
+    lock_timer.resting = !is_legal(&brick_pos_down_arr, &brick_stable_arr, &board_config);
+    if !lock_timer.resting {
+        return;
+    }
+
+    lock_timer.timer.tick(time.delta());
+    let is_manual_lock = config.soft_lock && action_state.just_pressed(Action::Lock);
+    if lock_timer.timer.finished() || is_manual_lock {
+        stable_event_writer.send(StableEvent);
+    }
+}
+
+/// rising mode sends a garbage row on every tick of its timer
+fn garbage_rising(
+    config: Res<GameConfig>,
+    time: Res<Time>,
+    mut garbage_queue: ResMut<GarbageQueue>,
+    mut garbage_event_writer: EventWriter<GarbageEvent>,
+) {
+    if config.mode != GameMode::Rising {
+        return;
+    }
+
+    let interval = Duration::from_secs_f32(config.rising_garbage_interval);
+    if garbage_queue.rising_timer.duration() != interval {
+        garbage_queue.rising_timer.set_duration(interval);
+    }
+    if garbage_queue
+        .rising_timer
+        .tick(time.delta())
+        .just_finished()
+    {
+        garbage_event_writer.send(GarbageEvent(1));
+    }
+}
+
+/// dim the moveable brick while it rests with soft lock, back to its color once it falls
+fn resting_update(
+    mut query_brick_movable: Query<(&mut Sprite, &BrickKind), With<BrickMoveable>>,
+    lock_timer: Res<LockTimer>,
+    config: Res<GameConfig>,
+    theme: Res<Theme>,
+) {
+    let factor = if config.soft_lock && lock_timer.resting {
+        RESTING_DIM
+    } else {
+        1.0
+    };
+
+    for (mut sprite, brick_kind) in query_brick_movable.iter_mut() {
+        let color = theme.brick_arr[brick_kind.0];
+        sprite.color = Color::rgba(
+            color.r() * factor,
+            color.g() * factor,
+            color.b() * factor,
+            sprite.color.a(),
+        );
+    }
+}
+
+/// a full row of garbage but for the gap, returns the cells it filled
+fn spawn_garbage_row(
+    commands: &mut Commands,
+    brick_texture: &BrickTexture,
+    theme: &Theme,
+    board_config: &BoardConfig,
+    config: &GameConfig,
+    y: i8,
+    gap_x: i8,
+) -> Vec<BrickPos> {
+    let mut brick_pos_arr = vec![];
+    for x in (0..board_config.width).filter(|&x| x != gap_x) {
+        let brick_pos = BrickPos::new(x, y);
+        brick_pos_arr.push(brick_pos);
+        commands.spawn((
+            brick_sprite_bundle(
+                brick_texture,
+                theme.garbage,
+                BRICK_WIDTH as f32,
+                get_brick_pos(x, y, 1.0, board_config, config.is_mirrored()),
+            ),
+            brick_pos,
+            GarbageBrick,
+        ));
+    }
+    brick_pos_arr
+}
+
+/// pick the gaps of incoming garbage now, so the preview shows where they will be
+fn garbage_queue(
+    mut garbage_queue: ResMut<GarbageQueue>,
+    mut game_rng: ResMut<GameRng>,
+    board_config: Res<BoardConfig>,
+    mut garbage_event_reader: EventReader<GarbageEvent>,
+) {
+    let rows = garbage_event_reader.iter().map(|e| e.0).sum::<u8>();
+    if rows == 0 {
+        return;
+    }
+
+    if garbage_queue.gap_x_arr.is_empty() {
+        garbage_queue.timer.reset();
+    }
+    for _ in 0..rows {
+        let gap_x = game_rng.0.gen_range(0..board_config.width);
+        garbage_queue.gap_x_arr.push(gap_x);
+    }
+}
+
+/// show the top incoming row under the board, gap included
+fn garbage_preview_update(
+    mut commands: Commands,
+    query_preview: Query<Entity, With<GarbagePreview>>,
+    garbage_queue: Res<GarbageQueue>,
+    theme: Res<Theme>,
+    board_config: Res<BoardConfig>,
+    config: Res<GameConfig>,
+) {
+    if !garbage_queue.is_changed()
+        && !theme.is_changed()
+        && !board_config.is_changed()
+        && !config.is_changed()
+    {
+        return;
+    }
+
+    for entity in query_preview.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let Some(&gap_x) = garbage_queue.gap_x_arr.first() else {
+        return;
+    };
+    let y = -(board_config.height as f32 * GRID_WIDTH as f32) / 2.0
+        - BOARD_BORDER as f32
+        - GRID_PADDING as f32
+        - GARBAGE_PREVIEW_HEIGHT / 2.0;
+    for x in (0..board_config.width).filter(|&x| x != gap_x) {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: theme.garbage,
+                    custom_size: Some(Vec2::new(BRICK_WIDTH as f32, GARBAGE_PREVIEW_HEIGHT)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(
+                    get_brick_pos_xy(x, 0, &board_config, config.is_mirrored()).0 as f32,
+                    y,
+                    1.0,
+                ),
+                ..default()
+            },
+            GarbagePreview,
+            OnBoard,
+        ));
+    }
+}
+
+/// once the telegraph ran out, push the stack up by the queued rows
+#[allow(clippy::too_many_arguments)]
+fn brick_garbage_rise(
+    mut commands: Commands,
+    mut query_brick_stable: Query<(&mut Transform, &mut BrickPos), Without<BrickMoveable>>,
+    mut query_brick_movable: Query<(&mut Transform, &mut BrickPos), With<BrickMoveable>>,
+    mut brick_state: ResMut<BrickState>,
+    mut transition: ResMut<Transition>,
+    mut garbage_queue: ResMut<GarbageQueue>,
+    mut garbage_rise_event_writer: EventWriter<GarbageRiseEvent>,
+    time: Res<Time>,
+    brick_texture: Res<BrickTexture>,
+    theme: Res<Theme>,
+    clear_delay: Res<ClearDelay>,
+    board_config: Res<BoardConfig>,
+    config: Res<GameConfig>,
+) {
+    // rows waiting to be cleared keep their place until they are gone
+    if garbage_queue.gap_x_arr.is_empty() || clear_delay.is_active() {
+        return;
+    }
+    if !garbage_queue.timer.tick(time.delta()).finished() {
+        return;
+    }
+
+    // the first queued row ends up on top
+    let gap_x_arr = std::mem::take(&mut garbage_queue.gap_x_arr);
+    let rows = gap_x_arr.len() as i8;
+
+    // push the stack up
+    let mut is_game_over = false;
+    for (mut transform, mut brick_pos) in query_brick_stable.iter_mut() {
+        brick_pos.y += rows;
+        if brick_pos.y >= board_config.total_height() {
+            is_game_over = true;
+        }
+
+        let xy = get_brick_pos_xy(
+            brick_pos.x,
+            brick_pos.y,
+            &board_config,
+            config.is_mirrored(),
+        );
+        transform.translation.x = xy.0 as f32;
+        transform.translation.y = xy.1 as f32;
     }
 
-    spawn_event_writer.send(SpawnEvent);
-    full_line_check_event_writer.send(FullLineCheckEvent);
+    // fill the bottom rows, leaving the queued gap in each
+    let mut brick_pos_garbage_arr = vec![];
+    for y in 0..rows {
+        let gap_x = gap_x_arr[(rows - 1 - y) as usize];
+        brick_pos_garbage_arr.extend(spawn_garbage_row(
+            &mut commands,
+            &brick_texture,
+            &theme,
+            &board_config,
+            &config,
+            y,
+            gap_x,
+        ));
+    }
+
+    // lift the moveable brick just enough to stay clear of the stack
+    let brick_pos_stable_arr = query_brick_stable
+        .iter()
+        .map(|(_, pos)| *pos)
+        .chain(brick_pos_garbage_arr)
+        .collect::<Vec<BrickPos>>();
+    let mut lift = 0;
+    while query_brick_movable
+        .iter()
+        .any(|(_, pos)| brick_pos_stable_arr.contains(&(*pos + BrickPos::new(0, lift))))
+    {
+        lift += 1;
+    }
+
+    if lift > 0 {
+        brick_state.brick_pos_origin.y += lift;
+        for (mut transform, mut brick_pos) in query_brick_movable.iter_mut() {
+            brick_pos.y += lift;
+            if brick_pos.y >= board_config.total_height() {
+                is_game_over = true;
+            }
+
+            let xy = get_brick_pos_xy(
+                brick_pos.x,
+                brick_pos.y,
+                &board_config,
+                config.is_mirrored(),
+            );
+            transform.translation.x = xy.0 as f32;
+            transform.translation.y = xy.1 as f32;
+        }
+    }
+
+    garbage_rise_event_writer.send(GarbageRiseEvent(rows as u8));
+
+    if is_game_over {
+        transition.start(AppState::GameOver, "garbage top-out");
+    }
 }
 
-fn brick_fullline_clear(
+#[allow(clippy::too_many_arguments)]
+fn brick_hold(
     mut commands: Commands,
-    mut query_brick_stable: Query<(Entity, &mut Transform, &mut BrickPos), Without<BrickMoveable>>,
-    mut full_line_check_event_reader: EventReader<FullLineCheckEvent>,
-    mut full_line_remove_event_writer: EventWriter<FullLineRemoveEvent>,
+    query_movable: Query<(Entity, &Transform), With<BrickMoveable>>,
+    brick_state: Res<BrickState>,
+    action_state: Res<ActionState>,
+    config: Res<GameConfig>,
+    mut hold: ResMut<Hold>,
+    mut stable_event_reader: EventReader<StableEvent>,
+    mut spawn_event_writer: EventWriter<SpawnEvent>,
 ) {
-    if full_line_check_event_reader.is_empty() || query_brick_stable.is_empty() {
+    // a lock gives back the hold, and the locking brick can't be held anymore
+    if !stable_event_reader.is_empty() {
+        stable_event_reader.clear();
+        hold.can_hold = true;
         return;
     }
-    full_line_check_event_reader.clear();
 
-    let brick_stable_arr = query_brick_stable
-        .iter()
-        .map(|(_, _, pos)| pos)
-        .collect::<Vec<&BrickPos>>();
+    if !action_state.just_pressed(Action::Hold)
+        || !config.hold_enabled
+        || !hold.can_hold
+        || query_movable.is_empty()
+    {
+        return;
+    }
 
-    // get all y to remove
-    let mut y_to_remove = vec![];
-    for y in 0..BOARD_HEIGHT {
-        let mut is_full_line = true;
-        for x in 0..BOARD_WIDTH {
-            let brick_pos_tmp = BrickPos::new(x, y);
-            if !brick_stable_arr.contains(&&brick_pos_tmp) {
-                is_full_line = false;
-                break;
-            }
+    for (entity, transform) in query_movable.iter() {
+        if !config.hold_animation {
+            commands.entity(entity).despawn_recursive();
+            continue;
         }
+        // off the board at once, only the sprite is left to fly into the panel
+        commands
+            .entity(entity)
+            .remove::<(BrickMoveable, BrickPos, BrickKind, Spawning)>()
+            .insert(HoldSwapOut::new(transform.translation));
+    }
+
+    spawn_event_writer.send(SpawnEvent {
+        brick_type_index: hold.brick_type_index,
+        from_hold: true,
+    });
+    hold.brick_type_index = Some(brick_state.brick_type_index);
+    hold.can_hold = false;
+}
+
+#[allow(clippy::too_many_arguments)]
+fn brick_stable(
+    mut commands: Commands,
+    query_movable: Query<(Entity, &BrickPos), With<BrickMoveable>>,
+    board_config: Res<BoardConfig>,
+    config: Res<GameConfig>,
+    mut clear_delay: ResMut<ClearDelay>,
+    mut transition: ResMut<Transition>,
+    mut stable_event_reader: EventReader<StableEvent>,
+    mut spawn_event_writer: EventWriter<SpawnEvent>,
+    mut full_line_check_event_writer: EventWriter<FullLineCheckEvent>,
+) {
+    if stable_event_reader.is_empty() {
+        return;
+    }
+    stable_event_reader.clear();
+
+    // a lock out is not a blocked spawn, the top out grace doesn't forgive it
+    let is_lock_out = !query_movable.is_empty()
+        && query_movable
+            .iter()
+            .all(|(_, brick_pos)| brick_pos.y >= board_config.topout_row());
 
-        if is_full_line {
-            y_to_remove.push(y);
+    for (entity, _) in query_movable.iter() {
+        commands.entity(entity).remove::<BrickMoveable>();
+        if config.lock_flash {
+            commands.entity(entity).insert(LockFlash::default());
         }
     }
 
-    if y_to_remove.len() == 0 {
-        return;
+    if is_lock_out {
+        transition.start(AppState::GameOver, "lock-out");
+    } else {
+        spawn_event_writer.send(SpawnEvent::default());
     }
+    full_line_check_event_writer.send(FullLineCheckEvent);
+    clear_delay.is_check_pending = true;
+}
 
-    // remove all y line
-    for (entity, _, brick_pos) in query_brick_stable.iter() {
-        if y_to_remove.contains(&brick_pos.y) {
-            commands.entity(entity).despawn();
+fn falling_update(
+    mut commands: Commands,
+    mut query_falling: Query<(Entity, &mut Falling, &mut Transform, &BrickPos)>,
+    time: Res<Time>,
+    board_config: Res<BoardConfig>,
+    config: Res<GameConfig>,
+) {
+    for (entity, mut falling, mut transform, brick_pos) in query_falling.iter_mut() {
+        falling.timer.tick(time.delta());
+
+        // the target comes from the position each frame, so garbage rising meanwhile is kept
+        let to_y = get_brick_pos_xy(
+            brick_pos.x,
+            brick_pos.y,
+            &board_config,
+            config.is_mirrored(),
+        )
+        .1 as f32;
+        let t = falling.timer.percent();
+        transform.translation.y = falling.from_y + (to_y - falling.from_y) * t * t;
+
+        if falling.timer.finished() {
+            commands.entity(entity).remove::<Falling>();
         }
     }
+}
 
-    // get all new brick_pos for left brick_pos
-    let mut left_brick_pos_new_pos_map = HashMap::new();
-    let mut target_y = 0_i8;
-    for y in 0..BOARD_HEIGHT {
-        if y_to_remove.contains(&y) {
-            continue;
+/// hide the cells in the vanish zone, whatever moved them there
+#[allow(clippy::type_complexity)]
+fn vanish_zone_clip(
+    mut query_brick: Query<(&BrickPos, &mut Visibility), (Changed<BrickPos>, Without<ToppedOut>)>,
+    board_config: Res<BoardConfig>,
+) {
+    for (brick_pos, mut visibility) in query_brick.iter_mut() {
+        *visibility = if brick_pos.y >= board_config.height {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        };
+    }
+}
+
+fn lock_flash_update(
+    mut commands: Commands,
+    mut query_flash: Query<(Entity, &mut LockFlash, &mut Sprite, &BrickKind)>,
+    theme: Res<Theme>,
+    time: Res<Time>,
+) {
+    for (entity, mut lock_flash, mut sprite, brick_kind) in query_flash.iter_mut() {
+        lock_flash.timer.tick(time.delta());
+
+        let t = lock_flash.timer.percent_left();
+        let from = theme.brick_arr[brick_kind.0];
+        let to = Color::WHITE;
+        sprite.color = Color::rgba(
+            from.r() + (to.r() - from.r()) * t,
+            from.g() + (to.g() - from.g()) * t,
+            from.b() + (to.b() - from.b()) * t,
+            sprite.color.a(),
+        );
+
+        if lock_flash.timer.finished() {
+            commands.entity(entity).remove::<LockFlash>();
         }
+    }
+}
 
-        let mut pos_assigned = false; // if new pos assigned in this target_y, target_y ++
-        for x in 0..BOARD_WIDTH {
-            let brick_pos_tmp = BrickPos::new(x, y);
-            if brick_stable_arr.contains(&&brick_pos_tmp) {
-                let brick_pos_new = BrickPos::new(x, target_y);
-                left_brick_pos_new_pos_map.insert(brick_pos_tmp, brick_pos_new);
-                pos_assigned = true;
+/// only the sprite animates, a brick hard dropped meanwhile keeps growing where it landed
+fn spawning_update(
+    mut commands: Commands,
+    mut query_spawning: Query<(Entity, &mut Spawning, &mut Transform, &mut Sprite)>,
+    time: Res<Time>,
+) {
+    for (entity, mut spawning, mut transform, mut sprite) in query_spawning.iter_mut() {
+        spawning.timer.tick(time.delta());
+
+        let t = spawning.timer.percent();
+        transform.scale = Vec3::splat(SPAWN_ANIMATION_SCALE + (1.0 - SPAWN_ANIMATION_SCALE) * t);
+        sprite.color.set_a(t);
+
+        if spawning.timer.finished() {
+            commands.entity(entity).remove::<Spawning>();
+        }
+    }
+}
+
+/// full lines stay on the board for `clear_delay`, the next spawn waits for them
+#[allow(clippy::too_many_arguments)]
+fn brick_fullline_clear(
+    mut commands: Commands,
+    mut query_brick_stable: Query<(Entity, &Transform, &mut BrickPos), Without<BrickMoveable>>,
+    mut full_line_check_event_reader: EventReader<FullLineCheckEvent>,
+    mut full_line_remove_event_writer: EventWriter<FullLineRemoveEvent>,
+    mut piece_lock_event_writer: EventWriter<PieceLockEvent>,
+    mut clear_delay: ResMut<ClearDelay>,
+    board_config: Res<BoardConfig>,
+    config: Res<GameConfig>,
+    time: Res<Time>,
+) {
+    let is_check = !full_line_check_event_reader.is_empty();
+    full_line_check_event_reader.clear();
+    if is_check {
+        clear_delay.is_check_pending = false;
+    }
+
+    if is_check && !query_brick_stable.is_empty() {
+        let brick_stable_arr = query_brick_stable
+            .iter()
+            .map(|(_, _, pos)| pos)
+            .collect::<Vec<&BrickPos>>();
+
+        // get all y to remove
+        let mut y_to_remove = vec![];
+        for y in 0..board_config.total_height() {
+            let mut is_full_line = true;
+            for x in 0..board_config.width {
+                let brick_pos_tmp = BrickPos::new(x, y);
+                if !brick_stable_arr.contains(&&brick_pos_tmp) {
+                    is_full_line = false;
+                    break;
+                }
+            }
+
+            // the stack trainer leaves full lines where they are, so the lock counts as
+            // clearing nothing
+            if is_full_line && config.mode.clears_lines() {
+                y_to_remove.push(y);
             }
         }
 
-        if pos_assigned {
-            target_y += 1;
+        piece_lock_event_writer.send(PieceLockEvent {
+            lines: y_to_remove.len() as u8,
+        });
+
+        if y_to_remove.len() == 0 {
+            return;
         }
+
+        clear_delay.y_arr = y_to_remove;
+        clear_delay.timer = Timer::from_seconds(config.clear_delay, TimerMode::Once);
     }
 
-    // move left brick pos to new pos
-    for (_, mut transform, mut brick_pos) in query_brick_stable.iter_mut() {
-        let brick_pos = brick_pos.as_mut();
-        if left_brick_pos_new_pos_map.contains_key(brick_pos) {
-            brick_pos.x = left_brick_pos_new_pos_map[brick_pos].x;
-            brick_pos.y = left_brick_pos_new_pos_map[brick_pos].y;
+    if !clear_delay.is_active() || !clear_delay.timer.tick(time.delta()).finished() {
+        return;
+    }
+    let y_to_remove = std::mem::take(&mut clear_delay.y_arr);
 
-            let xy = get_brick_pos_xy(brick_pos.x, brick_pos.y);
+    // every row left drops by the number of removed rows under it, the same as
+    // `Board::clear_full_lines`, an empty row in between is kept rather than squeezed out.
+    // the entities are moved, not respawned, so they keep their kind and color, the sprites
+    // follow in `falling_update`
+    for (entity, transform, mut brick_pos) in query_brick_stable.iter_mut() {
+        if y_to_remove.contains(&brick_pos.y) {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
 
-            transform.translation.x = xy.0 as f32;
-            transform.translation.y = xy.1 as f32;
+        let drop = y_to_remove.iter().filter(|&&y| y < brick_pos.y).count() as i8;
+        if drop == 0 {
+            continue;
         }
+        brick_pos.y -= drop;
+
+        commands.entity(entity).insert(Falling {
+            from_y: transform.translation.y,
+            timer: Timer::from_seconds(LINE_CLEAR_FALL_DURATION, TimerMode::Once),
+        });
     }
 
     full_line_remove_event_writer.send(FullLineRemoveEvent(y_to_remove.len() as u8));
 }
 
-fn is_legal(brick_pos_arr_new: &Vec<BrickPos>, brick_stable_arr: &Vec<&BrickPos>) -> bool {
+fn is_legal(
+    brick_pos_arr_new: &Vec<BrickPos>,
+    brick_stable_arr: &Vec<&BrickPos>,
+    board_config: &BoardConfig,
+) -> bool {
     for brick_pos in brick_pos_arr_new {
         if brick_pos.x < 0
-            || brick_pos.x >= BOARD_WIDTH
+            || brick_pos.x >= board_config.width
             || brick_pos.y < 0
-            || brick_pos.y >= BOARD_HEIGHT
+            || brick_pos.y >= board_config.total_height()
             || brick_stable_arr.contains(&&brick_pos)
         {
             return false;
@@ -568,52 +2288,842 @@ fn is_legal(brick_pos_arr_new: &Vec<BrickPos>, brick_stable_arr: &Vec<&BrickPos>
     return true;
 }
 
-lazy_static! {
-    pub static ref BRICK_TYPE_ARRAY: Vec<BrickType> = vec![
-        // quard
-        BrickType::new(vec![BrickShape::new([BrickPos::new(1, 0), BrickPos::new(1, 1), BrickPos::new(2, 0), BrickPos::new(2, 1)])]),
-        // line
-        BrickType::new(vec![
-           BrickShape::new([BrickPos::new(0, 1), BrickPos::new(1, 1), BrickPos::new(2, 1), BrickPos::new(3, 1)]),
-           BrickShape::new([BrickPos::new(2, 0), BrickPos::new(2, 1), BrickPos::new(2, 2), BrickPos::new(2, 3)]),
-        ]),
-
-        // J
-        BrickType::new(vec![
-           BrickShape::new([BrickPos::new(0, 1), BrickPos::new(1, 1), BrickPos::new(2, 1), BrickPos::new(2, 0)]),
-           BrickShape::new([BrickPos::new(1, 0), BrickPos::new(1, 1), BrickPos::new(1, 2), BrickPos::new(0, 0)]),
-           BrickShape::new([BrickPos::new(0, 1), BrickPos::new(1, 1), BrickPos::new(2, 1), BrickPos::new(0, 2)]),
-           BrickShape::new([BrickPos::new(1, 0), BrickPos::new(1, 1), BrickPos::new(1, 2), BrickPos::new(2, 2)]),
-        ]),
-
-        // L
-        BrickType::new(vec![
-           BrickShape::new([BrickPos::new(0, 1), BrickPos::new(1, 1), BrickPos::new(2, 1), BrickPos::new(0, 0)]),
-           BrickShape::new([BrickPos::new(1, 0), BrickPos::new(1, 1), BrickPos::new(1, 2), BrickPos::new(0, 2)]),
-           BrickShape::new([BrickPos::new(0, 1), BrickPos::new(1, 1), BrickPos::new(2, 1), BrickPos::new(2, 2)]),
-           BrickShape::new([BrickPos::new(1, 0), BrickPos::new(1, 1), BrickPos::new(1, 2), BrickPos::new(2, 0)]),
-        ]),
-
-        // S
-        BrickType::new(vec![
-           BrickShape::new([BrickPos::new(0, 0), BrickPos::new(1, 0), BrickPos::new(1, 1), BrickPos::new(2, 1)]),
-           BrickShape::new([BrickPos::new(1, 2), BrickPos::new(1, 1), BrickPos::new(2, 1), BrickPos::new(2, 0)]),
-        ]),
-
-        // Z
-        BrickType::new(vec![
-           BrickShape::new([BrickPos::new(0, 1), BrickPos::new(1, 1), BrickPos::new(1, 0), BrickPos::new(2, 0)]),
-           BrickShape::new([BrickPos::new(2, 2), BrickPos::new(2, 1), BrickPos::new(1, 1), BrickPos::new(1, 0)]),
-        ]),
-
-        // T
-        BrickType::new(vec![
-           BrickShape::new([BrickPos::new(0, 1), BrickPos::new(1, 1), BrickPos::new(2, 1), BrickPos::new(1, 0)]),
-           BrickShape::new([BrickPos::new(1, 0), BrickPos::new(1, 1), BrickPos::new(1, 2), BrickPos::new(0, 1)]),
-           BrickShape::new([BrickPos::new(0, 1), BrickPos::new(1, 1), BrickPos::new(2, 1), BrickPos::new(1, 2)]),
-           BrickShape::new([BrickPos::new(1, 0), BrickPos::new(1, 1), BrickPos::new(1, 2), BrickPos::new(2, 1)]),
-        ]),
-
-
-    ];
+#[cfg(test)]
+mod tests {
+    use bevy::time::TimeUpdateStrategy;
+
+    use super::*;
+    use crate::{
+        action::action_clear,
+        config::BOARD_WIDTH,
+        randomizer::RandomizerKind,
+        rotation::RotationSystem,
+        score::{score_up, soft_drop_score_up, ClearStats, Score},
+        sim::{advance, SimAction, SimState},
+        transition::TransitionPlugin,
+    };
+
+    const QUAD_INDEX: usize = 0;
+    const LINE_INDEX: usize = 1;
+    const T_INDEX: usize = 6;
+    const SEED: u64 = 11;
+
+    /// 1/64 s, so timers of a power of two fraction of a second end on an exact frame
+    const FRAME: Duration = Duration::from_micros(15_625);
+
+    /// the gameplay of `BrickPlugin` with a fixed frame time and without anything drawn, with
+    /// the first brick spawned; tests play it by pressing and releasing actions on `ActionState`
+    fn test_app(config: GameConfig) -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, TransitionPlugin))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME))
+            .add_state::<AppState>()
+            .insert_resource(config)
+            .init_resource::<BoardConfig>()
+            .init_resource::<GameRng>()
+            .init_resource::<PieceRandomizer>()
+            .init_resource::<Input<KeyCode>>()
+            .init_resource::<ActionState>()
+            .init_resource::<BrickTexture>()
+            .init_resource::<Theme>()
+            .init_resource::<Level>()
+            .init_resource::<Score>()
+            .init_resource::<ClearStats>()
+            .init_resource::<Hold>()
+            // the first restart takes it, every run deals the same pieces
+            .insert_resource(GameSeed {
+                replay: Some(SEED),
+                ..default()
+            })
+            .add_systems(Update, (score_up, soft_drop_score_up))
+            .add_systems(Last, action_clear);
+        add_gameplay(&mut app);
+
+        // skip the menu, the restart on the way in spawns the first brick
+        app.insert_resource(NextState(Some(AppState::Gaming)));
+        app.update();
+        app
+    }
+
+    fn run(app: &mut App, frames: usize) {
+        for _ in 0..frames {
+            app.update();
+        }
+    }
+
+    fn press(app: &mut App, action: Action) {
+        app.world.resource_mut::<ActionState>().press(action);
+    }
+
+    fn release(app: &mut App, action: Action) {
+        app.world.resource_mut::<ActionState>().release(action);
+    }
+
+    fn origin(app: &App) -> BrickPos {
+        app.world.resource::<BrickState>().brick_pos_origin
+    }
+
+    fn cells(app: &mut App) -> Vec<BrickPos> {
+        let mut brick_pos_arr = app
+            .world
+            .query_filtered::<&BrickPos, With<BrickMoveable>>()
+            .iter(&app.world)
+            .copied()
+            .collect::<Vec<BrickPos>>();
+        brick_pos_arr.sort_by_key(|pos| (pos.x, pos.y));
+        brick_pos_arr
+    }
+
+    /// the cells of the moveable brick where `BrickState` says they are
+    fn cells_at_origin(app: &App) -> Vec<BrickPos> {
+        let brick_state = app.world.resource::<BrickState>();
+        let rotation_system = app.world.resource::<GameConfig>().rotation_system;
+        let brick_shape = &rotation_system.brick_type_arr()[brick_state.brick_type_index]
+            .brick_shape_arr[brick_state.brick_shape_index];
+        let mut brick_pos_arr = brick_shape
+            .brick_pos_arr
+            .iter()
+            .map(|&pos| pos + brick_state.brick_pos_origin)
+            .collect::<Vec<BrickPos>>();
+        brick_pos_arr.sort_by_key(|pos| (pos.x, pos.y));
+        brick_pos_arr
+    }
+
+    /// fill the bottom of the board with garbage cells, rows drawn top to bottom with `#` for
+    /// a filled cell
+    fn stack(app: &mut App, row_arr: &[&str]) {
+        for (y, row) in row_arr.iter().rev().enumerate() {
+            for (x, _) in row.chars().enumerate().filter(|&(_, cell)| cell == '#') {
+                app.world.spawn((
+                    BrickPos::new(x as i8, y as i8),
+                    GarbageBrick,
+                    Transform::default(),
+                ));
+            }
+        }
+    }
+
+    fn falling_count(app: &mut App) -> usize {
+        app.world
+            .query_filtered::<(), With<Falling>>()
+            .iter(&app.world)
+            .count()
+    }
+    /// replace the falling brick with one of the given type
+    fn respawn(app: &mut App, brick_type_index: usize) {
+        let entity_arr = app
+            .world
+            .query_filtered::<Entity, With<BrickMoveable>>()
+            .iter(&app.world)
+            .collect::<Vec<Entity>>();
+        for entity in entity_arr {
+            app.world.entity_mut(entity).despawn_recursive();
+        }
+        app.world
+            .resource_mut::<Events<SpawnEvent>>()
+            .send(SpawnEvent {
+                brick_type_index: Some(brick_type_index),
+            });
+        app.update();
+    }
+
+    fn stable_count(app: &mut App) -> usize {
+        app.world
+            .query_filtered::<(), (With<BrickPos>, Without<BrickMoveable>)>()
+            .iter(&app.world)
+            .count()
+    }
+
+    fn moveable_count(app: &mut App) -> usize {
+        app.world
+            .query_filtered::<(), With<BrickMoveable>>()
+            .iter(&app.world)
+            .count()
+    }
+
+    /// run until the brick locks and the next one spawned
+    fn lock_and_spawn(app: &mut App) {
+        let stable_count_old = stable_count(app);
+        for _ in 0..60 {
+            app.update();
+            if stable_count(app) > stable_count_old && moveable_count(app) == 4 {
+                return;
+            }
+        }
+        panic!("no brick locked and spawned");
+    }
+
+    /// hold soft drop, once the spawn guard passed so it moves every frame, until the brick
+    /// stops one frame after reaching the floor
+    fn soft_drop_to_floor(app: &mut App) {
+        run(app, 8);
+        press(app, Action::SoftDrop);
+        for _ in 0..40 {
+            let y = origin(app).y;
+            app.update();
+            if origin(app).y == y {
+                return;
+            }
+        }
+        panic!("the brick didn't reach the floor");
+    }
+
+    fn score(app: &App) -> u32 {
+        app.world.resource::<Score>().get()
+    }
+
+    #[test]
+    fn soft_drop_scores_the_rows_dropped_and_nothing_for_gravity() {
+        let mut app = test_app(GameConfig::default());
+
+        // level 1 gravity moves the brick once within the first second
+        let spawn_y = origin(&app).y;
+        run(&mut app, 60);
+        let fall_y = origin(&app).y;
+        assert_eq!(fall_y, spawn_y - 1);
+        assert_eq!(score(&app), 0);
+
+        press(&mut app, Action::SoftDrop);
+        // a row on the press, then one every 0.05 s while held, ending on the 4th, 7th and
+        // 10th frame held
+        run(&mut app, 1 + 10);
+        release(&mut app, Action::SoftDrop);
+        run(&mut app, 1);
+
+        assert_eq!(origin(&app).y, fall_y - 4);
+        assert_eq!(score(&app), 4);
+    }
+
+    #[test]
+    fn a_move_in_a_frame_the_brick_falls_keeps_the_origin_on_its_cells() {
+        let mut app = test_app(GameConfig::default());
+        // two cells per frame, so the brick falls on every frame it moves
+        app.world.resource_mut::<Level>().0 = 31;
+        run(&mut app, 1);
+
+        let origin_before = origin(&app);
+        press(&mut app, Action::MoveRight);
+        run(&mut app, 1);
+        release(&mut app, Action::MoveRight);
+        assert_eq!(origin(&app).x, origin_before.x + 1);
+        assert!(origin(&app).y < origin_before.y);
+        assert_eq!(cells(&mut app), cells_at_origin(&app));
+
+        // the rotation is placed from the origin
+        press(&mut app, Action::Rotate);
+        run(&mut app, 1);
+        release(&mut app, Action::Rotate);
+        assert_eq!(cells(&mut app), cells_at_origin(&app));
+    }
+
+    #[test]
+    fn injected_rotation_turns_the_brick() {
+        let config = GameConfig {
+            gravity_scale: 0.0,
+            ..GameConfig::default()
+        };
+        let mut app = test_app(config);
+        respawn(&mut app, T_INDEX);
+
+        press(&mut app, Action::RotateCw);
+        run(&mut app, 1);
+        assert_eq!(app.world.resource::<BrickState>().brick_shape_index, 1);
+
+        release(&mut app, Action::RotateCw);
+        press(&mut app, Action::RotateCcw);
+        run(&mut app, 1);
+        assert_eq!(app.world.resource::<BrickState>().brick_shape_index, 0);
+    }
+
+    #[test]
+    fn injected_hold_swaps_once_until_the_lock() {
+        let config = GameConfig {
+            gravity_scale: 0.0,
+            ..GameConfig::default()
+        };
+        let mut app = test_app(config);
+        respawn(&mut app, T_INDEX);
+
+        press(&mut app, Action::Hold);
+        run(&mut app, 2);
+        let hold = app.world.resource::<Hold>();
+        assert_eq!(hold.brick_type_index, Some(T_INDEX));
+        assert!(!hold.can_hold);
+        let brick_type_index = app.world.resource::<BrickState>().brick_type_index;
+
+        release(&mut app, Action::Hold);
+        press(&mut app, Action::Hold);
+        run(&mut app, 2);
+        assert_eq!(
+            app.world.resource::<BrickState>().brick_type_index,
+            brick_type_index
+        );
+        assert_eq!(app.world.resource::<Hold>().brick_type_index, Some(T_INDEX));
+
+        release(&mut app, Action::Hold);
+        press(&mut app, Action::HardDrop);
+        run(&mut app, 1);
+        assert!(app.world.resource::<Hold>().can_hold);
+    }
+
+    #[test]
+    fn spawn_waits_until_the_cleared_rows_have_fallen() {
+        let config = GameConfig {
+            gravity_scale: 0.0,
+            lock_delay: 0.0,
+            clear_delay: 0.25,
+            ..GameConfig::default()
+        };
+        let mut app = test_app(config);
+        stack(&mut app, &["#.........", "###....###"]);
+        respawn(&mut app, LINE_INDEX);
+
+        press(&mut app, Action::HardDrop);
+        run(&mut app, 1);
+        assert_eq!(moveable_count(&mut app), 0);
+
+        // the clear delay of 16 frames, then the cell above falling for 10
+        for frame in 1..40 {
+            app.update();
+            if moveable_count(&mut app) == 0 {
+                continue;
+            }
+            assert!(frame > 16, "spawned on frame {}", frame);
+            assert_eq!(stable_count(&mut app), 1);
+            assert_eq!(falling_count(&mut app), 0);
+            return;
+        }
+        panic!("no brick spawned after the clear");
+    }
+
+    #[test]
+    fn double_clear_scores_and_shifts_the_cells_above_by_two_rows() {
+        let config = GameConfig {
+            gravity_scale: 0.0,
+            lock_delay: 0.0,
+            ..GameConfig::default()
+        };
+        let mut app = test_app(config);
+        stack(
+            &mut app,
+            &[".........#", "..........", "####..####", "####..####"],
+        );
+        app.world.spawn((
+            BrickPos::new(0, 2),
+            BrickKind(T_INDEX),
+            Transform::default(),
+        ));
+        respawn(&mut app, QUAD_INDEX);
+
+        press(&mut app, Action::HardDrop);
+        // the clear delay of 26 frames and the fall of 10
+        run(&mut app, 60);
+
+        assert_eq!(app.world.resource::<Score>().get(), 300);
+        let mut cell_arr = app
+            .world
+            .query_filtered::<(&BrickPos, Option<&BrickKind>), Without<BrickMoveable>>()
+            .iter(&app.world)
+            .map(|(&brick_pos, brick_kind)| (brick_pos, brick_kind.map(|kind| kind.0)))
+            .collect::<Vec<(BrickPos, Option<usize>)>>();
+        cell_arr.sort_by_key(|(brick_pos, _)| brick_pos.x);
+        assert_eq!(
+            cell_arr,
+            vec![
+                (BrickPos::new(0, 0), Some(T_INDEX)),
+                (BrickPos::new(9, 1), None)
+            ]
+        );
+        assert_eq!(falling_count(&mut app), 0);
+    }
+
+    #[test]
+    fn live_game_deals_the_pieces_of_a_simulation_with_its_seed() {
+        let config = GameConfig {
+            gravity_scale: 0.0,
+            lock_delay: 0.0,
+            randomizer: RandomizerKind::TgmHistory,
+            // draws the garbage gaps from the game rng before the first piece
+            training_garbage_rows: 2,
+            ..GameConfig::default()
+        };
+        let mut app = test_app(config);
+        let mut sim_state = SimState::new(
+            RotationSystem::Srs,
+            BoardConfig::default(),
+            app.world.resource::<GameSeed>().current,
+            RandomizerKind::TgmHistory,
+        );
+
+        for _ in 0..5 {
+            assert_eq!(
+                app.world.resource::<BrickState>().brick_type_index,
+                sim_state.piece.unwrap().brick_type_index
+            );
+            press(&mut app, Action::HardDrop);
+            lock_and_spawn(&mut app);
+            release(&mut app, Action::HardDrop);
+            advance(&mut sim_state, SimAction::HardDrop);
+        }
+    }
+
+    #[test]
+    fn restart_held_after_game_over_starts_one_game() {
+        let mut app = test_app(GameConfig::default());
+        app.insert_resource(NextState(Some(AppState::GameOver)));
+        run(&mut app, 1);
+        let mut restart_reader = app.world.resource::<Events<RestartEvent>>().get_reader();
+
+        // the fade of 0.6 s is 39 frames, R is still held well after it
+        press(&mut app, Action::Restart);
+        let mut restart_count = 0;
+        for _ in 0..80 {
+            app.update();
+            restart_count += restart_reader
+                .iter(app.world.resource::<Events<RestartEvent>>())
+                .count();
+        }
+
+        assert_eq!(restart_count, 1);
+        assert_eq!(
+            app.world.resource::<State<AppState>>().get(),
+            &AppState::Gaming
+        );
+        assert_eq!(moveable_count(&mut app), 4);
+        assert!(!app.world.resource::<RestartPending>().0);
+    }
+
+    fn lowest_y<F: bevy::ecs::query::ReadOnlyWorldQuery>(app: &mut App) -> i8 {
+        app.world
+            .query_filtered::<&BrickPos, F>()
+            .iter(&app.world)
+            .map(|brick_pos| brick_pos.y)
+            .min()
+            .unwrap()
+    }
+
+    #[test]
+    fn hard_drop_locks_on_the_frame_it_lands() {
+        let config = GameConfig {
+            gravity_scale: 0.0,
+            ..GameConfig::default()
+        };
+        let mut app = test_app(config);
+
+        press(&mut app, Action::HardDrop);
+        run(&mut app, 1);
+        assert_eq!(moveable_count(&mut app), 0);
+        assert_eq!(stable_count(&mut app), 4);
+        assert_eq!(lowest_y::<Without<BrickMoveable>>(&mut app), 0);
+    }
+
+    #[test]
+    fn hard_drop_lands_without_locking_when_set() {
+        let config = GameConfig {
+            gravity_scale: 0.0,
+            hard_drop_locks: false,
+            ..GameConfig::default()
+        };
+        let mut app = test_app(config);
+
+        press(&mut app, Action::HardDrop);
+        run(&mut app, 1);
+        assert_eq!(stable_count(&mut app), 0);
+        assert_eq!(lowest_y::<With<BrickMoveable>>(&mut app), 0);
+    }
+
+    #[test]
+    fn sonic_drop_lands_the_brick_and_leaves_it_moveable() {
+        let config = GameConfig {
+            gravity_scale: 0.0,
+            ..GameConfig::default()
+        };
+        let mut app = test_app(config);
+
+        press(&mut app, Action::SonicDrop);
+        run(&mut app, 1);
+        release(&mut app, Action::SonicDrop);
+        assert_eq!(stable_count(&mut app), 0);
+        assert_eq!(lowest_y::<With<BrickMoveable>>(&mut app), 0);
+
+        let x = origin(&app).x;
+        press(&mut app, Action::MoveLeft);
+        run(&mut app, 1);
+        release(&mut app, Action::MoveLeft);
+        assert_eq!(origin(&app).x, x - 1);
+        assert_eq!(moveable_count(&mut app), 4);
+
+        // the lock delay of 32 frames runs out
+        run(&mut app, 40);
+        assert_eq!(stable_count(&mut app), 4);
+    }
+
+    /// type and orientation of each of the first bricks of a game with random orientations
+    fn spawn_arr() -> Vec<(usize, usize)> {
+        let config = GameConfig {
+            gravity_scale: 0.0,
+            lock_delay: 0.0,
+            random_spawn_rotation: true,
+            ..GameConfig::default()
+        };
+        let mut app = test_app(config);
+        let mut spawn_arr = vec![];
+        for _ in 0..6 {
+            let brick_state = app.world.resource::<BrickState>();
+            spawn_arr.push((brick_state.brick_type_index, brick_state.brick_shape_index));
+            press(&mut app, Action::HardDrop);
+            lock_and_spawn(&mut app);
+            release(&mut app, Action::HardDrop);
+        }
+        spawn_arr
+    }
+
+    #[test]
+    fn random_spawn_orientations_repeat_under_the_same_seed() {
+        let spawn_arr_first = spawn_arr();
+        assert_eq!(spawn_arr_first, spawn_arr());
+        assert!(spawn_arr_first
+            .iter()
+            .any(|&(_, brick_shape_index)| brick_shape_index != 0));
+    }
+
+    /// types of the next bricks, hard dropping each
+    fn deal_arr(app: &mut App, count: usize) -> Vec<usize> {
+        let mut deal_arr = vec![];
+        for _ in 0..count {
+            deal_arr.push(app.world.resource::<BrickState>().brick_type_index);
+            press(app, Action::HardDrop);
+            lock_and_spawn(app);
+            release(app, Action::HardDrop);
+        }
+        deal_arr
+    }
+
+    #[test]
+    fn soft_reset_deals_the_pieces_of_the_game_again() {
+        let config = GameConfig {
+            gravity_scale: 0.0,
+            ..GameConfig::default()
+        };
+        let mut app = test_app(config);
+        let deal_arr_first = deal_arr(&mut app, 5);
+
+        press(&mut app, Action::SoftReset);
+        run(&mut app, 2);
+        release(&mut app, Action::SoftReset);
+        assert_eq!(stable_count(&mut app), 0);
+        assert_eq!(moveable_count(&mut app), 4);
+
+        assert_eq!(deal_arr(&mut app, 5), deal_arr_first);
+    }
+
+    #[test]
+    fn previews_spawn_in_the_order_shown() {
+        let config = GameConfig {
+            gravity_scale: 0.0,
+            lock_delay: 0.0,
+            preview_count: 5,
+            ..GameConfig::default()
+        };
+        let mut app = test_app(config);
+        let upcoming_arr = app
+            .world
+            .resource::<PieceRandomizer>()
+            .upcoming()
+            .collect::<Vec<usize>>();
+        assert_eq!(upcoming_arr.len(), 5);
+
+        for brick_type_index in upcoming_arr {
+            press(&mut app, Action::HardDrop);
+            lock_and_spawn(&mut app);
+            release(&mut app, Action::HardDrop);
+            assert_eq!(
+                app.world.resource::<BrickState>().brick_type_index,
+                brick_type_index
+            );
+        }
+    }
+
+    /// a vertical line against the left wall, turning it flat needs a kick
+    fn line_at_the_wall(strict_rotation: bool) -> App {
+        let config = GameConfig {
+            gravity_scale: 0.0,
+            strict_rotation,
+            ..GameConfig::default()
+        };
+        let mut app = test_app(config);
+        respawn(&mut app, LINE_INDEX);
+
+        press(&mut app, Action::RotateCw);
+        run(&mut app, 1);
+        release(&mut app, Action::RotateCw);
+        for _ in 0..6 {
+            press(&mut app, Action::MoveLeft);
+            run(&mut app, 1);
+            release(&mut app, Action::MoveLeft);
+            run(&mut app, 1);
+        }
+        assert_eq!(origin(&app).x, -2);
+
+        press(&mut app, Action::RotateCw);
+        run(&mut app, 1);
+        app
+    }
+
+    #[test]
+    fn strict_rotation_fails_where_a_kick_is_needed() {
+        let app = line_at_the_wall(true);
+        assert_eq!(app.world.resource::<BrickState>().brick_shape_index, 1);
+        assert_eq!(origin(&app).x, -2);
+    }
+
+    #[test]
+    fn rotation_kicks_off_the_wall_otherwise() {
+        let app = line_at_the_wall(false);
+        assert_eq!(app.world.resource::<BrickState>().brick_shape_index, 2);
+        assert_eq!(origin(&app).x, 0);
+    }
+
+    /// soft drop repeats every frame
+    fn soft_drop_config(soft_drop_locks_immediately: bool) -> GameConfig {
+        GameConfig {
+            gravity_scale: 0.0,
+            soft_drop_speed: 64.0,
+            soft_drop_locks_immediately,
+            ..GameConfig::default()
+        }
+    }
+
+    #[test]
+    fn held_soft_drop_rests_for_the_guard_before_locking() {
+        let mut app = test_app(soft_drop_config(true));
+
+        soft_drop_to_floor(&mut app);
+        assert_eq!(stable_count(&mut app), 0);
+
+        // 7 frames rest the 0.1 s guard, far short of the lock delay
+        run(&mut app, 8);
+        assert_eq!(stable_count(&mut app), 4);
+    }
+
+    /// a fresh soft drop press on a brick that rested past the guard
+    fn soft_drop_pressed_on_the_floor(soft_drop_locks_immediately: bool) -> App {
+        let mut app = test_app(soft_drop_config(soft_drop_locks_immediately));
+        soft_drop_to_floor(&mut app);
+        release(&mut app, Action::SoftDrop);
+        run(&mut app, 8);
+        assert_eq!(stable_count(&mut app), 0);
+
+        press(&mut app, Action::SoftDrop);
+        run(&mut app, 1);
+        app
+    }
+
+    #[test]
+    fn soft_drop_press_locks_a_resting_brick_when_set() {
+        let mut app = soft_drop_pressed_on_the_floor(true);
+        assert_eq!(stable_count(&mut app), 4);
+    }
+
+    #[test]
+    fn soft_drop_press_leaves_a_resting_brick_to_the_lock_delay_otherwise() {
+        let mut app = soft_drop_pressed_on_the_floor(false);
+        assert_eq!(stable_count(&mut app), 0);
+        assert_eq!(moveable_count(&mut app), 4);
+    }
+
+    #[test]
+    fn soft_drop_held_into_a_new_brick_waits_for_the_spawn_guard() {
+        let mut app = test_app(soft_drop_config(true));
+
+        soft_drop_to_floor(&mut app);
+        lock_and_spawn(&mut app);
+        let y = origin(&app).y;
+
+        // the guard of 0.1 s is 7 frames
+        run(&mut app, 5);
+        assert_eq!(origin(&app).y, y);
+
+        run(&mut app, 4);
+        assert!(origin(&app).y < y);
+    }
+
+    fn hard_drop_config(hard_drop_auto_repeat: bool) -> GameConfig {
+        GameConfig {
+            gravity_scale: 0.0,
+            hard_drop_auto_repeat,
+            ..GameConfig::default()
+        }
+    }
+
+    #[test]
+    fn held_hard_drop_needs_a_fresh_press_for_the_next_brick() {
+        let mut app = test_app(hard_drop_config(false));
+
+        press(&mut app, Action::HardDrop);
+        lock_and_spawn(&mut app);
+        let y = origin(&app).y;
+
+        run(&mut app, 30);
+        assert_eq!(origin(&app).y, y);
+        assert_eq!(stable_count(&mut app), 4);
+    }
+
+    #[test]
+    fn held_hard_drop_repeats_into_the_next_brick_after_the_spawn_guard() {
+        let mut app = test_app(hard_drop_config(true));
+
+        press(&mut app, Action::HardDrop);
+        lock_and_spawn(&mut app);
+        let y = origin(&app).y;
+
+        // the guard of 0.1 s is 7 frames
+        run(&mut app, 5);
+        assert_eq!(origin(&app).y, y);
+        assert_eq!(stable_count(&mut app), 4);
+
+        lock_and_spawn(&mut app);
+        assert_eq!(stable_count(&mut app), 8);
+    }
+
+    /// DAS of 16 frames, repeats every 4
+    fn das_config(das_direction_change_immediate: bool) -> GameConfig {
+        GameConfig {
+            das_delay: 0.25,
+            arr_interval: 0.0625,
+            das_direction_change_immediate,
+            ..GameConfig::default()
+        }
+    }
+
+    #[test]
+    fn direction_change_moves_at_once_when_immediate() {
+        let mut app = test_app(das_config(true));
+        let spawn_x = origin(&app).x;
+
+        press(&mut app, Action::MoveLeft);
+        run(&mut app, 1);
+        assert_eq!(origin(&app).x, spawn_x - 1);
+
+        press(&mut app, Action::MoveRight);
+        run(&mut app, 1);
+        assert_eq!(origin(&app).x, spawn_x);
+    }
+
+    #[test]
+    fn direction_change_waits_for_the_old_key_otherwise() {
+        let mut app = test_app(das_config(false));
+        let spawn_x = origin(&app).x;
+
+        press(&mut app, Action::MoveLeft);
+        run(&mut app, 1);
+        assert_eq!(origin(&app).x, spawn_x - 1);
+
+        // left keeps charging, the right press is ignored
+        press(&mut app, Action::MoveRight);
+        run(&mut app, 1);
+        assert_eq!(origin(&app).x, spawn_x - 1);
+
+        // releasing left hands over to the held right key, which charges its own DAS
+        release(&mut app, Action::MoveLeft);
+        run(&mut app, 1 + 15);
+        assert_eq!(origin(&app).x, spawn_x - 1);
+        run(&mut app, 1);
+        assert_eq!(origin(&app).x, spawn_x);
+    }
+
+    /// the stable cells with their garbage mark and sprite color, sorted by row then column
+    fn stable_cells(app: &mut App) -> Vec<(BrickPos, bool, Color)> {
+        let mut cell_arr = app
+            .world
+            .query_filtered::<(&BrickPos, Option<&GarbageBrick>, &Sprite), Without<BrickMoveable>>()
+            .iter(&app.world)
+            .map(|(pos, garbage_brick, sprite)| (*pos, garbage_brick.is_some(), sprite.color))
+            .collect::<Vec<(BrickPos, bool, Color)>>();
+        cell_arr.sort_by_key(|(pos, _, _)| (pos.y, pos.x));
+        cell_arr
+    }
+
+    #[test]
+    fn rising_mode_telegraphs_a_garbage_row_before_it_rises() {
+        let mut app = test_app(GameConfig {
+            mode: GameMode::Rising,
+            rising_garbage_interval: 3.0,
+            ..default()
+        });
+
+        // the row is queued after 3s, with its gap already picked
+        run(&mut app, 240);
+        let gap_x_arr = app.world.resource::<GarbageQueue>().gap_x_arr.clone();
+        assert_eq!(gap_x_arr.len(), 1);
+        assert!(stable_cells(&mut app).is_empty());
+
+        // and rises a second later, the gap where the telegraph showed it
+        run(&mut app, 40);
+        assert!(app.world.resource::<GarbageQueue>().gap_x_arr.is_empty());
+        let garbage = app.world.resource::<Theme>().garbage;
+        let expected = (0..BOARD_WIDTH)
+            .filter(|&x| x != gap_x_arr[0])
+            .map(|x| (BrickPos::new(x, 0), true, garbage))
+            .collect::<Vec<(BrickPos, bool, Color)>>();
+        assert_eq!(stable_cells(&mut app), expected);
+    }
+
+    #[test]
+    fn a_mixed_row_clears_and_the_garbage_above_keeps_its_place_and_color() {
+        let mut app = test_app(GameConfig::default());
+        let theme = app.world.resource::<Theme>().clone();
+
+        let spawn_cell = |app: &mut App, x: i8, y: i8, brick_kind: Option<usize>| {
+            let color = brick_kind.map_or(theme.garbage, |index| theme.brick_arr[index]);
+            let mut entity = app.world.spawn((
+                SpriteBundle {
+                    sprite: Sprite { color, ..default() },
+                    transform: get_brick_pos(x, y, 1.0, &BoardConfig::default(), false),
+                    ..default()
+                },
+                BrickPos::new(x, y),
+            ));
+            match brick_kind {
+                Some(index) => entity.insert(BrickKind(index)),
+                None => entity.insert(GarbageBrick),
+            };
+        };
+        // a full row, garbage on the left and a player brick on the right
+        for x in 0..BOARD_WIDTH {
+            spawn_cell(&mut app, x, 0, if x < 4 { None } else { Some(3) });
+        }
+        // a garbage row with its gap in column 0, and a player cell above the gap
+        for x in 1..BOARD_WIDTH {
+            spawn_cell(&mut app, x, 1, None);
+        }
+        spawn_cell(&mut app, 0, 2, Some(5));
+
+        app.world.send_event(FullLineCheckEvent);
+        // the full row stays for the clear delay
+        run(&mut app, 25);
+        assert_eq!(stable_count(&mut app), 2 * BOARD_WIDTH as usize);
+        run(&mut app, 1);
+
+        let mut expected = (1..BOARD_WIDTH)
+            .map(|x| (BrickPos::new(x, 0), true, theme.garbage))
+            .collect::<Vec<(BrickPos, bool, Color)>>();
+        expected.push((BrickPos::new(0, 1), false, theme.brick_arr[5]));
+        assert_eq!(stable_cells(&mut app), expected);
+
+        // the sprites fall into place over 0.15 s
+        run(&mut app, 10);
+        let transform_arr = app
+            .world
+            .query_filtered::<(&BrickPos, &Transform), Without<BrickMoveable>>()
+            .iter(&app.world)
+            .map(|(pos, transform)| (*pos, transform.translation))
+            .collect::<Vec<(BrickPos, Vec3)>>();
+        for (pos, translation) in transform_arr {
+            assert_eq!(
+                translation,
+                get_brick_pos(pos.x, pos.y, 1.0, &BoardConfig::default(), false).translation
+            );
+        }
+    }
 }
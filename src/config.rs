@@ -0,0 +1,694 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{randomizer::RandomizerKind, rotation::RotationSystem, theme::ThemeKind};
+
+pub struct ConfigPlugin;
+
+impl Plugin for ConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BoardConfig>()
+            .init_resource::<GameConfig>();
+    }
+}
+
+pub const BOARD_WIDTH: i8 = 10;
+pub const BOARD_HEIGHT: i8 = 20;
+/// hidden rows above the visible board, bricks spawn here and may stay here without topping out
+pub const VANISH_HEIGHT: i8 = 2;
+/// sizes the menu offers, every brick still spawns and turns on the smallest board
+pub const BOARD_WIDTH_MIN: i8 = 6;
+pub const BOARD_WIDTH_MAX: i8 = 16;
+pub const BOARD_HEIGHT_MIN: i8 = 12;
+pub const BOARD_HEIGHT_MAX: i8 = 30;
+/// columns every brick fits in whatever its orientation, a spawn column leaves room for it
+const SPAWN_BOX_WIDTH: i8 = 4;
+pub const PREVIEW_COUNT_MAX: usize = 5;
+/// percent of the line clear points scored with each preview count, playing with fewer
+/// bricks shown ahead pays more
+const PREVIEW_MULTIPLIER_PERCENT_ARR: [u32; PREVIEW_COUNT_MAX + 1] = [150, 100, 95, 90, 85, 80];
+
+#[derive(Debug, Resource, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BoardConfig {
+    pub width: i8,
+    /// visible rows
+    pub height: i8,
+    pub vanish_height: i8,
+    /// origin column of a new brick instead of the middle of the board
+    pub spawn_x_override: Option<i8>,
+    /// origin row of a new brick instead of the one of the rotation system
+    pub spawn_y_override: Option<i8>,
+    /// a brick locking with all of its cells at or above this row ends the game
+    pub topout_row_override: Option<i8>,
+}
+
+impl Default for BoardConfig {
+    fn default() -> Self {
+        Self {
+            width: BOARD_WIDTH,
+            height: BOARD_HEIGHT,
+            vanish_height: VANISH_HEIGHT,
+            spawn_x_override: None,
+            spawn_y_override: None,
+            topout_row_override: None,
+        }
+    }
+}
+
+impl BoardConfig {
+    /// rows at and above this one are over the ceiling line, it keeps the top fifth of the
+    /// visible rows above it whatever the board height
+    pub fn ceiling_row(&self) -> i8 {
+        self.height * 4 / 5
+    }
+
+    /// visible rows plus the vanish zone
+    pub fn total_height(&self) -> i8 {
+        self.height + self.vanish_height
+    }
+
+    /// origin column of a new brick, its 4 wide box in the middle of the board unless
+    /// overridden, an override that doesn't leave room for the box is ignored
+    pub fn spawn_x(&self) -> i8 {
+        match self.spawn_x_override {
+            Some(x) if (0..=self.width - SPAWN_BOX_WIDTH).contains(&x) => x,
+            _ => self.width / 2 - SPAWN_BOX_WIDTH / 2,
+        }
+    }
+
+    /// origin row of a new brick, an override that puts a cell of any spawn shape off the
+    /// board is ignored
+    pub fn spawn_y(&self, rotation_system: RotationSystem) -> i8 {
+        let Some(y) = self.spawn_y_override else {
+            return rotation_system.spawn_y(self);
+        };
+        let is_inside = rotation_system.brick_type_arr().iter().all(|brick_type| {
+            brick_type.brick_shape_arr[0]
+                .brick_pos_arr
+                .iter()
+                .all(|pos| (0..self.total_height()).contains(&(y + pos.y)))
+        });
+        if is_inside {
+            y
+        } else {
+            rotation_system.spawn_y(self)
+        }
+    }
+
+    /// the top of the board unless overridden, so only a blocked spawn ends the game
+    pub fn topout_row(&self) -> i8 {
+        self.topout_row_override.unwrap_or(self.total_height())
+    }
+}
+
+/// when the lock delay of a resting brick starts over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LockBehavior {
+    /// only moving down to a new lowest row resets the lock delay,
+    /// sliding and rotating on the stack don't buy more time
+    Classic,
+    /// any successful move or rotation resets the lock delay, at most
+    /// `LOCK_RESET_MAX` times until the brick reaches a new lowest row
+    #[default]
+    MoveReset,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum GameMode {
+    #[default]
+    Marathon,
+    /// locked bricks fade out after `invisible_fade_delay`, V peeks at the stack
+    Invisible,
+    /// the game ends as soon as the stack is left above `BoardConfig::ceiling_row`
+    Ceiling,
+    /// a garbage row rises under the stack every `rising_garbage_interval` seconds
+    Rising,
+    /// marathon that is cleared once `line_target` lines are reached
+    Target,
+    /// board and left/right controls are flipped horizontally
+    Mirror,
+    /// marathon with numbered columns and rows and lessons that highlight where to place
+    Tutorial,
+    /// full lines stay on the board, to practice clean stacking until the top out
+    StackTrainer,
+}
+
+impl GameMode {
+    pub fn next(self) -> Self {
+        match self {
+            GameMode::Marathon => GameMode::Invisible,
+            GameMode::Invisible => GameMode::Ceiling,
+            GameMode::Ceiling => GameMode::Rising,
+            GameMode::Rising => GameMode::Target,
+            GameMode::Target => GameMode::Mirror,
+            GameMode::Mirror => GameMode::Tutorial,
+            GameMode::Tutorial => GameMode::StackTrainer,
+            GameMode::StackTrainer => GameMode::Marathon,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GameMode::Marathon => "Marathon",
+            GameMode::Invisible => "Invisible",
+            GameMode::Ceiling => "Ceiling",
+            GameMode::Rising => "Rising",
+            GameMode::Target => "Target",
+            GameMode::Mirror => "Mirror",
+            GameMode::Tutorial => "Tutorial",
+            GameMode::StackTrainer => "Stack trainer",
+        }
+    }
+
+    pub fn clears_lines(self) -> bool {
+        self != GameMode::StackTrainer
+    }
+}
+
+/// how the ghost follows the landing spot of the moveable brick
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GhostSmoothing {
+    /// jumps to the new landing spot on the frame the brick moves
+    #[default]
+    Instant,
+    /// glides there within a few frames
+    Lerp,
+}
+
+impl GhostSmoothing {
+    pub fn next(self) -> Self {
+        match self {
+            GhostSmoothing::Instant => GhostSmoothing::Lerp,
+            GhostSmoothing::Lerp => GhostSmoothing::Instant,
+        }
+    }
+}
+
+/// the earlier game played along with a new one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RaceReplay {
+    /// play alone
+    #[default]
+    Off,
+    /// the replay saved at the end of the last game
+    LastGame,
+    /// the replay saved with the best score of the mode
+    PersonalBest,
+}
+
+impl RaceReplay {
+    pub fn next(self) -> Self {
+        match self {
+            RaceReplay::Off => RaceReplay::LastGame,
+            RaceReplay::LastGame => RaceReplay::PersonalBest,
+            RaceReplay::PersonalBest => RaceReplay::Off,
+        }
+    }
+
+    pub fn prev(self) -> Self {
+        match self {
+            RaceReplay::Off => RaceReplay::PersonalBest,
+            RaceReplay::LastGame => RaceReplay::Off,
+            RaceReplay::PersonalBest => RaceReplay::LastGame,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            RaceReplay::Off => "off",
+            RaceReplay::LastGame => "last game",
+            RaceReplay::PersonalBest => "personal best",
+        }
+    }
+}
+
+/// how strongly the ghost is drawn, a display preference, turning it off here doesn't count
+/// as playing without the ghost assist
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum GhostOpacity {
+    Off,
+    Faint,
+    #[default]
+    Medium,
+    Strong,
+}
+
+impl GhostOpacity {
+    pub fn next(self) -> Self {
+        match self {
+            GhostOpacity::Off => GhostOpacity::Faint,
+            GhostOpacity::Faint => GhostOpacity::Medium,
+            GhostOpacity::Medium => GhostOpacity::Strong,
+            GhostOpacity::Strong => GhostOpacity::Off,
+        }
+    }
+
+    pub fn alpha(self) -> f32 {
+        match self {
+            GhostOpacity::Off => 0.0,
+            GhostOpacity::Faint => 0.15,
+            GhostOpacity::Medium => 0.3,
+            GhostOpacity::Strong => 0.55,
+        }
+    }
+}
+
+pub fn preview_multiplier_percent(preview_count: usize) -> u32 {
+    PREVIEW_MULTIPLIER_PERCENT_ARR[preview_count.min(PREVIEW_COUNT_MAX)]
+}
+
+/// assists a game was played with, kept with its leaderboard entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Assists {
+    pub hold: bool,
+    pub ghost: bool,
+}
+
+impl Assists {
+    pub fn is_none(self) -> bool {
+        !self.hold && !self.ghost
+    }
+
+    pub fn label(self) -> &'static str {
+        match (self.hold, self.ghost) {
+            (false, false) => "no assists",
+            (true, false) => "hold",
+            (false, true) => "ghost",
+            (true, true) => "hold, ghost",
+        }
+    }
+}
+
+/// where the gaps of the training garbage are
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GarbageGaps {
+    /// one random column for every row, cleared with a well
+    #[default]
+    Aligned,
+    /// a random column per row, to practice digging
+    Scattered,
+}
+
+/// where finished games are logged, off by default
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StatsExport {
+    #[default]
+    Off,
+    /// a row per game in history.csv
+    Csv,
+    /// an object per line in history.jsonl
+    Json,
+}
+
+/// how many points a line clear is worth
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScoringMode {
+    /// 100, 300, 500 and 800 times the level
+    #[default]
+    Guideline,
+    /// 40, 100, 300 and 1200 times the NES level plus one, which is our level as it counts
+    /// from 1 where the NES counts from 0
+    Classic,
+}
+
+impl ScoringMode {
+    pub fn next(self) -> Self {
+        match self {
+            ScoringMode::Guideline => ScoringMode::Classic,
+            ScoringMode::Classic => ScoringMode::Guideline,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ScoringMode::Guideline => "Guideline",
+            ScoringMode::Classic => "Classic",
+        }
+    }
+
+    /// points for clearing `lines` lines at once at `level`
+    pub fn line_clear_points(self, lines: u8, level: u32) -> u32 {
+        let base = match (self, lines) {
+            (ScoringMode::Guideline, 1) => 100,
+            (ScoringMode::Guideline, 2) => 300,
+            (ScoringMode::Guideline, 3) => 500,
+            (ScoringMode::Guideline, 4) => 800,
+            (ScoringMode::Classic, 1) => 40,
+            (ScoringMode::Classic, 2) => 100,
+            (ScoringMode::Classic, 3) => 300,
+            (ScoringMode::Classic, 4) => 1200,
+            _ => 0,
+        };
+        base * level
+    }
+}
+
+/// bundles of the settings that make the game easier or harder
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Difficulty {
+    Casual,
+    #[default]
+    Normal,
+    Expert,
+    Marathon,
+    /// no preset is applied, the settings screen on the menu unlocks to tune each setting
+    Custom,
+}
+
+impl Difficulty {
+    pub fn next(self) -> Self {
+        match self {
+            Difficulty::Casual => Difficulty::Normal,
+            Difficulty::Normal => Difficulty::Expert,
+            Difficulty::Expert => Difficulty::Marathon,
+            Difficulty::Marathon => Difficulty::Custom,
+            Difficulty::Custom => Difficulty::Casual,
+        }
+    }
+
+    pub fn prev(self) -> Self {
+        match self {
+            Difficulty::Casual => Difficulty::Custom,
+            Difficulty::Normal => Difficulty::Casual,
+            Difficulty::Expert => Difficulty::Normal,
+            Difficulty::Marathon => Difficulty::Expert,
+            Difficulty::Custom => Difficulty::Marathon,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Casual => "Casual",
+            Difficulty::Normal => "Normal",
+            Difficulty::Expert => "Expert",
+            Difficulty::Marathon => "Marathon",
+            Difficulty::Custom => "Custom",
+        }
+    }
+
+    /// the settings of a preset, Normal matches the `GameConfig` defaults
+    pub fn preset(self) -> Option<DifficultyPreset> {
+        let preset = match self {
+            Difficulty::Casual => DifficultyPreset {
+                start_level: 1,
+                gravity_scale: 0.5,
+                lock_delay: 1.0,
+                das_delay: 0.2,
+                arr_interval: 0.06,
+                hold_enabled: true,
+                ghost_enabled: true,
+                topout_grace: true,
+            },
+            Difficulty::Normal => DifficultyPreset {
+                start_level: 1,
+                gravity_scale: 1.0,
+                lock_delay: 0.5,
+                das_delay: 0.17,
+                arr_interval: 0.05,
+                hold_enabled: true,
+                ghost_enabled: true,
+                topout_grace: false,
+            },
+            Difficulty::Expert => DifficultyPreset {
+                start_level: 10,
+                gravity_scale: 1.0,
+                lock_delay: 0.3,
+                das_delay: 0.1,
+                arr_interval: 0.02,
+                hold_enabled: false,
+                ghost_enabled: false,
+                topout_grace: false,
+            },
+            Difficulty::Marathon => DifficultyPreset {
+                start_level: 1,
+                gravity_scale: 0.8,
+                lock_delay: 0.6,
+                das_delay: 0.15,
+                arr_interval: 0.04,
+                hold_enabled: true,
+                ghost_enabled: true,
+                topout_grace: false,
+            },
+            Difficulty::Custom => return None,
+        };
+        Some(preset)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DifficultyPreset {
+    pub start_level: u32,
+    pub gravity_scale: f32,
+    pub lock_delay: f32,
+    pub das_delay: f32,
+    pub arr_interval: f32,
+    pub hold_enabled: bool,
+    pub ghost_enabled: bool,
+    pub topout_grace: bool,
+}
+
+impl DifficultyPreset {
+    pub fn apply(&self, config: &mut GameConfig) {
+        config.start_level = self.start_level;
+        config.gravity_scale = self.gravity_scale;
+        config.lock_delay = self.lock_delay;
+        config.das_delay = self.das_delay;
+        config.arr_interval = self.arr_interval;
+        config.hold_enabled = self.hold_enabled;
+        config.ghost_enabled = self.ghost_enabled;
+        config.topout_grace = self.topout_grace;
+    }
+
+    /// false once any of the preset settings was changed by hand
+    pub fn matches(&self, config: &GameConfig) -> bool {
+        config.start_level == self.start_level
+            && config.gravity_scale == self.gravity_scale
+            && config.lock_delay == self.lock_delay
+            && config.das_delay == self.das_delay
+            && config.arr_interval == self.arr_interval
+            && config.hold_enabled == self.hold_enabled
+            && config.ghost_enabled == self.ghost_enabled
+            && config.topout_grace == self.topout_grace
+    }
+}
+
+#[derive(Debug, Resource, Clone)]
+pub struct GameConfig {
+    pub mode: GameMode,
+    pub show_grid_lines: bool,
+    pub theme: ThemeKind,
+    pub lock_behavior: LockBehavior,
+    /// warn when a brick is placed with more inputs than needed
+    pub finesse_feedback: bool,
+    /// seconds before a locked brick starts fading in invisible mode
+    pub invisible_fade_delay: f32,
+    /// tap and swipe with mouse or touch, on by default only in the web build, so desktop
+    /// clicks do nothing
+    pub pointer_controls: bool,
+    /// seconds a direction is held before it starts repeating
+    pub das_delay: f32,
+    /// seconds between repeated moves once DAS is charged
+    pub arr_interval: f32,
+    /// pressing the other direction while one is held moves at once and restarts DAS,
+    /// otherwise the old direction keeps repeating until released
+    pub das_direction_change_immediate: bool,
+    /// a held rotate key keeps rotating, F10 toggles it
+    pub rotate_repeat: bool,
+    /// seconds a rotate key is held before it starts repeating
+    pub rotate_repeat_delay: f32,
+    /// seconds between repeated rotations
+    pub rotate_repeat_interval: f32,
+    pub rotation_system: RotationSystem,
+    /// flash the cells of a brick white when it locks
+    pub lock_flash: bool,
+    /// seconds between garbage rows in rising mode
+    pub rising_garbage_interval: f32,
+    pub difficulty: Difficulty,
+    /// level a new game starts at, line clears count up from here
+    pub start_level: u32,
+    /// multiplies the gravity of every level
+    pub gravity_scale: f32,
+    /// seconds a resting brick waits before it locks
+    pub lock_delay: f32,
+    pub hold_enabled: bool,
+    /// cells per second while soft drop is held
+    pub soft_drop_speed: f32,
+    /// tint the background under the moveable brick down to the floor
+    pub show_trajectory: bool,
+    pub randomizer: RandomizerKind,
+    /// lines to clear in target mode
+    pub line_target: u32,
+    /// rotations that don't fit in place fail instead of trying wall kicks
+    pub strict_rotation: bool,
+    /// show the bricks left in the current 7-bag next to the board
+    pub show_bag_preview: bool,
+    pub scoring_mode: ScoringMode,
+    /// seconds full lines stay on the board before they are removed
+    pub clear_delay: f32,
+    pub ghost_smoothing: GhostSmoothing,
+    /// F3 cycles it, saved so it's back on the next start
+    pub ghost_opacity: GhostOpacity,
+    /// draw the next brick at full cell size in a large box instead of the small panel
+    pub big_next_preview: bool,
+    /// asset path of a picture shown behind the playfield, None keeps the solid colors
+    pub background_image: Option<String>,
+    /// earlier game raced against, picked in the menu
+    pub race_replay: RaceReplay,
+    /// draw the faint cells of the empty board, off leaves only the board background
+    pub show_background_bricks: bool,
+    pub stats_export: StatsExport,
+    /// a brick that can't fall is dimmed while it rests, and E locks it before the lock
+    /// delay runs out
+    pub soft_lock: bool,
+    pub ghost_enabled: bool,
+    /// keep the aspect of the game and fill the rest of the window with black bars
+    pub letterbox: bool,
+    /// flash the ghost when dropping there would cover an empty cell
+    pub ghost_hole_warning: bool,
+    /// bricks shown ahead in the next panel, 0 hides the panel, fewer scores more
+    pub preview_count: usize,
+    /// garbage rows filling the bottom of the board when a game starts, 0 for none
+    pub training_garbage_rows: u8,
+    pub training_garbage_gaps: GarbageGaps,
+    /// soft dropping into the stack locks the brick instead of waiting out the lock delay
+    pub soft_drop_locks_immediately: bool,
+    /// a hard drop locks the brick on the frame it lands, otherwise the lock delay still runs
+    /// and the brick can slide
+    pub hard_drop_locks: bool,
+    /// the sonic drop key drops the brick to the floor without locking it
+    pub sonic_drop: bool,
+    /// a held hard or sonic drop key drops each new brick once the spawn guard passed,
+    /// off needs a fresh press for every brick
+    pub hard_drop_auto_repeat: bool,
+    /// the board wobbles for a moment on level up, F1 toggles it
+    pub level_up_tilt: bool,
+    /// a new brick grows and fades in for a moment instead of popping up
+    pub spawn_animation: bool,
+    /// a held brick flies into the hold panel and the swapped in one flies out of it
+    pub hold_animation: bool,
+    /// on game over the stack falls off the screen before the summary shows up
+    pub gameover_fall: bool,
+    /// the first block-out of a game clears the top two rows instead of ending it, only
+    /// the casual preset turns it on
+    pub topout_grace: bool,
+    /// a bar under a resting brick shows the lock delay left, F6 toggles it
+    pub lock_bar: bool,
+    /// seconds counted down before a paused game plays again, 0 resumes right away, F7
+    /// cycles it
+    pub resume_countdown: u32,
+    /// bricks spawn in a random orientation instead of the usual one, F8 toggles it
+    pub random_spawn_rotation: bool,
+}
+
+impl GameConfig {
+    pub fn is_mirrored(&self) -> bool {
+        self.mode == GameMode::Mirror
+    }
+
+    /// percent of the line clear points earned with the current preview count
+    pub fn preview_multiplier_percent(&self) -> u32 {
+        preview_multiplier_percent(self.preview_count)
+    }
+
+    pub fn assists(&self) -> Assists {
+        Assists {
+            hold: self.hold_enabled,
+            ghost: self.ghost_enabled,
+        }
+    }
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            mode: GameMode::default(),
+            show_grid_lines: true,
+            theme: ThemeKind::default(),
+            lock_behavior: LockBehavior::default(),
+            finesse_feedback: false,
+            invisible_fade_delay: 3.0,
+            pointer_controls: cfg!(target_arch = "wasm32"),
+            das_delay: 0.17,
+            arr_interval: 0.05,
+            das_direction_change_immediate: true,
+            rotate_repeat: false,
+            rotate_repeat_delay: 0.3,
+            rotate_repeat_interval: 0.15,
+            rotation_system: RotationSystem::default(),
+            lock_flash: true,
+            rising_garbage_interval: 8.0,
+            difficulty: Difficulty::default(),
+            start_level: 1,
+            gravity_scale: 1.0,
+            lock_delay: 0.5,
+            hold_enabled: true,
+            soft_drop_speed: 20.0,
+            show_trajectory: false,
+            randomizer: RandomizerKind::default(),
+            line_target: 150,
+            strict_rotation: false,
+            show_bag_preview: false,
+            scoring_mode: ScoringMode::default(),
+            clear_delay: 0.4,
+            ghost_smoothing: GhostSmoothing::default(),
+            ghost_opacity: GhostOpacity::default(),
+            big_next_preview: false,
+            background_image: None,
+            race_replay: RaceReplay::Off,
+            show_background_bricks: true,
+            stats_export: StatsExport::default(),
+            soft_lock: false,
+            ghost_enabled: true,
+            letterbox: true,
+            ghost_hole_warning: false,
+            preview_count: 1,
+            training_garbage_rows: 0,
+            training_garbage_gaps: GarbageGaps::default(),
+            soft_drop_locks_immediately: false,
+            hard_drop_locks: true,
+            sonic_drop: true,
+            hard_drop_auto_repeat: false,
+            level_up_tilt: false,
+            spawn_animation: true,
+            hold_animation: true,
+            gameover_fall: true,
+            topout_grace: false,
+            lock_bar: false,
+            resume_countdown: 3,
+            random_spawn_rotation: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ceiling_row_follows_the_board_height() {
+        assert_eq!(BoardConfig::default().ceiling_row(), 16);
+        for height in BOARD_HEIGHT_MIN..=BOARD_HEIGHT_MAX {
+            let board_config = BoardConfig {
+                height,
+                ..BoardConfig::default()
+            };
+            let ceiling_row = board_config.ceiling_row();
+            assert!(ceiling_row > 0 && ceiling_row < height, "height {}", height);
+        }
+    }
+
+    #[test]
+    fn line_clear_points_follow_the_scoring_table() {
+        let table_arr = [
+            (ScoringMode::Guideline, [100, 300, 500, 800]),
+            (ScoringMode::Classic, [40, 100, 300, 1200]),
+        ];
+        for (scoring_mode, base_arr) in table_arr {
+            assert_eq!(scoring_mode.line_clear_points(0, 1), 0);
+            for (lines, base) in (1..=4).zip(base_arr) {
+                assert_eq!(scoring_mode.line_clear_points(lines, 1), base);
+                assert_eq!(scoring_mode.line_clear_points(lines, 3), base * 3);
+            }
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::VecDeque;
+
+use bevy::{prelude::*, utils::HashSet};
+
+use crate::{
+    action::{Action, ActionState},
+    app_state::AppState,
+    brick::{BrickState, RestartEvent, StableEvent},
+    config::{BoardConfig, GameConfig},
+    rotation::{Pivot, RotationSystem},
+    transition::in_transition,
+};
+
+pub struct FinessePlugin;
+
+impl Plugin for FinessePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Finesse>()
+            .add_event::<FinesseFaultEvent>()
+            .add_systems(Update, (finesse_toggle, finesse_reset))
+            .add_systems(
+                Update,
+                finesse_count_input.run_if(in_state(AppState::Gaming).and_then(not(in_transition))),
+            )
+            .add_systems(
+                PostUpdate,
+                finesse_check.run_if(in_state(AppState::Gaming).and_then(not(in_transition))),
+            );
+    }
+}
+
+#[derive(Debug, Resource, Default)]
+pub struct Finesse {
+    /// move and rotate presses for the current brick
+    pub input_count: u32,
+    /// extra presses over the minimum, summed over the game
+    pub error_count: u32,
+}
+
+/// sent on a lock that used more inputs than needed, with the number of extra inputs
+#[derive(Event)]
+pub struct FinesseFaultEvent(pub u32);
+
+fn finesse_toggle(keys: Res<Input<KeyCode>>, mut config: ResMut<GameConfig>) {
+    if keys.just_pressed(KeyCode::F) {
+        config.finesse_feedback = !config.finesse_feedback;
+    }
+}
+
+fn finesse_reset(mut finesse: ResMut<Finesse>, mut event_reader: EventReader<RestartEvent>) {
+    if event_reader.is_empty() {
+        return;
+    }
+    event_reader.clear();
+
+    *finesse = Finesse::default();
+}
+
+fn finesse_count_input(action_state: Res<ActionState>, mut finesse: ResMut<Finesse>) {
+    let input_arr = [
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::RotateCw,
+        Action::RotateCcw,
+    ];
+    finesse.input_count += action_state
+        .get_just_pressed()
+        .filter(|&action| input_arr.contains(action))
+        .count() as u32;
+}
+
+fn finesse_check(
+    brick_state: Res<BrickState>,
+    board_config: Res<BoardConfig>,
+    config: Res<GameConfig>,
+    mut finesse: ResMut<Finesse>,
+    mut stable_event_reader: EventReader<StableEvent>,
+    mut event_writer_fault: EventWriter<FinesseFaultEvent>,
+) {
+    if stable_event_reader.is_empty() {
+        return;
+    }
+    stable_event_reader.clear();
+
+    let input_count = finesse.input_count;
+    finesse.input_count = 0;
+
+    if !config.finesse_feedback {
+        return;
+    }
+
+    // placements reached by tucks or spins under the stack have no minimum from the top
+    let Some(min_input_count) = min_input_count(
+        brick_state.brick_type_index,
+        brick_state.brick_pos_origin.x,
+        brick_state.brick_shape_index,
+        &board_config,
+        config.rotation_system,
+        config.strict_rotation,
+    ) else {
+        return;
+    };
+
+    if input_count > min_input_count {
+        finesse.error_count += input_count - min_input_count;
+        event_writer_fault.send(FinesseFaultEvent(input_count - min_input_count));
+    }
+}
+
+/// least move/rotate presses to bring a brick from spawn to the given column and shape,
+/// found by BFS over (origin x, shape index) on an empty board, rotations take the first
+/// kick that stays inside the walls, or only the rotation in place when strict
+pub fn min_input_count(
+    brick_type_index: usize,
+    target_x: i8,
+    target_shape_index: usize,
+    board_config: &BoardConfig,
+    rotation_system: RotationSystem,
+    strict_rotation: bool,
+) -> Option<u32> {
+    let kick_count = if strict_rotation { 1 } else { usize::MAX };
+    let brick_shape_arr = &rotation_system.brick_type_arr()[brick_type_index].brick_shape_arr;
+    let is_inside = |x: i8, shape_index: usize| {
+        brick_shape_arr[shape_index]
+            .brick_pos_arr
+            .iter()
+            .all(|pos| x + pos.x >= 0 && x + pos.x < board_config.width)
+    };
+
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::new();
+    visited.insert((board_config.spawn_x(), 0));
+    queue.push_back((board_config.spawn_x(), 0, 0));
+
+    while let Some((x, shape_index, count)) = queue.pop_front() {
+        if x == target_x && shape_index == target_shape_index {
+            return Some(count);
+        }
+
+        let shape_index_rotated = (shape_index + 1) % brick_shape_arr.len();
+        let x_rotated = rotation_system
+            .kick_arr(brick_type_index, shape_index)
+            .iter()
+            .take(kick_count)
+            .map(|kick| x + kick.x)
+            .find(|&x_kicked| is_inside(x_kicked, shape_index_rotated))
+            .unwrap_or(x);
+
+        let shape_index_ccw = (shape_index + brick_shape_arr.len() - 1) % brick_shape_arr.len();
+        let x_ccw = rotation_system
+            .kick_ccw_arr(brick_type_index, shape_index_ccw)
+            .take(kick_count)
+            .map(|kick| x + kick.x)
+            .find(|&x_kicked| is_inside(x_kicked, shape_index_ccw))
+            .unwrap_or(x);
+
+        let next_arr = [
+            (x - 1, shape_index),
+            (x + 1, shape_index),
+            (x_rotated, shape_index_rotated),
+            (x_ccw, shape_index_ccw),
+        ];
+        let next_arr = if rotation_system.pivot(brick_type_index) == Pivot::Fixed {
+            &next_arr[..2]
+        } else {
+            &next_arr[..]
+        };
+        for &(x_next, shape_index_next) in next_arr {
+            if is_inside(x_next, shape_index_next) && visited.insert((x_next, shape_index_next)) {
+                queue.push_back((x_next, shape_index_next, count + 1));
+            }
+        }
+    }
+
+    None
+}
@@ -1,26 +1,99 @@
 use bevy::prelude::*;
+use rand::Rng;
 
-use crate::app_state::AppState;
+use crate::{
+    achievement::{Achievement, Achievements},
+    app_state::AppState,
+    attack::AttackStat,
+    board::StackHeightMax,
+    brick::BrickPos,
+    config::{GameConfig, GameMode},
+    finesse::Finesse,
+    pace::PaceStat,
+    randomizer::{GameSeed, LastGame},
+    score::{ClearStats, Cleared, Score},
+    theme::Theme,
+    transition::{in_transition, Transition},
+};
 
 pub struct GameoverPlugin;
 
 impl Plugin for GameoverPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, setup)
+        app.init_resource::<GameOverFall>()
+            .add_systems(Startup, setup)
             .add_systems(OnEnter(AppState::GameOver), on_gameover)
-            .add_systems(OnEnter(AppState::Gaming), on_gaming);
+            .add_systems(OnExit(AppState::GameOver), on_gameover_exit)
+            .add_systems(Update, theme_apply)
+            .add_systems(
+                Update,
+                (mode_select, assist_select)
+                    .run_if(in_state(AppState::GameOver).and_then(not(is_gameover_falling))),
+            )
+            .add_systems(
+                Update,
+                (menu_return, seed_replay).run_if(
+                    in_state(AppState::GameOver)
+                        .and_then(not(in_transition))
+                        .and_then(not(is_gameover_falling)),
+                ),
+            )
+            .add_systems(
+                Update,
+                gameover_fall_update
+                    .after(menu_return)
+                    .after(seed_replay)
+                    .run_if(in_state(AppState::GameOver).and_then(not(in_transition))),
+            );
     }
 }
 
+const FALL_DURATION: f32 = 1.5;
+/// pixels per second squared
+const FALL_GRAVITY: f32 = 1800.0;
+/// largest sideways speed of a falling brick, pixels per second
+const FALL_DRIFT: f32 = 80.0;
+
 #[derive(Debug, Component)]
 pub struct Gameover;
 
-pub fn setup(mut commands: Commands) {
+/// the stack falling off the screen before the summary shows up
+#[derive(Debug, Resource)]
+pub struct GameOverFall {
+    timer: Timer,
+    is_falling: bool,
+}
+
+impl Default for GameOverFall {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(FALL_DURATION, TimerMode::Once),
+            is_falling: false,
+        }
+    }
+}
+
+/// run condition, true until the stack is gone and the summary is shown
+pub fn is_gameover_falling(gameover_fall: Res<GameOverFall>) -> bool {
+    gameover_fall.is_falling
+}
+
+/// a brick of the finished game, no longer on the board, only its transform moves
+#[derive(Debug, Component)]
+pub struct FallingOff {
+    velocity: Vec2,
+}
+
+/// stats of the finished game, shown under the game over text
+#[derive(Debug, Component)]
+pub struct Summary;
+
+pub fn setup(mut commands: Commands, theme: Res<Theme>) {
     let mut textbundle = TextBundle::from_section(
         "Game Over, press r to restart",
         TextStyle {
             font_size: 80.0,
-            color: Color::rgb(1.0, 0.5, 0.0),
+            color: theme.gameover_text,
             ..default()
         },
     )
@@ -31,14 +104,243 @@ pub fn setup(mut commands: Commands) {
     });
     textbundle.visibility = Visibility::Hidden;
     commands.spawn((textbundle, Gameover));
+
+    let mut textbundle = TextBundle::from_section(
+        "",
+        TextStyle {
+            font_size: 40.0,
+            color: theme.gameover_text,
+            ..default()
+        },
+    )
+    .with_style(Style {
+        top: Val::Px(150.0),
+        left: Val::Px(250.0),
+        ..default()
+    });
+    textbundle.visibility = Visibility::Hidden;
+    commands.spawn((textbundle, Gameover, Summary));
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn on_gameover(
+    mut commands: Commands,
+    query_brick: Query<Entity, With<BrickPos>>,
+    mut gameover_fall: ResMut<GameOverFall>,
+    mut query_style: Query<&mut Visibility, With<Gameover>>,
+    mut query_title: Query<&mut Text, (With<Gameover>, Without<Summary>)>,
+    mut query_summary: Query<&mut Text, With<Summary>>,
+    config: Res<GameConfig>,
+    finesse: Res<Finesse>,
+    attack_stat: Res<AttackStat>,
+    achievements: Res<Achievements>,
+    cleared: Res<Cleared>,
+    pace_stat: Res<PaceStat>,
+    score: Res<Score>,
+    clear_stats: Res<ClearStats>,
+    game_seed: Res<GameSeed>,
+    stack_height_max: Res<StackHeightMax>,
+) {
+    // the summary is filled in now and only shown once the stack has fallen
+    if config.gameover_fall && !query_brick.is_empty() {
+        let mut rng = rand::thread_rng();
+        for entity in query_brick.iter() {
+            commands.entity(entity).insert(FallingOff {
+                velocity: Vec2::new(rng.gen_range(-FALL_DRIFT..=FALL_DRIFT), 0.0),
+            });
+        }
+        *gameover_fall = GameOverFall {
+            is_falling: true,
+            ..default()
+        };
+    } else {
+        for mut v in query_style.iter_mut() {
+            *v = Visibility::Visible;
+        }
+    }
+
+    query_title.single_mut().sections[0].value = if cleared.0 {
+        "Cleared! press r to restart".to_string()
+    } else {
+        "Game Over, press r to restart".to_string()
+    };
+
+    let mut line_arr = vec![
+        summary_mode_line(&config),
+        format!("Difficulty: {}", config.difficulty.name()),
+        summary_assist_line(&config),
+        format!(
+            "Attack: {} lines, {:.1} per minute",
+            attack_stat.lines,
+            attack_stat.per_minute()
+        ),
+        format!("Pace: {}", pace_stat.text(score.get())),
+        format!("Seed: {} (press z to play it again)", game_seed.current),
+    ];
+    if config.mode == GameMode::StackTrainer {
+        line_arr.push(format!("Max height: {} rows", stack_height_max.0));
+    }
+    line_arr.push("Clears:".to_string());
+    for (index, name) in ClearStats::NAME_ARR.iter().enumerate() {
+        line_arr.push(format!(
+            "  {}: {} for {} points",
+            name, clear_stats.count_arr[index], clear_stats.points_arr[index]
+        ));
+    }
+    line_arr.push(format!("  Perfect clear: {}", clear_stats.perfect_clears));
+    if config.finesse_feedback {
+        line_arr.push(format!("Finesse errors: {}", finesse.error_count));
+    }
+    line_arr.push(format!(
+        "Achievements: {}/{}",
+        achievements.unlocked.len(),
+        Achievement::ALL.len()
+    ));
+    for achievement in Achievement::ALL {
+        let mark = if achievements.unlocked.contains(&achievement) {
+            "x"
+        } else {
+            " "
+        };
+        line_arr.push(format!("  [{}] {}", mark, achievement.name()));
+    }
+    line_arr.push("Press enter for the menu".to_string());
+    query_summary.single_mut().sections[0].value = line_arr.join("\n");
+}
+
+fn summary_mode_line(config: &GameConfig) -> String {
+    format!("Mode: {} (press m to change)", config.mode.name())
+}
+
+fn summary_assist_line(config: &GameConfig) -> String {
+    format!(
+        "Assists: {} (press o / i to toggle hold / ghost)",
+        config.assists().label()
+    )
+}
+
+fn summary_line_set(
+    query_summary: &mut Query<&mut Text, With<Summary>>,
+    index: usize,
+    line: String,
+) {
+    let mut text = query_summary.single_mut();
+    let mut line_arr = text.sections[0]
+        .value
+        .lines()
+        .map(String::from)
+        .collect::<Vec<String>>();
+    line_arr[index] = line;
+    text.sections[0].value = line_arr.join("\n");
+}
+
+/// the mode applies to the next game
+pub fn mode_select(
+    keys: Res<Input<KeyCode>>,
+    mut config: ResMut<GameConfig>,
+    mut query_summary: Query<&mut Text, With<Summary>>,
+) {
+    if !keys.just_pressed(KeyCode::M) {
+        return;
+    }
+    config.mode = config.mode.next();
+
+    summary_line_set(&mut query_summary, 0, summary_mode_line(&config));
 }
 
-pub fn on_gameover(mut query_style: Query<&mut Visibility, With<Gameover>>) {
-    let mut v = query_style.single_mut();
-    *v = Visibility::Visible;
+/// difficulty and settings are picked on the menu
+pub fn menu_return(keys: Res<Input<KeyCode>>, mut transition: ResMut<Transition>) {
+    if keys.just_pressed(KeyCode::Return) {
+        transition.start(AppState::Menu, "back to menu");
+    }
 }
 
-pub fn on_gaming(mut query_style: Query<&mut Visibility, With<Gameover>>) {
-    let mut v = query_style.single_mut();
-    *v = Visibility::Hidden;
+/// hold and ghost for the next game, the leaderboard keeps runs without them apart
+pub fn assist_select(
+    keys: Res<Input<KeyCode>>,
+    mut config: ResMut<GameConfig>,
+    mut query_summary: Query<&mut Text, With<Summary>>,
+) {
+    if keys.just_pressed(KeyCode::O) {
+        config.hold_enabled = !config.hold_enabled;
+    } else if keys.just_pressed(KeyCode::I) {
+        config.ghost_enabled = !config.ghost_enabled;
+    } else {
+        return;
+    }
+
+    summary_line_set(&mut query_summary, 2, summary_assist_line(&config));
+}
+
+/// same seed and mode as the game that just ended
+pub fn seed_replay(
+    keys: Res<Input<KeyCode>>,
+    last_game: Option<Res<LastGame>>,
+    mut game_seed: ResMut<GameSeed>,
+    mut config: ResMut<GameConfig>,
+    mut transition: ResMut<Transition>,
+) {
+    let Some(last_game) = last_game else {
+        return;
+    };
+    if !keys.just_pressed(KeyCode::Z) {
+        return;
+    }
+
+    last_game.replay(&mut game_seed, &mut config);
+    transition.start(AppState::Gaming, "replay seed");
+}
+
+/// collision is ignored, the bricks drop through the floor, any key skips to the summary
+pub fn gameover_fall_update(
+    mut commands: Commands,
+    mut query_falling: Query<(Entity, &mut FallingOff, &mut Transform)>,
+    mut query_style: Query<&mut Visibility, With<Gameover>>,
+    mut gameover_fall: ResMut<GameOverFall>,
+    keys: Res<Input<KeyCode>>,
+    time: Res<Time>,
+) {
+    if !gameover_fall.is_falling {
+        return;
+    }
+    gameover_fall.timer.tick(time.delta());
+
+    for (_, mut falling_off, mut transform) in query_falling.iter_mut() {
+        falling_off.velocity.y -= FALL_GRAVITY * time.delta_seconds();
+        transform.translation += (falling_off.velocity * time.delta_seconds()).extend(0.0);
+    }
+
+    if !gameover_fall.timer.finished() && keys.get_just_pressed().next().is_none() {
+        return;
+    }
+
+    for (entity, _, _) in query_falling.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    gameover_fall.is_falling = false;
+    for mut v in query_style.iter_mut() {
+        *v = Visibility::Visible;
+    }
+}
+
+pub fn on_gameover_exit(
+    mut query_style: Query<&mut Visibility, With<Gameover>>,
+    mut gameover_fall: ResMut<GameOverFall>,
+) {
+    for mut v in query_style.iter_mut() {
+        *v = Visibility::Hidden;
+    }
+    gameover_fall.is_falling = false;
+}
+
+pub fn theme_apply(theme: Res<Theme>, mut query_text: Query<&mut Text, With<Gameover>>) {
+    if !theme.is_changed() {
+        return;
+    }
+
+    for mut text in query_text.iter_mut() {
+        for section in text.sections.iter_mut() {
+            section.style.color = theme.gameover_text;
+        }
+    }
 }
@@ -0,0 +1,168 @@
+use bevy::{prelude::*, utils::HashSet};
+
+use crate::{
+    board::{Board, Cell},
+    brick::{
+        brick_apply_new_pos, get_brick_pos_xy, BrickKind, BrickMoveable, BrickPos, ToppedOut,
+        BRICK_WIDTH,
+    },
+    config::{BoardConfig, GameConfig, GhostOpacity, GhostSmoothing},
+    root::OnBoard,
+    storage,
+    texture::{brick_sprite_bundle, BrickTexture},
+    theme::Theme,
+};
+
+pub struct GhostPlugin;
+
+impl Plugin for GhostPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PreStartup, ghost_opacity_load)
+            .add_systems(Startup, setup_ghost)
+            .add_systems(
+                Update,
+                (
+                    ghost_smoothing_toggle,
+                    ghost_hole_warning_toggle,
+                    ghost_opacity_toggle,
+                    ghost_update.after(brick_apply_new_pos),
+                ),
+            );
+    }
+}
+
+const GHOST_OPACITY_FILE: &str = "ghost_opacity.json";
+/// how fast a smoothed ghost closes in on the landing spot, per second
+const GHOST_LERP_RATE: f32 = 30.0;
+const GHOST_Z: f32 = 0.9;
+/// blinks per second of a ghost that would leave a hole
+const GHOST_WARNING_BLINK: f32 = 4.0;
+
+/// one cell of the outline showing where the moveable brick would land
+#[derive(Debug, Component)]
+pub struct GhostCell;
+
+fn setup_ghost(
+    mut commands: Commands,
+    brick_texture: Res<BrickTexture>,
+    theme: Res<Theme>,
+    config: Res<GameConfig>,
+) {
+    for _ in 0..4 {
+        let mut bundle = brick_sprite_bundle(
+            &brick_texture,
+            theme.brick_arr[0].with_a(config.ghost_opacity.alpha()),
+            BRICK_WIDTH as f32,
+            Transform::from_xyz(0.0, 0.0, GHOST_Z),
+        );
+        bundle.0.visibility = Visibility::Hidden;
+        commands.spawn((bundle, GhostCell, OnBoard));
+    }
+}
+
+fn ghost_smoothing_toggle(keys: Res<Input<KeyCode>>, mut config: ResMut<GameConfig>) {
+    if keys.just_pressed(KeyCode::J) {
+        config.ghost_smoothing = config.ghost_smoothing.next();
+    }
+}
+
+fn ghost_opacity_load(mut config: ResMut<GameConfig>) {
+    if let Some(ghost_opacity) = storage::load::<GhostOpacity>(GHOST_OPACITY_FILE) {
+        config.ghost_opacity = ghost_opacity;
+    }
+}
+
+fn ghost_opacity_toggle(keys: Res<Input<KeyCode>>, mut config: ResMut<GameConfig>) {
+    if keys.just_pressed(KeyCode::F3) {
+        config.ghost_opacity = config.ghost_opacity.next();
+        storage::save(GHOST_OPACITY_FILE, &config.ghost_opacity);
+    }
+}
+
+fn ghost_hole_warning_toggle(keys: Res<Input<KeyCode>>, mut config: ResMut<GameConfig>) {
+    if keys.just_pressed(KeyCode::U) {
+        config.ghost_hole_warning = !config.ghost_hole_warning;
+    }
+}
+
+/// true when locking the brick at the landing spot adds holes, lines it completes are
+/// cleared first so a placement that fills the row under it doesn't count
+fn is_creating_hole(board: &Board, brick_pos_arr: &[BrickPos], kind: usize) -> bool {
+    let mut board_after = board.clone();
+    for pos in brick_pos_arr {
+        board_after.set(pos.x, pos.y, Some(Cell::Brick(kind)));
+    }
+    board_after.clear_full_lines();
+    board_after.holes() > board.holes()
+}
+
+/// recomputed every frame after moves and rotations are applied, so the landing spot is
+/// never stale, a ghost that was hidden snaps to it even when smoothing
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn ghost_update(
+    query_brick_movable: Query<(&BrickPos, &BrickKind), (With<BrickMoveable>, Without<ToppedOut>)>,
+    query_brick_stable: Query<&BrickPos, Without<BrickMoveable>>,
+    mut query_ghost: Query<(&mut Transform, &mut Sprite, &mut Visibility), With<GhostCell>>,
+    board: Res<Board>,
+    board_config: Res<BoardConfig>,
+    config: Res<GameConfig>,
+    theme: Res<Theme>,
+    time: Res<Time>,
+) {
+    let brick_move_arr = query_brick_movable.iter().collect::<Vec<_>>();
+    if brick_move_arr.is_empty()
+        || !config.ghost_enabled
+        || config.ghost_opacity == GhostOpacity::Off
+    {
+        for (_, _, mut visibility) in query_ghost.iter_mut() {
+            *visibility = Visibility::Hidden;
+        }
+        return;
+    }
+
+    let brick_stable_set = query_brick_stable.iter().collect::<HashSet<&BrickPos>>();
+    let fits = |drop: i8| {
+        brick_move_arr.iter().all(|(pos, _)| {
+            let pos_drop = BrickPos::new(pos.x, pos.y - drop);
+            pos_drop.y >= 0 && !brick_stable_set.contains(&pos_drop)
+        })
+    };
+    let mut drop = 0;
+    while fits(drop + 1) {
+        drop += 1;
+    }
+
+    let is_warning = config.ghost_hole_warning && {
+        let landing_arr = brick_move_arr
+            .iter()
+            .map(|(pos, _)| BrickPos::new(pos.x, pos.y - drop))
+            .collect::<Vec<_>>();
+        is_creating_hole(&board, &landing_arr, brick_move_arr[0].1 .0)
+    };
+    let is_warning_lit = is_warning && (time.elapsed_seconds() * GHOST_WARNING_BLINK).fract() < 0.5;
+
+    let lerp = match config.ghost_smoothing {
+        GhostSmoothing::Instant => 1.0,
+        GhostSmoothing::Lerp => 1.0 - (-GHOST_LERP_RATE * time.delta_seconds()).exp(),
+    };
+
+    for ((mut transform, mut sprite, mut visibility), (pos, kind)) in
+        query_ghost.iter_mut().zip(brick_move_arr.iter())
+    {
+        let xy = get_brick_pos_xy(pos.x, pos.y - drop, &board_config, config.is_mirrored());
+        let target = Vec2::new(xy.0 as f32, xy.1 as f32);
+        if *visibility == Visibility::Hidden {
+            transform.translation = target.extend(GHOST_Z);
+        } else {
+            let current = transform.translation.truncate();
+            transform.translation = current.lerp(target, lerp).extend(GHOST_Z);
+        }
+        let color = if is_warning_lit {
+            theme.danger
+        } else {
+            theme.brick_arr[kind.0]
+        };
+        sprite.color = color.with_a(config.ghost_opacity.alpha());
+        *visibility = Visibility::Inherited;
+    }
+}
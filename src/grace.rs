@@ -0,0 +1,120 @@
+use bevy::prelude::*;
+
+use crate::{
+    app_state::AppState,
+    brick::{
+        brick_gen, get_brick_pos_xy, BrickMoveable, BrickPos, BrickState, GameOverEvent,
+        RestartEvent, SpawnEvent, ToppedOut,
+    },
+    config::{BoardConfig, GameConfig},
+    transition::{in_transition, Transition},
+};
+
+pub struct GracePlugin;
+
+impl Plugin for GracePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TopOutGrace>()
+            .add_event::<TopOutGraceEvent>()
+            .add_systems(Update, grace_reset)
+            .add_systems(
+                Update,
+                // the cleared rows are gone before `brick_gen` tries the spawn again
+                (topout_grace, apply_deferred)
+                    .chain()
+                    .before(brick_gen)
+                    .run_if(in_state(AppState::Gaming).and_then(not(in_transition))),
+            );
+    }
+}
+
+/// rows cleared off the top of the stack when the grace is used
+const GRACE_ROWS: usize = 2;
+
+/// the one block-out per game forgiven when `GameConfig::topout_grace` is on
+#[derive(Debug, Resource, Default)]
+pub struct TopOutGrace {
+    pub is_used: bool,
+}
+
+/// the grace cleared the top of the stack, the board is rebuilt and the player told
+#[derive(Event)]
+pub struct TopOutGraceEvent;
+
+fn grace_reset(mut grace: ResMut<TopOutGrace>, mut event_reader: EventReader<RestartEvent>) {
+    if event_reader.is_empty() {
+        return;
+    }
+    event_reader.clear();
+
+    *grace = TopOutGrace::default();
+}
+
+/// runs on the frame after the block-out, once the topped out brick is spawned, either ends
+/// the game or takes the topped out brick back, clears the two highest rows of the stack and
+/// spawns the same brick again, a spawn that is still blocked then ends the game
+#[allow(clippy::too_many_arguments)]
+fn topout_grace(
+    mut commands: Commands,
+    query_topped_out: Query<Entity, With<ToppedOut>>,
+    mut query_brick_stable: Query<(Entity, &mut BrickPos, &mut Transform), Without<BrickMoveable>>,
+    mut grace: ResMut<TopOutGrace>,
+    mut transition: ResMut<Transition>,
+    mut game_over_event_reader: EventReader<GameOverEvent>,
+    mut spawn_event_writer: EventWriter<SpawnEvent>,
+    mut grace_event_writer: EventWriter<TopOutGraceEvent>,
+    brick_state: Res<BrickState>,
+    board_config: Res<BoardConfig>,
+    config: Res<GameConfig>,
+) {
+    if game_over_event_reader.is_empty() {
+        return;
+    }
+    game_over_event_reader.clear();
+
+    if !config.topout_grace || grace.is_used {
+        transition.start(AppState::GameOver, "block-out");
+        return;
+    }
+    grace.is_used = true;
+
+    for entity in query_topped_out.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let mut y_arr = query_brick_stable
+        .iter()
+        .map(|(_, brick_pos, _)| brick_pos.y)
+        .collect::<Vec<i8>>();
+    y_arr.sort_unstable();
+    y_arr.dedup();
+    let y_to_remove = y_arr.iter().rev().take(GRACE_ROWS).collect::<Vec<&i8>>();
+
+    for (entity, mut brick_pos, mut transform) in query_brick_stable.iter_mut() {
+        if y_to_remove.contains(&&brick_pos.y) {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        // only a row between the two removed ones has anything to drop
+        let drop = y_to_remove.iter().filter(|&&&y| y < brick_pos.y).count() as i8;
+        if drop == 0 {
+            continue;
+        }
+        brick_pos.y -= drop;
+        let xy = get_brick_pos_xy(
+            brick_pos.x,
+            brick_pos.y,
+            &board_config,
+            config.is_mirrored(),
+        );
+        transform.translation.x = xy.0 as f32;
+        transform.translation.y = xy.1 as f32;
+    }
+
+    spawn_event_writer.send(SpawnEvent {
+        brick_type_index: Some(brick_state.brick_type_index),
+        ..default()
+    });
+    grace_event_writer.send(TopOutGraceEvent);
+}
@@ -0,0 +1,112 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    board::Board,
+    brick::{
+        brick_apply_new_pos, get_brick_pos_xy, BrickMoveable, BrickState, ToppedOut, BRICK_WIDTH,
+    },
+    config::{BoardConfig, GameConfig},
+    root::OnBoard,
+    sim::{reachable_arr, Piece},
+};
+
+/// the cells the moveable brick can reach, by the placement search of `sim`, only built with
+/// the `debug` feature
+pub struct HeatmapPlugin;
+
+impl Plugin for HeatmapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Heatmap>().add_systems(
+            Update,
+            (heatmap_toggle, heatmap_update.after(brick_apply_new_pos)).chain(),
+        );
+    }
+}
+
+/// under the ghost and the live bricks
+const HEATMAP_Z: f32 = 0.8;
+/// cells the brick can pass through
+const HEATMAP_PASS_COLOR: Color = Color::rgba(0.2, 0.6, 1.0, 0.25);
+/// cells the brick can lock in
+const HEATMAP_LOCK_COLOR: Color = Color::rgba(1.0, 0.7, 0.1, 0.45);
+
+#[derive(Debug, Resource, Default)]
+pub struct Heatmap {
+    pub is_shown: bool,
+}
+
+#[derive(Debug, Component)]
+pub struct HeatCell;
+
+fn heatmap_toggle(keys: Res<Input<KeyCode>>, mut heatmap: ResMut<Heatmap>) {
+    if keys.just_pressed(KeyCode::F11) {
+        heatmap.is_shown = !heatmap.is_shown;
+    }
+}
+
+/// redrawn when the brick or the stack changes, and cleared while no brick is moveable so
+/// a locked brick takes its heatmap with it
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn heatmap_update(
+    mut commands: Commands,
+    query_cell: Query<Entity, With<HeatCell>>,
+    query_brick_movable: Query<(), (With<BrickMoveable>, Without<ToppedOut>)>,
+    heatmap: Res<Heatmap>,
+    brick_state: Res<BrickState>,
+    board: Res<Board>,
+    board_config: Res<BoardConfig>,
+    config: Res<GameConfig>,
+) {
+    let is_shown = heatmap.is_shown && !query_brick_movable.is_empty();
+    if is_shown
+        && !heatmap.is_changed()
+        && !brick_state.is_changed()
+        && !board.is_changed()
+        && !query_cell.is_empty()
+    {
+        return;
+    }
+
+    for entity in query_cell.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    if !is_shown {
+        return;
+    }
+
+    let piece = Piece {
+        brick_type_index: brick_state.brick_type_index,
+        brick_shape_index: brick_state.brick_shape_index,
+        origin: brick_state.brick_pos_origin,
+    };
+    // a cell any lock spot covers shows as one, over the cells only passed through
+    let mut cell_map = HashMap::new();
+    for (piece, is_resting) in reachable_arr(&board, config.rotation_system, &piece) {
+        for pos in piece.brick_pos_arr(config.rotation_system) {
+            let is_lock = cell_map.entry(pos).or_insert(false);
+            *is_lock |= is_resting;
+        }
+    }
+
+    for (pos, is_lock) in cell_map {
+        let color = if is_lock {
+            HEATMAP_LOCK_COLOR
+        } else {
+            HEATMAP_PASS_COLOR
+        };
+        let xy = get_brick_pos_xy(pos.x, pos.y, &board_config, config.is_mirrored());
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color,
+                    custom_size: Some(Vec2::splat(BRICK_WIDTH as f32)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(xy.0 as f32, xy.1 as f32, HEATMAP_Z),
+                ..default()
+            },
+            HeatCell,
+            OnBoard,
+        ));
+    }
+}
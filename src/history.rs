@@ -0,0 +1,138 @@
+use bevy::prelude::*;
+use serde::Serialize;
+
+use crate::{
+    app_state::AppState,
+    config::{GameConfig, StatsExport},
+    pace::PaceStat,
+    score::{ClearStats, Level, Lines, Score},
+    storage,
+};
+
+pub struct HistoryPlugin;
+
+impl Plugin for HistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::GameOver), history_append);
+    }
+}
+
+const HISTORY_CSV_FILE: &str = "history.csv";
+/// one json object per line so a game can be appended without reading the rest back
+const HISTORY_JSON_FILE: &str = "history.jsonl";
+
+/// stats of one finished game, a row of the history file
+#[derive(Debug, Serialize)]
+pub struct GameRecord {
+    /// utc, empty where the clock can't be read
+    pub date: String,
+    pub mode: String,
+    pub score: u32,
+    pub lines: u32,
+    pub level: u32,
+    /// seconds played
+    pub time: f32,
+    pub pieces: u32,
+    /// share of the cleared lines that came from tetrises
+    pub tetris_rate: f32,
+}
+
+impl GameRecord {
+    const CSV_HEADER: &'static str = "date,mode,score,lines,level,time,pieces,tetris_rate";
+
+    fn to_csv(&self) -> String {
+        format!(
+            "{},{},{},{},{},{:.2},{},{:.3}",
+            self.date,
+            self.mode,
+            self.score,
+            self.lines,
+            self.level,
+            self.time,
+            self.pieces,
+            self.tetris_rate
+        )
+    }
+}
+
+fn history_append(
+    config: Res<GameConfig>,
+    score: Res<Score>,
+    lines: Res<Lines>,
+    level: Res<Level>,
+    pace_stat: Res<PaceStat>,
+    clear_stats: Res<ClearStats>,
+) {
+    if config.stats_export == StatsExport::Off {
+        return;
+    }
+
+    let tetris_rate = if lines.0 == 0 {
+        0.0
+    } else {
+        (clear_stats.count_arr[3] * 4) as f32 / lines.0 as f32
+    };
+    let record = GameRecord {
+        date: utc_date(),
+        mode: config.mode.name().to_string(),
+        score: score.get(),
+        lines: lines.0,
+        level: level.0,
+        time: pace_stat.elapsed,
+        pieces: pace_stat.pieces,
+        tetris_rate,
+    };
+
+    match config.stats_export {
+        StatsExport::Off => {}
+        StatsExport::Csv => storage::append(
+            HISTORY_CSV_FILE,
+            Some(GameRecord::CSV_HEADER),
+            &record.to_csv(),
+        ),
+        StatsExport::Json => match serde_json::to_string(&record) {
+            Ok(line) => storage::append(HISTORY_JSON_FILE, None, &line),
+            Err(err) => warn!("failed to write game record: {}", err),
+        },
+    }
+}
+
+/// `YYYY-MM-DD hh:mm:ss`, the std clock isn't available on the web
+#[cfg(not(target_arch = "wasm32"))]
+fn utc_date() -> String {
+    let Ok(duration) = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) else {
+        return String::new();
+    };
+    let secs = duration.as_secs() as i64;
+    let (year, month, day) = civil_from_days(secs.div_euclid(86400));
+    let secs_of_day = secs.rem_euclid(86400);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day % 3600 / 60,
+        secs_of_day % 60
+    )
+}
+
+#[cfg(target_arch = "wasm32")]
+fn utc_date() -> String {
+    String::new()
+}
+
+/// days since 1970-01-01 to a gregorian date, Howard Hinnant's `civil_from_days`
+#[cfg(not(target_arch = "wasm32"))]
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
@@ -0,0 +1,261 @@
+use bevy::prelude::*;
+
+use crate::{
+    brick::{brick_apply_new_pos, get_brick_pos_xy, BrickPos, RestartEvent, BRICK_WIDTH},
+    config::{BoardConfig, GameConfig},
+    texture::{brick_sprite_bundle, BrickTexture},
+    theme::Theme,
+};
+
+pub struct HoldPlugin;
+
+impl Plugin for HoldPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Hold>()
+            .add_systems(Startup, setup_panel)
+            .add_systems(
+                Update,
+                (hold_reset, hold_panel_update, hold_panel_flash).chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    hold_swap_out_update,
+                    hold_swap_in_update.after(brick_apply_new_pos),
+                ),
+            );
+    }
+}
+
+const PANEL_X: f32 = -260.0;
+const PANEL_Y: f32 = 250.0;
+const PANEL_SIZE: f32 = 110.0;
+const PANEL_CELL_WIDTH: f32 = 22.0;
+const PANEL_CELL_PADDING: f32 = 1.5;
+const PANEL_FLASH: f32 = 0.25;
+const SWAP_DURATION: f32 = 0.12;
+/// scale of a board cell drawn at panel cell size
+const SWAP_SCALE: f32 = PANEL_CELL_WIDTH / BRICK_WIDTH as f32;
+
+/// the held brick type, `can_hold` is false once hold is used until the next lock
+#[derive(Debug, Resource)]
+pub struct Hold {
+    pub brick_type_index: Option<usize>,
+    pub can_hold: bool,
+}
+
+impl Default for Hold {
+    fn default() -> Self {
+        Self {
+            brick_type_index: None,
+            can_hold: true,
+        }
+    }
+}
+
+#[derive(Debug, Component)]
+pub struct HoldPanel {
+    flash: Timer,
+}
+
+#[derive(Debug, Component)]
+pub struct HoldCell;
+
+/// sprite of a cell of the brick put on hold, shrinking into the panel, it is no longer
+/// part of the board
+#[derive(Debug, Component)]
+pub struct HoldSwapOut {
+    timer: Timer,
+    from: Vec3,
+}
+
+impl HoldSwapOut {
+    pub fn new(from: Vec3) -> Self {
+        Self {
+            timer: Timer::from_seconds(SWAP_DURATION, TimerMode::Once),
+            from,
+        }
+    }
+}
+
+/// cell of the brick swapped in, its `BrickPos` is live from the start and only the sprite
+/// travels from the panel to it
+#[derive(Debug, Component)]
+pub struct HoldSwapIn {
+    timer: Timer,
+}
+
+impl Default for HoldSwapIn {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(SWAP_DURATION, TimerMode::Once),
+        }
+    }
+}
+
+fn setup_panel(mut commands: Commands, theme: Res<Theme>) {
+    let mut flash = Timer::from_seconds(PANEL_FLASH, TimerMode::Once);
+    flash.tick(flash.duration());
+
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: theme.board_background,
+                custom_size: Some(Vec2::new(PANEL_SIZE, PANEL_SIZE)),
+                ..default()
+            },
+            transform: Transform::from_xyz(PANEL_X, PANEL_Y, 0.1),
+            ..default()
+        },
+        HoldPanel { flash },
+    ));
+}
+
+fn hold_reset(mut hold: ResMut<Hold>, mut event_reader: EventReader<RestartEvent>) {
+    if event_reader.is_empty() {
+        return;
+    }
+    event_reader.clear();
+
+    *hold = Hold::default();
+}
+
+/// redraw the held brick, grayed out while hold can't be used
+#[allow(clippy::too_many_arguments)]
+fn hold_panel_update(
+    mut commands: Commands,
+    query_cell: Query<Entity, With<HoldCell>>,
+    mut query_panel: Query<(&mut HoldPanel, &mut Visibility)>,
+    hold: Res<Hold>,
+    theme: Res<Theme>,
+    brick_texture: Res<BrickTexture>,
+    config: Res<GameConfig>,
+    mut brick_type_index_last: Local<Option<usize>>,
+) {
+    if !hold.is_changed() && !theme.is_changed() && !config.is_changed() {
+        return;
+    }
+
+    for entity in query_cell.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let (mut panel, mut visibility) = query_panel.single_mut();
+    // no panel at all when hold is turned off
+    *visibility = if config.hold_enabled {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    if !config.hold_enabled {
+        return;
+    }
+
+    if hold.brick_type_index != *brick_type_index_last {
+        *brick_type_index_last = hold.brick_type_index;
+        if hold.brick_type_index.is_some() {
+            panel.flash.reset();
+        }
+    }
+
+    let Some(brick_type_index) = hold.brick_type_index else {
+        return;
+    };
+
+    let color = if hold.can_hold {
+        theme.brick_arr[brick_type_index]
+    } else {
+        theme.hold_disabled
+    };
+
+    let brick_shape = &config.rotation_system.brick_type_arr()[brick_type_index].brick_shape_arr[0];
+    // shown the way it looks on the board
+    let mirror_sign = if config.is_mirrored() { -1.0 } else { 1.0 };
+
+    for offset in brick_shape.centered_arr() {
+        let x = PANEL_X + offset.x * mirror_sign * PANEL_CELL_WIDTH;
+        let y = PANEL_Y + offset.y * PANEL_CELL_WIDTH;
+        commands.spawn((
+            brick_sprite_bundle(
+                &brick_texture,
+                color,
+                PANEL_CELL_WIDTH - PANEL_CELL_PADDING * 2.0,
+                Transform::from_xyz(x, y, 0.2),
+            ),
+            HoldCell,
+        ));
+    }
+}
+
+fn hold_swap_out_update(
+    mut commands: Commands,
+    mut query_swap: Query<(Entity, &mut HoldSwapOut, &mut Transform, &mut Sprite)>,
+    time: Res<Time>,
+) {
+    let panel = Vec3::new(PANEL_X, PANEL_Y, 0.2);
+    for (entity, mut swap, mut transform, mut sprite) in query_swap.iter_mut() {
+        swap.timer.tick(time.delta());
+
+        let t = swap.timer.percent();
+        transform.translation = swap.from.lerp(panel, t);
+        transform.scale = Vec3::splat(1.0 + (SWAP_SCALE - 1.0) * t);
+        sprite.color.set_a(1.0 - t);
+
+        if swap.timer.finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// runs after the move is applied so the cell heads for where the brick is now, moves made
+/// during the swap aren't lost
+fn hold_swap_in_update(
+    mut commands: Commands,
+    mut query_swap: Query<(Entity, &mut HoldSwapIn, &mut Transform, &BrickPos)>,
+    board_config: Res<BoardConfig>,
+    config: Res<GameConfig>,
+    time: Res<Time>,
+) {
+    let panel = Vec2::new(PANEL_X, PANEL_Y);
+    for (entity, mut swap, mut transform, brick_pos) in query_swap.iter_mut() {
+        swap.timer.tick(time.delta());
+
+        let t = swap.timer.percent();
+        let xy = get_brick_pos_xy(
+            brick_pos.x,
+            brick_pos.y,
+            &board_config,
+            config.is_mirrored(),
+        );
+        let target = Vec2::new(xy.0 as f32, xy.1 as f32);
+        transform.translation = panel.lerp(target, t).extend(transform.translation.z);
+        transform.scale = Vec3::splat(SWAP_SCALE + (1.0 - SWAP_SCALE) * t);
+
+        if swap.timer.finished() {
+            commands.entity(entity).remove::<HoldSwapIn>();
+        }
+    }
+}
+
+/// briefly light up the panel when a brick is swapped in
+fn hold_panel_flash(
+    mut query_panel: Query<(&mut HoldPanel, &mut Sprite)>,
+    theme: Res<Theme>,
+    time: Res<Time>,
+) {
+    for (mut panel, mut sprite) in query_panel.iter_mut() {
+        if panel.flash.finished() && !theme.is_changed() {
+            continue;
+        }
+        panel.flash.tick(time.delta());
+
+        let t = panel.flash.percent_left();
+        let from = theme.board_background;
+        let to = theme.board_border;
+        sprite.color = Color::rgb(
+            from.r() + (to.r() - from.r()) * t,
+            from.g() + (to.g() - from.g()) * t,
+            from.b() + (to.b() - from.b()) * t,
+        );
+    }
+}
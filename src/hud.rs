@@ -0,0 +1,116 @@
+use bevy::prelude::*;
+
+use crate::{
+    attack::AttackStat,
+    score::{combo_update, Combo},
+    theme::Theme,
+};
+
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<StatsHudVisible>()
+            .add_systems(Startup, setup_ui)
+            .add_systems(
+                Update,
+                (stats_hud_toggle, stats_hud_visibility, theme_apply).chain(),
+            )
+            .add_systems(
+                Update,
+                hud_text_update
+                    .after(combo_update)
+                    .after(stats_hud_visibility),
+            );
+    }
+}
+
+/// level, lines, pace, combo and attack readouts are shown, the score always is
+#[derive(Debug, Resource, Default)]
+pub struct StatsHudVisible(pub bool);
+
+/// a stat readout hidden along with the stats hud
+#[derive(Debug, Component)]
+pub struct StatsHud;
+
+#[derive(Debug, Component)]
+pub struct HudText;
+
+fn hud_text(combo: u32, attack_stat: &AttackStat) -> String {
+    format!(
+        "Combo: {}  Attack: {} ({:.1}/min)",
+        combo,
+        attack_stat.lines,
+        attack_stat.per_minute()
+    )
+}
+
+fn setup_ui(mut commands: Commands, theme: Res<Theme>) {
+    commands.spawn((
+        TextBundle::from_section(
+            hud_text(0, &AttackStat::default()),
+            TextStyle {
+                font_size: 30.0,
+                color: theme.text,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            top: Val::Px(500.0),
+            left: Val::Px(100.0),
+            ..default()
+        }),
+        HudText,
+        StatsHud,
+    ));
+}
+
+fn stats_hud_toggle(keys: Res<Input<KeyCode>>, mut stats_hud_visible: ResMut<StatsHudVisible>) {
+    if keys.just_pressed(KeyCode::F9) {
+        stats_hud_visible.0 = !stats_hud_visible.0;
+    }
+}
+
+fn stats_hud_visibility(
+    mut query_hud: Query<&mut Visibility, With<StatsHud>>,
+    stats_hud_visible: Res<StatsHudVisible>,
+) {
+    if !stats_hud_visible.is_changed() {
+        return;
+    }
+
+    for mut visibility in query_hud.iter_mut() {
+        *visibility = if stats_hud_visible.0 {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+/// the rate changes with the play time every frame, so a hidden readout isn't formatted,
+/// it catches up when shown
+fn hud_text_update(
+    mut query_text: Query<&mut Text, With<HudText>>,
+    combo: Res<Combo>,
+    attack_stat: Res<AttackStat>,
+    stats_hud_visible: Res<StatsHudVisible>,
+) {
+    if !stats_hud_visible.0
+        || (!combo.is_changed() && !attack_stat.is_changed() && !stats_hud_visible.is_changed())
+    {
+        return;
+    }
+
+    query_text.single_mut().sections[0].value = hud_text(combo.0, &attack_stat);
+}
+
+fn theme_apply(theme: Res<Theme>, mut query_text: Query<&mut Text, With<HudText>>) {
+    if !theme.is_changed() {
+        return;
+    }
+
+    for mut text in query_text.iter_mut() {
+        text.sections[0].style.color = theme.text;
+    }
+}
@@ -0,0 +1,106 @@
+use bevy::prelude::*;
+
+use crate::{
+    app_state::AppState,
+    brick::BrickMoveable,
+    config::{GameConfig, GameMode},
+};
+
+pub struct InvisiblePlugin;
+
+impl Plugin for InvisiblePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Peek>().add_systems(
+            Update,
+            (fade_insert, peek_input, fade_update)
+                .chain()
+                .run_if(in_state(AppState::Gaming)),
+        );
+    }
+}
+
+const FADE_DURATION: f32 = 0.5;
+const PEEK_DURATION: f32 = 1.0;
+
+/// fades a locked brick to invisible once `delay` runs out, rendering only
+#[derive(Debug, Component)]
+pub struct FadeTimer {
+    delay: Timer,
+    fade: Timer,
+}
+
+impl FadeTimer {
+    pub fn new(delay: f32) -> Self {
+        Self {
+            delay: Timer::from_seconds(delay, TimerMode::Once),
+            fade: Timer::from_seconds(FADE_DURATION, TimerMode::Once),
+        }
+    }
+
+    fn alpha(&self) -> f32 {
+        if self.delay.finished() {
+            self.fade.percent_left()
+        } else {
+            1.0
+        }
+    }
+}
+
+/// reveals all faded bricks while the timer runs
+#[derive(Debug, Resource)]
+pub struct Peek(Timer);
+
+impl Default for Peek {
+    fn default() -> Self {
+        let mut timer = Timer::from_seconds(PEEK_DURATION, TimerMode::Once);
+        timer.tick(timer.duration());
+        Self(timer)
+    }
+}
+
+fn fade_insert(
+    mut commands: Commands,
+    query_sprite: Query<(), (With<Sprite>, Without<BrickMoveable>)>,
+    mut removed_movable: RemovedComponents<BrickMoveable>,
+    config: Res<GameConfig>,
+) {
+    for entity in removed_movable.iter() {
+        // held bricks are despawned rather than locked
+        if config.mode != GameMode::Invisible || query_sprite.get(entity).is_err() {
+            continue;
+        }
+
+        commands
+            .entity(entity)
+            .insert(FadeTimer::new(config.invisible_fade_delay));
+    }
+}
+
+fn peek_input(keys: Res<Input<KeyCode>>, mut peek: ResMut<Peek>) {
+    if keys.just_pressed(KeyCode::V) {
+        peek.0.reset();
+    }
+}
+
+fn fade_update(
+    mut query_fade: Query<(&mut FadeTimer, &mut Sprite)>,
+    mut peek: ResMut<Peek>,
+    time: Res<Time>,
+) {
+    peek.0.tick(time.delta());
+
+    for (mut fade_timer, mut sprite) in query_fade.iter_mut() {
+        if fade_timer.delay.finished() {
+            fade_timer.fade.tick(time.delta());
+        } else {
+            fade_timer.delay.tick(time.delta());
+        }
+
+        let alpha = if peek.0.finished() {
+            fade_timer.alpha()
+        } else {
+            1.0
+        };
+        sprite.color.set_a(alpha);
+    }
+}
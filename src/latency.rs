@@ -0,0 +1,150 @@
+use std::collections::VecDeque;
+
+use bevy::{core::FrameCount, prelude::*};
+
+use crate::{
+    action::{Action, ActionState},
+    brick::{brick_apply_new_pos, NewPosEvent},
+    hud::StatsHud,
+    theme::Theme,
+};
+
+/// frames from a move, rotate or drop press to the brick drawn at its new position, shown
+/// with the stats hud, only built with the `debug` feature
+pub struct LatencyPlugin;
+
+impl Plugin for LatencyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InputLatency>()
+            .add_systems(Startup, setup_ui)
+            .add_systems(
+                Update,
+                (
+                    latency_press.before(brick_apply_new_pos),
+                    latency_apply.after(brick_apply_new_pos),
+                    latency_text_update,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// presses averaged in the readout
+const LATENCY_SAMPLE_COUNT: usize = 20;
+/// a press that hasn't moved the brick by then was blocked and is dropped
+const LATENCY_TIMEOUT_FRAMES: u32 = 30;
+const LATENCY_ACTION_ARR: [Action; 6] = [
+    Action::MoveLeft,
+    Action::MoveRight,
+    Action::RotateCw,
+    Action::RotateCcw,
+    Action::HardDrop,
+    Action::SonicDrop,
+];
+
+#[derive(Debug, Resource, Default)]
+pub struct InputLatency {
+    /// frame of the press still waiting for its move
+    press_frame: Option<u32>,
+    /// frames each of the last presses took, oldest first
+    sample_arr: VecDeque<u32>,
+}
+
+impl InputLatency {
+    pub fn average(&self) -> Option<f32> {
+        if self.sample_arr.is_empty() {
+            return None;
+        }
+        Some(self.sample_arr.iter().sum::<u32>() as f32 / self.sample_arr.len() as f32)
+    }
+
+    fn text(&self) -> String {
+        match self.average() {
+            Some(average) => format!(
+                "Input latency: {:.1} frames ({} presses)",
+                average,
+                self.sample_arr.len()
+            ),
+            None => "Input latency: -".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Component)]
+pub struct LatencyText;
+
+fn setup_ui(mut commands: Commands, theme: Res<Theme>) {
+    commands.spawn((
+        TextBundle::from_section(
+            InputLatency::default().text(),
+            TextStyle {
+                font_size: 30.0,
+                color: theme.text,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            top: Val::Px(540.0),
+            left: Val::Px(100.0),
+            ..default()
+        }),
+        LatencyText,
+        StatsHud,
+    ));
+}
+
+/// the action state is built before the frame's update, a press made while another waits
+/// restarts the wait
+fn latency_press(
+    action_state: Res<ActionState>,
+    frame_count: Res<FrameCount>,
+    mut input_latency: ResMut<InputLatency>,
+) {
+    if LATENCY_ACTION_ARR
+        .iter()
+        .any(|&action| action_state.just_pressed(action))
+    {
+        input_latency.press_frame = Some(frame_count.0);
+    }
+}
+
+/// the brick is drawn at the end of the frame it moves in, which counts as one frame
+fn latency_apply(
+    mut new_pos_event_reader: EventReader<NewPosEvent>,
+    frame_count: Res<FrameCount>,
+    mut input_latency: ResMut<InputLatency>,
+) {
+    let Some(press_frame) = input_latency.press_frame else {
+        new_pos_event_reader.clear();
+        return;
+    };
+    let frames = frame_count.0.wrapping_sub(press_frame) + 1;
+
+    if new_pos_event_reader.is_empty() {
+        if frames > LATENCY_TIMEOUT_FRAMES {
+            input_latency.press_frame = None;
+        }
+        return;
+    }
+    new_pos_event_reader.clear();
+
+    input_latency.press_frame = None;
+    input_latency.sample_arr.push_back(frames);
+    if input_latency.sample_arr.len() > LATENCY_SAMPLE_COUNT {
+        input_latency.sample_arr.pop_front();
+    }
+}
+
+fn latency_text_update(
+    mut query_text: Query<&mut Text, With<LatencyText>>,
+    input_latency: Res<InputLatency>,
+    theme: Res<Theme>,
+) {
+    if !input_latency.is_changed() && !theme.is_changed() {
+        return;
+    }
+
+    let section = &mut query_text.single_mut().sections[0];
+    section.value = input_latency.text();
+    section.style.color = theme.text;
+}
@@ -0,0 +1,389 @@
+use bevy::{prelude::*, utils::HashMap};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    config::{preview_multiplier_percent, Assists, GameConfig, GameMode},
+    replay::{replay_save, Replay, ReplayRecorder},
+    score::{Level, Lines, Score},
+    storage,
+    theme::Theme,
+};
+
+pub struct LeaderboardPlugin;
+
+impl Plugin for LeaderboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(storage::load::<Leaderboard>(LEADERBOARD_FILE).unwrap_or_default())
+            .init_resource::<LeaderboardScreen>()
+            .add_systems(Startup, setup_screen)
+            .add_systems(
+                OnEnter(AppState::GameOver),
+                leaderboard_record.before(replay_save),
+            )
+            .add_systems(
+                Update,
+                leaderboard_input.run_if(in_state(AppState::Menu).and_then(is_leaderboard_open)),
+            )
+            .add_systems(Update, leaderboard_screen_update.after(leaderboard_input));
+    }
+}
+
+const LEADERBOARD_FILE: &str = "leaderboard.json";
+/// the best game of each mode is kept here, one file per mode
+const BEST_RUN_DIR: &str = "best_runs";
+/// entries kept for each mode and assist profile, so assisted runs never push out
+/// unassisted ones and a mode never pushes out another
+const LEADERBOARD_SIZE: usize = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreEntry {
+    pub score: u32,
+    pub lines: u32,
+    pub level: u32,
+    pub mode: GameMode,
+    pub assists: Assists,
+    /// bricks shown ahead, entries saved before it was recorded were played with one
+    #[serde(default = "default_preview_count")]
+    pub preview_count: usize,
+}
+
+fn default_preview_count() -> usize {
+    1
+}
+
+impl ScoreEntry {
+    /// the game just played with the settings it was scored under
+    pub fn new(score: u32, lines: u32, level: u32, config: &GameConfig) -> Self {
+        Self {
+            score,
+            lines,
+            level,
+            mode: config.mode,
+            assists: config.assists(),
+            preview_count: config.preview_count,
+        }
+    }
+}
+
+/// a personal best with the replay of its inputs, so it can be raced or watched again
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BestRun {
+    pub entry: ScoreEntry,
+    pub replay: Replay,
+}
+
+pub fn best_run_file(mode: GameMode, score: u32) -> String {
+    format!(
+        "{}/{}_{}.json",
+        BEST_RUN_DIR,
+        mode.name().to_lowercase().replace(' ', "_"),
+        score
+    )
+}
+
+/// best games, highest score first, saved after every game
+#[derive(Debug, Resource, Default, Serialize, Deserialize)]
+pub struct Leaderboard {
+    pub entry_arr: Vec<ScoreEntry>,
+}
+
+impl Leaderboard {
+    /// the highest score of the mode, of any assist profile
+    pub fn best_score(&self, mode: GameMode) -> Option<u32> {
+        self.entry_arr
+            .iter()
+            .filter(|entry| entry.mode == mode)
+            .map(|entry| entry.score)
+            .max()
+    }
+
+    fn insert(&mut self, entry: ScoreEntry) {
+        self.entry_arr.push(entry);
+        self.entry_arr.sort_by(|a, b| b.score.cmp(&a.score));
+
+        let mut count_map = HashMap::new();
+        self.entry_arr.retain(|entry| {
+            let count = count_map.entry((entry.mode, entry.assists)).or_insert(0);
+            *count += 1;
+            *count <= LEADERBOARD_SIZE
+        });
+    }
+}
+
+/// which runs the leaderboard screen lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AssistFilter {
+    #[default]
+    All,
+    Unassisted,
+    /// exactly this set of assists
+    Profile(Assists),
+    /// any assist
+    Assisted,
+}
+
+impl AssistFilter {
+    pub fn next(self) -> Self {
+        let hold_only = Assists {
+            hold: true,
+            ghost: false,
+        };
+        let ghost_only = Assists {
+            hold: false,
+            ghost: true,
+        };
+        let both = Assists {
+            hold: true,
+            ghost: true,
+        };
+        match self {
+            AssistFilter::All => AssistFilter::Unassisted,
+            AssistFilter::Unassisted => AssistFilter::Profile(hold_only),
+            AssistFilter::Profile(assists) if assists == hold_only => {
+                AssistFilter::Profile(ghost_only)
+            }
+            AssistFilter::Profile(assists) if assists == ghost_only => AssistFilter::Profile(both),
+            AssistFilter::Profile(_) => AssistFilter::Assisted,
+            AssistFilter::Assisted => AssistFilter::All,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AssistFilter::All => "All",
+            AssistFilter::Unassisted => "Unassisted",
+            AssistFilter::Profile(assists) => assists.label(),
+            AssistFilter::Assisted => "Assisted",
+        }
+    }
+
+    fn accepts(self, assists: Assists) -> bool {
+        match self {
+            AssistFilter::All => true,
+            AssistFilter::Unassisted => assists.is_none(),
+            AssistFilter::Profile(profile) => assists == profile,
+            AssistFilter::Assisted => !assists.is_none(),
+        }
+    }
+}
+
+/// the mode listed, None lists every mode, left and right step through them with None
+/// between the last and the first
+fn mode_filter_step(mode_filter: Option<GameMode>, is_forward: bool) -> Option<GameMode> {
+    let first = GameMode::default();
+    let prev = |mode: GameMode| {
+        let mut prev = mode;
+        while prev.next() != mode {
+            prev = prev.next();
+        }
+        prev
+    };
+    match (mode_filter, is_forward) {
+        (None, true) => Some(first),
+        (None, false) => Some(prev(first)),
+        (Some(mode), true) if mode.next() == first => None,
+        (Some(mode), true) => Some(mode.next()),
+        (Some(mode), false) if mode == first => None,
+        (Some(mode), false) => Some(prev(mode)),
+    }
+}
+
+/// the screen is opened from the menu and covers it until closed
+#[derive(Debug, Resource, Default)]
+pub struct LeaderboardScreen {
+    pub is_open: bool,
+    pub filter: AssistFilter,
+    pub mode_filter: Option<GameMode>,
+}
+
+pub fn is_leaderboard_open(leaderboard_screen: Res<LeaderboardScreen>) -> bool {
+    leaderboard_screen.is_open
+}
+
+#[derive(Debug, Component)]
+pub struct LeaderboardPanel;
+
+#[derive(Debug, Component)]
+pub struct LeaderboardText;
+
+fn setup_screen(mut commands: Commands, theme: Res<Theme>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: theme.board_background.into(),
+                z_index: ZIndex::Global(60),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            LeaderboardPanel,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 30.0,
+                        color: theme.text,
+                        ..default()
+                    },
+                ),
+                LeaderboardText,
+            ));
+        });
+}
+
+/// a new best of the mode replaces the best run saved before it
+fn leaderboard_record(
+    mut leaderboard: ResMut<Leaderboard>,
+    config: Res<GameConfig>,
+    recorder: Res<ReplayRecorder>,
+    score: Res<Score>,
+    lines: Res<Lines>,
+    level: Res<Level>,
+) {
+    let entry = ScoreEntry::new(score.get(), lines.0, level.0, &config);
+
+    let best_score = leaderboard.best_score(config.mode);
+    if entry.score > best_score.unwrap_or(0) {
+        if let Some(replay) = recorder.replay.clone() {
+            // the old best is only dropped once the new one is on disk, a failed write
+            // never leaves the mode without a best run
+            let is_saved = storage::save(
+                &best_run_file(config.mode, entry.score),
+                &BestRun {
+                    entry: entry.clone(),
+                    replay,
+                },
+            );
+            if let (true, Some(best_score)) = (is_saved, best_score) {
+                storage::remove(&best_run_file(config.mode, best_score));
+            }
+        }
+    }
+
+    leaderboard.insert(entry);
+
+    storage::save(LEADERBOARD_FILE, &*leaderboard);
+}
+
+/// tab switches the assist filter, left and right the mode, backspace goes back to the menu
+fn leaderboard_input(keys: Res<Input<KeyCode>>, mut leaderboard_screen: ResMut<LeaderboardScreen>) {
+    if keys.just_pressed(KeyCode::Tab) {
+        leaderboard_screen.filter = leaderboard_screen.filter.next();
+    }
+    if keys.just_pressed(KeyCode::Right) {
+        leaderboard_screen.mode_filter = mode_filter_step(leaderboard_screen.mode_filter, true);
+    }
+    if keys.just_pressed(KeyCode::Left) {
+        leaderboard_screen.mode_filter = mode_filter_step(leaderboard_screen.mode_filter, false);
+    }
+    if keys.just_pressed(KeyCode::Back) {
+        leaderboard_screen.is_open = false;
+    }
+}
+
+fn leaderboard_screen_update(
+    mut query_panel: Query<(&mut Visibility, &mut BackgroundColor), With<LeaderboardPanel>>,
+    mut query_text: Query<&mut Text, With<LeaderboardText>>,
+    leaderboard: Res<Leaderboard>,
+    leaderboard_screen: Res<LeaderboardScreen>,
+    theme: Res<Theme>,
+) {
+    if !leaderboard.is_changed() && !leaderboard_screen.is_changed() && !theme.is_changed() {
+        return;
+    }
+
+    let (mut visibility, mut background_color) = query_panel.single_mut();
+    *visibility = if leaderboard_screen.is_open {
+        Visibility::Visible
+    } else {
+        Visibility::Hidden
+    };
+    *background_color = theme.board_background.into();
+
+    let mut line_arr = vec![
+        format!(
+            "Leaderboard: {} (press tab to change)",
+            leaderboard_screen.filter.name()
+        ),
+        format!(
+            "Mode: {} (press left or right to change)",
+            leaderboard_screen
+                .mode_filter
+                .map_or("All", |mode| mode.name())
+        ),
+        String::new(),
+    ];
+    let entry_arr = leaderboard
+        .entry_arr
+        .iter()
+        .filter(|entry| leaderboard_screen.filter.accepts(entry.assists))
+        .filter(|entry| {
+            leaderboard_screen
+                .mode_filter
+                .map_or(true, |mode| entry.mode == mode)
+        })
+        .take(LEADERBOARD_SIZE)
+        .collect::<Vec<_>>();
+    if entry_arr.is_empty() {
+        line_arr.push("No games yet".to_string());
+    }
+    for (index, entry) in entry_arr.iter().enumerate() {
+        line_arr.push(format!(
+            "{:>2}. {:>7}  {} lines  level {}  {}  [{}, {} next x{:.2}]",
+            index + 1,
+            entry.score,
+            entry.lines,
+            entry.level,
+            entry.mode.name(),
+            entry.assists.label(),
+            entry.preview_count,
+            preview_multiplier_percent(entry.preview_count) as f32 / 100.0
+        ));
+    }
+    line_arr.push(String::new());
+    line_arr.push("press backspace to go back".to_string());
+
+    let section = &mut query_text.single_mut().sections[0];
+    section.value = line_arr.join("\n");
+    section.style.color = theme.text;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entry_records_the_preview_count_it_was_scored_with() {
+        let config = GameConfig {
+            preview_count: 5,
+            ..GameConfig::default()
+        };
+
+        let entry = ScoreEntry::new(240, 2, 1, &config);
+        assert_eq!(entry.preview_count, 5);
+
+        let json = serde_json::to_string(&entry).unwrap();
+        let entry_loaded: ScoreEntry = serde_json::from_str(&json).unwrap();
+        assert_eq!(entry_loaded.preview_count, 5);
+    }
+
+    #[test]
+    fn entry_saved_before_the_preview_count_was_played_with_one() {
+        let entry = ScoreEntry::new(300, 2, 1, &GameConfig::default());
+        let mut json = serde_json::to_value(&entry).unwrap();
+        json.as_object_mut().unwrap().remove("preview_count");
+
+        let entry_loaded: ScoreEntry = serde_json::from_value(json).unwrap();
+        assert_eq!(entry_loaded.preview_count, 1);
+    }
+}
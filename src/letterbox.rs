@@ -0,0 +1,155 @@
+use bevy::{
+    core_pipeline::clear_color::ClearColorConfig,
+    prelude::*,
+    render::{
+        camera::{ScalingMode, Viewport},
+        view::RenderLayers,
+    },
+    window::{PrimaryWindow, WindowResized},
+};
+
+use crate::config::GameConfig;
+
+pub struct LetterboxPlugin;
+
+impl Plugin for LetterboxPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_letterbox)
+            .add_systems(Update, (letterbox_apply, letterbox_background_fit).chain());
+    }
+}
+
+/// world units of the board, the side panels and the HUD with some margin, the camera
+/// keeps this aspect whatever the window shape
+const GAME_SIZE: Vec2 = Vec2::new(960.0, 720.0);
+/// a layer nothing is drawn on, the bar camera only clears
+const BAR_LAYER: u8 = 1;
+const BAR_COLOR: Color = Color::BLACK;
+
+/// renders before the game camera and paints the whole window black, the game camera
+/// then draws over its viewport only
+#[derive(Debug, Component)]
+pub struct BarCamera;
+
+/// stands in for the clear color inside the viewport, as the game camera no longer clears
+#[derive(Debug, Component)]
+pub struct LetterboxBackground;
+
+fn setup_letterbox(mut commands: Commands, clear_color: Res<ClearColor>) {
+    commands.spawn((
+        Camera2dBundle {
+            camera: Camera {
+                order: -1,
+                ..default()
+            },
+            camera_2d: Camera2d {
+                clear_color: ClearColorConfig::Custom(BAR_COLOR),
+            },
+            ..default()
+        },
+        RenderLayers::layer(BAR_LAYER),
+        UiCameraConfig { show_ui: false },
+        BarCamera,
+    ));
+
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: clear_color.0,
+                custom_size: Some(GAME_SIZE),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 0.0, -2.0),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        LetterboxBackground,
+    ));
+}
+
+/// fit the largest rectangle of the game aspect in the window and center it
+fn letterbox_viewport(window: &Window) -> Option<Viewport> {
+    let window_size = UVec2::new(window.physical_width(), window.physical_height());
+    // minimized, a zero sized viewport is invalid
+    if window_size.x == 0 || window_size.y == 0 {
+        return None;
+    }
+
+    let aspect = GAME_SIZE.x / GAME_SIZE.y;
+    let size = if window_size.x as f32 / window_size.y as f32 > aspect {
+        UVec2::new((window_size.y as f32 * aspect) as u32, window_size.y)
+    } else {
+        UVec2::new(window_size.x, (window_size.x as f32 / aspect) as u32)
+    }
+    .max(UVec2::ONE);
+
+    Some(Viewport {
+        physical_position: (window_size - size) / 2,
+        physical_size: size,
+        ..default()
+    })
+}
+
+#[allow(clippy::type_complexity)]
+fn letterbox_apply(
+    query_window: Query<&Window, With<PrimaryWindow>>,
+    mut query_camera: ParamSet<(
+        Query<(&mut Camera, &mut Camera2d, &mut OrthographicProjection), Without<BarCamera>>,
+        Query<&mut Camera, With<BarCamera>>,
+    )>,
+    config: Res<GameConfig>,
+    mut window_resized_event_reader: EventReader<WindowResized>,
+    mut is_applied: Local<bool>,
+) {
+    let is_resized = window_resized_event_reader.iter().count() > 0;
+    if *is_applied && !is_resized && !config.is_changed() {
+        return;
+    }
+    let Ok(window) = query_window.get_single() else {
+        return;
+    };
+
+    for (mut camera, mut camera_2d, mut projection) in query_camera.p0().iter_mut() {
+        if config.letterbox {
+            camera.viewport = letterbox_viewport(window);
+            camera_2d.clear_color = ClearColorConfig::None;
+            projection.scaling_mode = ScalingMode::Fixed {
+                width: GAME_SIZE.x,
+                height: GAME_SIZE.y,
+            };
+        } else {
+            camera.viewport = None;
+            camera_2d.clear_color = ClearColorConfig::Default;
+            projection.scaling_mode = ScalingMode::WindowSize(1.0);
+        }
+        *is_applied = true;
+    }
+    for mut camera in query_camera.p1().iter_mut() {
+        camera.is_active = config.letterbox;
+    }
+}
+
+/// cover what the game camera shows, zoomed out that is more than `GAME_SIZE`
+fn letterbox_background_fit(
+    query_projection: Query<&OrthographicProjection, (With<Camera2d>, Without<BarCamera>)>,
+    mut query_background: Query<(&mut Sprite, &mut Visibility), With<LetterboxBackground>>,
+    clear_color: Res<ClearColor>,
+    config: Res<GameConfig>,
+) {
+    let Ok(projection) = query_projection.get_single() else {
+        return;
+    };
+    let size = projection.area.size();
+
+    for (mut sprite, mut visibility) in query_background.iter_mut() {
+        if sprite.custom_size != Some(size) || sprite.color != clear_color.0 {
+            sprite.custom_size = Some(size);
+            sprite.color = clear_color.0;
+        }
+        *visibility = if config.letterbox {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
@@ -1,4 +1,52 @@
+pub mod achievement;
+pub mod action;
 pub mod app_state;
+pub mod attack;
+pub mod bag;
+pub mod banner;
+pub mod board;
 pub mod brick;
+pub mod config;
+pub mod finesse;
 pub mod gameover;
+pub mod ghost;
+pub mod grace;
+#[cfg(feature = "debug")]
+pub mod heatmap;
+pub mod history;
+pub mod hold;
+pub mod hud;
+pub mod invisible;
+#[cfg(feature = "debug")]
+pub mod latency;
+pub mod leaderboard;
+pub mod letterbox;
+pub mod lockbar;
+pub mod menu;
+pub mod meter;
+pub mod next;
+pub mod outline;
+pub mod pace;
+pub mod pause;
+#[cfg(feature = "debug")]
+pub mod position;
+pub mod quicksave;
+pub mod race;
+pub mod randomizer;
+pub mod replay;
+pub mod root;
+pub mod rotation;
 pub mod score;
+pub mod sim;
+#[cfg(feature = "debug")]
+pub mod statelog;
+pub mod status;
+pub mod storage;
+pub mod texture;
+pub mod theme;
+pub mod tilt;
+pub mod transition;
+pub mod tutorial;
+#[cfg(feature = "debug")]
+pub mod validate;
+pub mod zoom;
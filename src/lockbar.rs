@@ -0,0 +1,88 @@
+use bevy::prelude::*;
+
+use crate::{
+    app_state::AppState,
+    brick::{brick_lock, BrickMoveable, LockTimer, ToppedOut, BRICK_WIDTH},
+    config::GameConfig,
+    root::OnBoard,
+    theme::Theme,
+};
+
+pub struct LockBarPlugin;
+
+impl Plugin for LockBarPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_lock_bar)
+            .add_systems(Update, (lock_bar_toggle, lock_bar_update.after(brick_lock)));
+    }
+}
+
+const LOCK_BAR_HEIGHT: f32 = 4.0;
+/// gap between the lowest cells of the brick and the bar
+const LOCK_BAR_GAP: f32 = 2.0;
+/// over the live bricks, a bar under a brick resting on the stack covers the top of the stack
+const LOCK_BAR_Z: f32 = 3.0;
+
+/// shrinks from both sides as the lock delay of a resting brick runs out
+#[derive(Debug, Component)]
+pub struct LockBar;
+
+fn setup_lock_bar(mut commands: Commands) {
+    commands.spawn((
+        SpriteBundle {
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        LockBar,
+        OnBoard,
+    ));
+}
+
+fn lock_bar_toggle(keys: Res<Input<KeyCode>>, mut config: ResMut<GameConfig>) {
+    if keys.just_pressed(KeyCode::F6) {
+        config.lock_bar = !config.lock_bar;
+    }
+}
+
+/// as wide as the brick while the timer is full, gone while the brick can still fall and
+/// outside of a game
+#[allow(clippy::type_complexity)]
+fn lock_bar_update(
+    query_brick_movable: Query<&Transform, (With<BrickMoveable>, Without<ToppedOut>)>,
+    mut query_bar: Query<
+        (&mut Transform, &mut Sprite, &mut Visibility),
+        (With<LockBar>, Without<BrickMoveable>),
+    >,
+    lock_timer: Res<LockTimer>,
+    state: Res<State<AppState>>,
+    config: Res<GameConfig>,
+    theme: Res<Theme>,
+) {
+    let (mut transform, mut sprite, mut visibility) = query_bar.single_mut();
+    if !config.lock_bar
+        || !lock_timer.resting
+        || *state.get() != AppState::Gaming
+        || query_brick_movable.is_empty()
+    {
+        *visibility = Visibility::Hidden;
+        return;
+    }
+
+    let translation_arr = query_brick_movable
+        .iter()
+        .map(|transform| transform.translation)
+        .collect::<Vec<Vec3>>();
+    let min_x = translation_arr.iter().map(|t| t.x).fold(f32::MAX, f32::min);
+    let max_x = translation_arr.iter().map(|t| t.x).fold(f32::MIN, f32::max);
+    let min_y = translation_arr.iter().map(|t| t.y).fold(f32::MAX, f32::min);
+
+    let width = (max_x - min_x + BRICK_WIDTH as f32) * lock_timer.timer.percent_left();
+    sprite.custom_size = Some(Vec2::new(width, LOCK_BAR_HEIGHT));
+    sprite.color = theme.text;
+    transform.translation = Vec3::new(
+        (min_x + max_x) / 2.0,
+        min_y - BRICK_WIDTH as f32 / 2.0 - LOCK_BAR_GAP - LOCK_BAR_HEIGHT / 2.0,
+        LOCK_BAR_Z,
+    );
+    *visibility = Visibility::Inherited;
+}
@@ -1,18 +1,81 @@
-use bevy::{prelude::*, window::close_on_esc, DefaultPlugins};
+#[cfg(not(target_arch = "wasm32"))]
+use bevy::window::close_on_esc;
+use bevy::{prelude::*, DefaultPlugins};
 use bevy_tetris::{
-    app_state::AppState, brick::BrickPlugin, gameover::GameoverPlugin, score::ScorePlugin,
+    achievement::AchievementPlugin, action::ActionPlugin, app_state::AppState,
+    attack::AttackPlugin, bag::BagPlugin, banner::BannerPlugin, board::BoardPlugin,
+    brick::BrickPlugin, config::ConfigPlugin, finesse::FinessePlugin, gameover::GameoverPlugin,
+    ghost::GhostPlugin, grace::GracePlugin, history::HistoryPlugin, hold::HoldPlugin,
+    hud::HudPlugin, invisible::InvisiblePlugin, leaderboard::LeaderboardPlugin,
+    letterbox::LetterboxPlugin, lockbar::LockBarPlugin, menu::MenuPlugin, meter::MeterPlugin,
+    next::NextPlugin, outline::OutlinePlugin, pace::PacePlugin, pause::PausePlugin,
+    quicksave::QuickSavePlugin, race::RacePlugin, randomizer::RandomizerPlugin,
+    replay::ReplayPlugin, root::RootPlugin, score::ScorePlugin, status::StatusPlugin,
+    texture::TexturePlugin, theme::ThemePlugin, tilt::TiltPlugin, transition::TransitionPlugin,
+    tutorial::TutorialPlugin, zoom::ZoomPlugin,
+};
+#[cfg(feature = "debug")]
+use bevy_tetris::{
+    heatmap::HeatmapPlugin, latency::LatencyPlugin, position::PositionPlugin,
+    statelog::StateLogPlugin, validate::ValidatePlugin,
 };
 
 fn main() {
-    App::new()
-        .add_state::<AppState>()
+    let mut app = App::new();
+    app.add_state::<AppState>()
         .add_plugins(DefaultPlugins)
+        .add_plugins(ConfigPlugin)
+        .add_plugins(ActionPlugin)
+        .add_plugins(TransitionPlugin)
+        .add_plugins(ThemePlugin)
+        .add_plugins(TexturePlugin)
+        .add_plugins(MenuPlugin)
+        .add_plugins(RandomizerPlugin)
+        .add_plugins(RootPlugin)
         .add_plugins(BrickPlugin)
+        .add_plugins(ReplayPlugin)
+        .add_plugins(BoardPlugin)
+        .add_plugins(GhostPlugin)
+        .add_plugins(RacePlugin)
+        .add_plugins(OutlinePlugin)
+        .add_plugins(LockBarPlugin)
+        .add_plugins(HoldPlugin)
+        .add_plugins(GracePlugin)
+        .add_plugins(QuickSavePlugin)
+        .add_plugins(NextPlugin)
+        .add_plugins(BagPlugin)
+        .add_plugins(InvisiblePlugin)
         .add_plugins(ScorePlugin)
+        .add_plugins(MeterPlugin)
+        .add_plugins(AttackPlugin)
+        .add_plugins(PacePlugin)
+        .add_plugins(HudPlugin)
+        .add_plugins(PausePlugin)
+        .add_plugins(StatusPlugin)
+        .add_plugins(ZoomPlugin)
+        .add_plugins(TiltPlugin)
+        .add_plugins(TutorialPlugin)
+        .add_plugins(LetterboxPlugin)
         .add_plugins(GameoverPlugin)
-        .add_systems(Startup, setup)
-        .add_systems(Update, close_on_esc)
-        .run();
+        .add_plugins(HistoryPlugin)
+        .add_plugins(LeaderboardPlugin)
+        .add_plugins(FinessePlugin)
+        .add_plugins(AchievementPlugin)
+        .add_plugins(BannerPlugin)
+        .add_systems(Startup, setup);
+
+    #[cfg(feature = "debug")]
+    app.add_plugins(ValidatePlugin)
+        .add_plugins(HeatmapPlugin)
+        .add_plugins(LatencyPlugin)
+        .add_plugins(PositionPlugin)
+        .add_plugins(StateLogPlugin);
+
+    // there is no window to close in the browser
+    #[cfg(not(target_arch = "wasm32"))]
+    app.add_systems(Update, close_on_esc);
+
+    app.run();
 }
 
 fn setup(mut commands: Commands) {
@@ -0,0 +1,714 @@
+use bevy::{app::AppExit, prelude::*};
+
+use crate::{
+    app_state::AppState,
+    config::{
+        BoardConfig, Difficulty, GameConfig, LockBehavior, BOARD_HEIGHT_MAX, BOARD_HEIGHT_MIN,
+        BOARD_WIDTH_MAX, BOARD_WIDTH_MIN, PREVIEW_COUNT_MAX,
+    },
+    leaderboard::{is_leaderboard_open, LeaderboardScreen},
+    randomizer::{GameSeed, LastGame},
+    rotation::RotationSystem,
+    theme::Theme,
+    transition::{in_transition, Transition},
+};
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MenuSelection>()
+            .init_resource::<SettingsScreen>()
+            .init_resource::<DifficultyConfirm>()
+            .add_systems(Startup, setup_menu)
+            .add_systems(OnEnter(AppState::Menu), menu_show)
+            .add_systems(OnExit(AppState::Menu), menu_hide)
+            .add_systems(
+                Update,
+                (
+                    menu_navigate,
+                    difficulty_select,
+                    menu_activate,
+                    race_select,
+                    menu_value_adjust,
+                )
+                    .after(settings_input)
+                    .run_if(
+                        in_state(AppState::Menu)
+                            .and_then(not(in_transition))
+                            .and_then(not(is_leaderboard_open)),
+                    )
+                    .run_if(not(is_settings_open)),
+            )
+            .add_systems(
+                Update,
+                settings_input
+                    .run_if(in_state(AppState::Menu).and_then(not(in_transition)))
+                    .run_if(is_settings_open),
+            )
+            .add_systems(Update, menu_highlight.after(menu_activate))
+            .add_systems(Update, board_preview_update.after(menu_value_adjust));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuItem {
+    Play,
+    Difficulty,
+    /// a new game with the seed and mode of the last one
+    ReplaySeed,
+    /// left and right pick the earlier game raced against
+    Race,
+    /// left and right change the size, the next game is played on it
+    BoardWidth,
+    BoardHeight,
+    /// bricks shown ahead, fewer multiply the line clear points more
+    Previews,
+    Settings,
+    Leaderboard,
+    Quit,
+}
+
+impl MenuItem {
+    pub const ALL: [MenuItem; 10] = [
+        MenuItem::Play,
+        MenuItem::Difficulty,
+        MenuItem::ReplaySeed,
+        MenuItem::Race,
+        MenuItem::BoardWidth,
+        MenuItem::BoardHeight,
+        MenuItem::Previews,
+        MenuItem::Settings,
+        MenuItem::Leaderboard,
+        MenuItem::Quit,
+    ];
+
+    pub fn label(self, board_config: &BoardConfig, config: &GameConfig) -> String {
+        match self {
+            MenuItem::Play => "Play".to_string(),
+            MenuItem::Difficulty => format!("Difficulty: < {} >", config.difficulty.name()),
+            MenuItem::ReplaySeed => "Replay seed".to_string(),
+            MenuItem::Race => format!("Race: < {} >", config.race_replay.name()),
+            MenuItem::BoardWidth => format!("Board width: < {} >", board_config.width),
+            MenuItem::BoardHeight => format!("Board height: < {} >", board_config.height),
+            MenuItem::Previews => format!(
+                "Next previews: < {} > (score x{:.2})",
+                config.preview_count,
+                config.preview_multiplier_percent() as f32 / 100.0
+            ),
+            MenuItem::Settings => "Settings".to_string(),
+            MenuItem::Leaderboard => "Leaderboard".to_string(),
+            MenuItem::Quit => "Quit".to_string(),
+        }
+    }
+
+    /// disabled items, or nothing to replay, are shown dimmed and do nothing
+    pub fn is_enabled(self, config: &GameConfig, has_last_game: bool) -> bool {
+        match self {
+            MenuItem::Play
+            | MenuItem::Difficulty
+            | MenuItem::Race
+            | MenuItem::BoardWidth
+            | MenuItem::BoardHeight
+            | MenuItem::Previews
+            | MenuItem::Leaderboard => true,
+            MenuItem::ReplaySeed => has_last_game,
+            // the Custom difficulty unlocks the settings
+            MenuItem::Settings => config.difficulty == Difficulty::Custom,
+            // a browser tab can't be closed from the game
+            MenuItem::Quit => cfg!(not(target_arch = "wasm32")),
+        }
+    }
+}
+
+/// highlighted row of a list, wrapping at both ends, shared by every keyboard driven list
+#[derive(Debug, Resource, Default)]
+pub struct MenuSelection {
+    pub index: usize,
+}
+
+impl MenuSelection {
+    pub fn up(&mut self, len: usize) {
+        self.index = (self.index + len - 1) % len;
+    }
+
+    pub fn down(&mut self, len: usize) {
+        self.index = (self.index + 1) % len;
+    }
+
+    /// step with the arrow keys
+    pub fn navigate(&mut self, keys: &Input<KeyCode>, len: usize) {
+        if keys.just_pressed(KeyCode::Up) {
+            self.up(len);
+        }
+        if keys.just_pressed(KeyCode::Down) {
+            self.down(len);
+        }
+    }
+}
+
+/// rows of the settings screen, the first ones are the ones a difficulty preset sets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsItem {
+    StartLevel,
+    GravityScale,
+    LockDelay,
+    DasDelay,
+    ArrInterval,
+    Hold,
+    Ghost,
+    LockReset,
+    DirectionChange,
+    RotateRepeat,
+    RotateRepeatDelay,
+    RotateRepeatInterval,
+    SonicDrop,
+    DropRepeat,
+    RotationSystem,
+    PointerControls,
+}
+
+impl SettingsItem {
+    pub const ALL: [SettingsItem; 16] = [
+        SettingsItem::StartLevel,
+        SettingsItem::GravityScale,
+        SettingsItem::LockDelay,
+        SettingsItem::DasDelay,
+        SettingsItem::ArrInterval,
+        SettingsItem::Hold,
+        SettingsItem::Ghost,
+        SettingsItem::LockReset,
+        SettingsItem::DirectionChange,
+        SettingsItem::RotateRepeat,
+        SettingsItem::RotateRepeatDelay,
+        SettingsItem::RotateRepeatInterval,
+        SettingsItem::SonicDrop,
+        SettingsItem::DropRepeat,
+        SettingsItem::RotationSystem,
+        SettingsItem::PointerControls,
+    ];
+
+    pub fn label(self, config: &GameConfig) -> String {
+        let on_off = |on: bool| if on { "on" } else { "off" };
+        match self {
+            SettingsItem::StartLevel => format!("Start level: {}", config.start_level),
+            SettingsItem::GravityScale => format!("Gravity: x{:.2}", config.gravity_scale),
+            SettingsItem::LockDelay => format!("Lock delay: {:.1}s", config.lock_delay),
+            SettingsItem::DasDelay => format!("DAS: {:.2}s", config.das_delay),
+            SettingsItem::ArrInterval => format!("ARR: {:.2}s", config.arr_interval),
+            SettingsItem::Hold => format!("Hold: {}", on_off(config.hold_enabled)),
+            SettingsItem::Ghost => format!("Ghost: {}", on_off(config.ghost_enabled)),
+            SettingsItem::LockReset => format!(
+                "Lock reset: {}",
+                match config.lock_behavior {
+                    LockBehavior::Classic => "classic",
+                    LockBehavior::MoveReset => "move reset",
+                }
+            ),
+            SettingsItem::DirectionChange => format!(
+                "Direction change: {}",
+                if config.das_direction_change_immediate {
+                    "immediate"
+                } else {
+                    "wait"
+                }
+            ),
+            SettingsItem::RotateRepeat => {
+                format!("Rotate repeat: {}", on_off(config.rotate_repeat))
+            }
+            SettingsItem::RotateRepeatDelay => {
+                format!("Rotate repeat delay: {:.2}s", config.rotate_repeat_delay)
+            }
+            SettingsItem::RotateRepeatInterval => {
+                format!(
+                    "Rotate repeat interval: {:.2}s",
+                    config.rotate_repeat_interval
+                )
+            }
+            SettingsItem::SonicDrop => format!("Sonic drop: {}", on_off(config.sonic_drop)),
+            SettingsItem::DropRepeat => {
+                format!("Drop key repeat: {}", on_off(config.hard_drop_auto_repeat))
+            }
+            SettingsItem::RotationSystem => {
+                format!("Rotation: {}", config.rotation_system.name())
+            }
+            SettingsItem::PointerControls => {
+                format!("Mouse and touch: {}", on_off(config.pointer_controls))
+            }
+        }
+    }
+
+    /// step the setting one notch, `direction` is -1 or 1
+    pub fn adjust(self, config: &mut GameConfig, direction: i32) {
+        match self {
+            SettingsItem::StartLevel => {
+                config.start_level = (config.start_level as i32 + direction).clamp(1, 20) as u32;
+            }
+            SettingsItem::GravityScale => {
+                config.gravity_scale = step(config.gravity_scale, 0.25, direction, 0.25, 3.0);
+            }
+            SettingsItem::LockDelay => {
+                config.lock_delay = step(config.lock_delay, 0.1, direction, 0.1, 2.0);
+            }
+            SettingsItem::DasDelay => {
+                config.das_delay = step(config.das_delay, 0.01, direction, 0.05, 0.4);
+            }
+            SettingsItem::ArrInterval => {
+                config.arr_interval = step(config.arr_interval, 0.01, direction, 0.0, 0.2);
+            }
+            SettingsItem::Hold => config.hold_enabled = !config.hold_enabled,
+            SettingsItem::Ghost => config.ghost_enabled = !config.ghost_enabled,
+            SettingsItem::LockReset => {
+                config.lock_behavior = match config.lock_behavior {
+                    LockBehavior::Classic => LockBehavior::MoveReset,
+                    LockBehavior::MoveReset => LockBehavior::Classic,
+                }
+            }
+            SettingsItem::DirectionChange => {
+                config.das_direction_change_immediate = !config.das_direction_change_immediate
+            }
+            SettingsItem::RotateRepeat => config.rotate_repeat = !config.rotate_repeat,
+            SettingsItem::RotateRepeatDelay => {
+                config.rotate_repeat_delay =
+                    step(config.rotate_repeat_delay, 0.05, direction, 0.1, 0.6);
+            }
+            SettingsItem::RotateRepeatInterval => {
+                config.rotate_repeat_interval =
+                    step(config.rotate_repeat_interval, 0.05, direction, 0.05, 0.4);
+            }
+            SettingsItem::SonicDrop => config.sonic_drop = !config.sonic_drop,
+            SettingsItem::DropRepeat => {
+                config.hard_drop_auto_repeat = !config.hard_drop_auto_repeat
+            }
+            SettingsItem::RotationSystem => {
+                config.rotation_system = match config.rotation_system {
+                    RotationSystem::Srs => RotationSystem::Classic,
+                    RotationSystem::Classic => RotationSystem::Srs,
+                }
+            }
+            SettingsItem::PointerControls => config.pointer_controls = !config.pointer_controls,
+        }
+    }
+}
+
+/// move `value` one `size` up or down, snapped to the step so repeated presses don't drift
+fn step(value: f32, size: f32, direction: i32, min: f32, max: f32) -> f32 {
+    (((value / size).round() + direction as f32) * size).clamp(min, max)
+}
+
+/// the full settings screen over the menu, only reachable with the Custom difficulty
+#[derive(Debug, Resource, Default)]
+pub struct SettingsScreen {
+    pub is_open: bool,
+    pub selection: MenuSelection,
+}
+
+pub fn is_settings_open(settings_screen: Res<SettingsScreen>) -> bool {
+    settings_screen.is_open
+}
+
+/// a preset switch waiting for a second press, it would overwrite custom settings
+#[derive(Debug, Resource, Default)]
+pub struct DifficultyConfirm {
+    pub is_pending: bool,
+}
+
+#[derive(Debug, Component)]
+pub struct Menu;
+
+#[derive(Debug, Component)]
+pub struct MenuItemText(pub MenuItem);
+
+/// outline of the board at the chosen size, beside the items
+#[derive(Debug, Component)]
+pub struct BoardPreview;
+
+/// pixels of a board cell in the preview
+const PREVIEW_CELL_SIZE: f32 = 10.0;
+const PREVIEW_BORDER: f32 = 2.0;
+
+#[derive(Debug, Component)]
+pub struct DifficultyWarning;
+
+#[derive(Debug, Component)]
+pub struct SettingsMenu;
+
+#[derive(Debug, Component)]
+pub struct SettingsItemText(pub SettingsItem);
+
+fn setup_menu(mut commands: Commands, theme: Res<Theme>) {
+    let text_style = |font_size: f32| TextStyle {
+        font_size,
+        color: theme.text,
+        ..default()
+    };
+    let column_style = Style {
+        position_type: PositionType::Absolute,
+        width: Val::Percent(100.0),
+        height: Val::Percent(100.0),
+        flex_direction: FlexDirection::Column,
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        row_gap: Val::Px(20.0),
+        ..default()
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: column_style.clone(),
+                background_color: theme.board_background.into(),
+                z_index: ZIndex::Global(50),
+                ..default()
+            },
+            Menu,
+        ))
+        .with_children(|parent| {
+            for item in MenuItem::ALL {
+                parent.spawn((
+                    TextBundle::from_section("", text_style(60.0)),
+                    MenuItemText(item),
+                ));
+            }
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        right: Val::Px(100.0),
+                        top: Val::Px(100.0),
+                        border: UiRect::all(Val::Px(PREVIEW_BORDER)),
+                        ..default()
+                    },
+                    border_color: theme.text.into(),
+                    ..default()
+                },
+                BoardPreview,
+            ));
+            parent.spawn((
+                TextBundle::from_section("", text_style(30.0)),
+                DifficultyWarning,
+            ));
+        });
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    row_gap: Val::Px(8.0),
+                    ..column_style
+                },
+                background_color: theme.board_background.into(),
+                z_index: ZIndex::Global(51),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            Menu,
+            SettingsMenu,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section("Settings", text_style(60.0)));
+            for item in SettingsItem::ALL {
+                parent.spawn((
+                    TextBundle::from_section("", text_style(36.0)),
+                    SettingsItemText(item),
+                ));
+            }
+            parent.spawn(TextBundle::from_section(
+                "left/right change, enter back",
+                text_style(24.0),
+            ));
+        });
+}
+
+/// the settings panel keeps its own visibility, it's only shown while open
+fn menu_show(
+    settings_screen: Res<SettingsScreen>,
+    mut query_menu: Query<(&mut Visibility, Has<SettingsMenu>), With<Menu>>,
+) {
+    for (mut visibility, is_settings) in query_menu.iter_mut() {
+        if !is_settings || settings_screen.is_open {
+            *visibility = Visibility::Visible;
+        }
+    }
+}
+
+fn menu_hide(mut query_menu: Query<&mut Visibility, With<Menu>>) {
+    for mut visibility in query_menu.iter_mut() {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+fn menu_navigate(keys: Res<Input<KeyCode>>, mut menu_selection: ResMut<MenuSelection>) {
+    menu_selection.navigate(&keys, MenuItem::ALL.len());
+}
+
+/// left and right on the difficulty step through the presets, leaving custom settings takes
+/// a second press
+fn difficulty_select(
+    keys: Res<Input<KeyCode>>,
+    menu_selection: Res<MenuSelection>,
+    mut config: ResMut<GameConfig>,
+    mut confirm: ResMut<DifficultyConfirm>,
+) {
+    if MenuItem::ALL[menu_selection.index] != MenuItem::Difficulty {
+        if confirm.is_pending {
+            confirm.is_pending = false;
+        }
+        return;
+    }
+
+    let direction = if keys.just_pressed(KeyCode::Left) {
+        -1
+    } else if keys.just_pressed(KeyCode::Right) {
+        1
+    } else {
+        return;
+    };
+
+    let is_custom = config
+        .difficulty
+        .preset()
+        .map_or(true, |preset| !preset.matches(&config));
+    if is_custom && !confirm.is_pending {
+        confirm.is_pending = true;
+        return;
+    }
+    confirm.is_pending = false;
+
+    config.difficulty = if direction < 0 {
+        config.difficulty.prev()
+    } else {
+        config.difficulty.next()
+    };
+    if let Some(preset) = config.difficulty.preset() {
+        preset.apply(&mut config);
+    }
+}
+
+/// left and right on the race step through the earlier games to race against
+fn race_select(
+    keys: Res<Input<KeyCode>>,
+    menu_selection: Res<MenuSelection>,
+    mut config: ResMut<GameConfig>,
+) {
+    if MenuItem::ALL[menu_selection.index] != MenuItem::Race {
+        return;
+    }
+
+    if keys.just_pressed(KeyCode::Left) {
+        config.race_replay = config.race_replay.prev();
+    }
+    if keys.just_pressed(KeyCode::Right) {
+        config.race_replay = config.race_replay.next();
+    }
+}
+
+/// left and right on a value item, sizes are kept within the ones every brick can spawn on
+fn menu_value_adjust(
+    keys: Res<Input<KeyCode>>,
+    menu_selection: Res<MenuSelection>,
+    mut board_config: ResMut<BoardConfig>,
+    mut config: ResMut<GameConfig>,
+) {
+    let step = if keys.just_pressed(KeyCode::Right) {
+        1
+    } else if keys.just_pressed(KeyCode::Left) {
+        -1
+    } else {
+        return;
+    };
+
+    match MenuItem::ALL[menu_selection.index] {
+        MenuItem::BoardWidth => {
+            board_config.width =
+                (board_config.width + step).clamp(BOARD_WIDTH_MIN, BOARD_WIDTH_MAX);
+        }
+        MenuItem::BoardHeight => {
+            board_config.height =
+                (board_config.height + step).clamp(BOARD_HEIGHT_MIN, BOARD_HEIGHT_MAX);
+        }
+        MenuItem::Previews => {
+            config.preview_count =
+                (config.preview_count as i8 + step).clamp(0, PREVIEW_COUNT_MAX as i8) as usize;
+        }
+        _ => {}
+    }
+}
+
+/// the outline resizes as the values change, the board itself is rebuilt behind the menu
+fn board_preview_update(
+    board_config: Res<BoardConfig>,
+    theme: Res<Theme>,
+    mut query_preview: Query<(&mut Style, &mut BorderColor), With<BoardPreview>>,
+) {
+    if !board_config.is_changed() && !theme.is_changed() {
+        return;
+    }
+
+    for (mut style, mut border_color) in query_preview.iter_mut() {
+        style.width = Val::Px(board_config.width as f32 * PREVIEW_CELL_SIZE + PREVIEW_BORDER * 2.0);
+        style.height =
+            Val::Px(board_config.height as f32 * PREVIEW_CELL_SIZE + PREVIEW_BORDER * 2.0);
+        *border_color = theme.text.into();
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn menu_activate(
+    keys: Res<Input<KeyCode>>,
+    menu_selection: Res<MenuSelection>,
+    mut settings_screen: ResMut<SettingsScreen>,
+    mut transition: ResMut<Transition>,
+    mut leaderboard_screen: ResMut<LeaderboardScreen>,
+    last_game: Option<Res<LastGame>>,
+    mut game_seed: ResMut<GameSeed>,
+    mut config: ResMut<GameConfig>,
+    mut event_writer_exit: EventWriter<AppExit>,
+) {
+    if !keys.just_pressed(KeyCode::Return) {
+        return;
+    }
+
+    let item = MenuItem::ALL[menu_selection.index];
+    if !item.is_enabled(&config, last_game.is_some()) {
+        return;
+    }
+    match item {
+        MenuItem::Play => transition.start(AppState::Gaming, "play"),
+        MenuItem::ReplaySeed => {
+            if let Some(last_game) = last_game {
+                last_game.replay(&mut game_seed, &mut config);
+                transition.start(AppState::Gaming, "replay seed");
+            }
+        }
+        MenuItem::Settings => {
+            settings_screen.is_open = true;
+            settings_screen.selection = MenuSelection::default();
+        }
+        MenuItem::Leaderboard => leaderboard_screen.is_open = true,
+        MenuItem::Quit => event_writer_exit.send(AppExit),
+        MenuItem::Difficulty
+        | MenuItem::Race
+        | MenuItem::BoardWidth
+        | MenuItem::BoardHeight
+        | MenuItem::Previews => {}
+    }
+}
+
+fn settings_input(
+    mut keys: ResMut<Input<KeyCode>>,
+    mut config: ResMut<GameConfig>,
+    mut settings_screen: ResMut<SettingsScreen>,
+) {
+    settings_screen
+        .selection
+        .navigate(&keys, SettingsItem::ALL.len());
+
+    let item = SettingsItem::ALL[settings_screen.selection.index];
+    if keys.just_pressed(KeyCode::Left) {
+        item.adjust(&mut config, -1);
+    }
+    if keys.just_pressed(KeyCode::Right) {
+        item.adjust(&mut config, 1);
+    }
+
+    if keys.just_pressed(KeyCode::Back) || keys.just_pressed(KeyCode::Return) {
+        settings_screen.is_open = false;
+        // the menu runs next with settings selected, it would open them again
+        keys.clear_just_pressed(KeyCode::Return);
+    }
+}
+
+/// the selected row is marked and drawn in the highlight color, disabled rows are dimmed
+fn highlighted(
+    label: String,
+    is_selected: bool,
+    is_enabled: bool,
+    theme: &Theme,
+) -> (String, Color) {
+    let value = if is_selected {
+        format!("> {} <", label)
+    } else {
+        label
+    };
+    let color = if !is_enabled {
+        theme.hold_disabled
+    } else if is_selected {
+        theme.gameover_text
+    } else {
+        theme.text
+    };
+    (value, color)
+}
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+fn menu_highlight(
+    menu_selection: Res<MenuSelection>,
+    settings_screen: Res<SettingsScreen>,
+    confirm: Res<DifficultyConfirm>,
+    config: Res<GameConfig>,
+    board_config: Res<BoardConfig>,
+    theme: Res<Theme>,
+    mut query_menu: Query<(&mut BackgroundColor, &mut Visibility, Has<SettingsMenu>), With<Menu>>,
+    mut query_text: ParamSet<(
+        Query<(&MenuItemText, &mut Text)>,
+        Query<(&SettingsItemText, &mut Text)>,
+        Query<&mut Text, With<DifficultyWarning>>,
+    )>,
+    state: Res<State<AppState>>,
+    last_game: Option<Res<LastGame>>,
+) {
+    if !menu_selection.is_changed()
+        && !settings_screen.is_changed()
+        && !confirm.is_changed()
+        && !config.is_changed()
+        && !board_config.is_changed()
+        && !theme.is_changed()
+        && !last_game
+            .as_ref()
+            .is_some_and(|last_game| last_game.is_changed())
+    {
+        return;
+    }
+
+    for (mut background_color, mut visibility, is_settings) in query_menu.iter_mut() {
+        *background_color = theme.board_background.into();
+        if is_settings && *state.get() == AppState::Menu {
+            *visibility = if settings_screen.is_open {
+                Visibility::Visible
+            } else {
+                Visibility::Hidden
+            };
+        }
+    }
+
+    for (item_text, mut text) in query_text.p0().iter_mut() {
+        let item = item_text.0;
+        let is_selected = MenuItem::ALL[menu_selection.index] == item;
+        let (value, color) = highlighted(
+            item.label(&board_config, &config),
+            is_selected,
+            item.is_enabled(&config, last_game.is_some()),
+            &theme,
+        );
+        text.sections[0].value = value;
+        text.sections[0].style.color = color;
+    }
+
+    for (item_text, mut text) in query_text.p1().iter_mut() {
+        let item = item_text.0;
+        let is_selected = SettingsItem::ALL[settings_screen.selection.index] == item;
+        let (value, color) = highlighted(item.label(&config), is_selected, true, &theme);
+        text.sections[0].value = value;
+        text.sections[0].style.color = color;
+    }
+
+    for mut text in query_text.p2().iter_mut() {
+        text.sections[0].value = if confirm.is_pending {
+            "custom settings will be lost, press again to switch".to_string()
+        } else {
+            String::new()
+        };
+        text.sections[0].style.color = theme.danger;
+    }
+}
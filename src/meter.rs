@@ -0,0 +1,152 @@
+use bevy::{prelude::*, sprite::Anchor};
+
+use crate::{
+    score::{combo_update, BackToBack, Combo, ComboBreakEvent},
+    theme::Theme,
+};
+
+pub struct MeterPlugin;
+
+impl Plugin for MeterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_meter)
+            .add_systems(Update, (meter_update.after(combo_update), theme_apply));
+    }
+}
+
+const METER_X: f32 = 195.0;
+const METER_Y: f32 = -150.0;
+const METER_WIDTH: f32 = 16.0;
+const METER_HEIGHT: f32 = 300.0;
+const METER_BORDER: f32 = 3.0;
+
+/// momentum that fills the meter, a back-to-back counts as much as two combo steps
+const METER_MAX: f32 = 10.0;
+const BACK_TO_BACK_WEIGHT: f32 = 2.0;
+
+/// fraction of the way to the target the fill moves each second
+const METER_SPEED: f32 = 6.0;
+/// seconds a broken combo holds the fill in red before it drains
+const BREAK_FLASH_DURATION: f32 = 0.4;
+
+/// the meter border and background, hidden while there is no momentum
+#[derive(Debug, Component)]
+pub struct Meter;
+
+/// the fill, `value` eases toward the momentum from the combo and back-to-back chain
+#[derive(Debug, Component, Default)]
+pub struct MeterFill {
+    pub value: f32,
+    /// running while a broken combo flashes the fill
+    pub break_flash: Option<Timer>,
+}
+
+fn setup_meter(mut commands: Commands, theme: Res<Theme>) {
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: theme.board_border,
+                    custom_size: Some(Vec2::new(
+                        METER_WIDTH + METER_BORDER * 2.0,
+                        METER_HEIGHT + METER_BORDER * 2.0,
+                    )),
+                    anchor: Anchor::BottomCenter,
+                    ..default()
+                },
+                transform: Transform::from_xyz(METER_X, METER_Y - METER_BORDER, 0.0),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            Meter,
+        ))
+        .with_children(|parent| {
+            parent.spawn(SpriteBundle {
+                sprite: Sprite {
+                    color: theme.board_background,
+                    custom_size: Some(Vec2::new(METER_WIDTH, METER_HEIGHT)),
+                    anchor: Anchor::BottomCenter,
+                    ..default()
+                },
+                transform: Transform::from_xyz(0.0, METER_BORDER, 0.1),
+                ..default()
+            });
+            parent.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        custom_size: Some(Vec2::new(METER_WIDTH, 0.0)),
+                        anchor: Anchor::BottomCenter,
+                        ..default()
+                    },
+                    transform: Transform::from_xyz(0.0, METER_BORDER, 0.2),
+                    ..default()
+                },
+                MeterFill::default(),
+            ));
+        });
+}
+
+fn meter_update(
+    mut query_meter: Query<&mut Visibility, With<Meter>>,
+    mut query_fill: Query<(&mut MeterFill, &mut Sprite)>,
+    mut event_reader: EventReader<ComboBreakEvent>,
+    combo: Res<Combo>,
+    back_to_back: Res<BackToBack>,
+    theme: Res<Theme>,
+    time: Res<Time>,
+) {
+    let momentum = combo.0.saturating_sub(1) as f32
+        + back_to_back.0.saturating_sub(1) as f32 * BACK_TO_BACK_WEIGHT;
+    let target = (momentum / METER_MAX).min(1.0);
+
+    let (mut fill, mut sprite) = query_fill.single_mut();
+    if !event_reader.is_empty() {
+        event_reader.clear();
+        fill.break_flash = Some(Timer::from_seconds(BREAK_FLASH_DURATION, TimerMode::Once));
+    }
+
+    // the fill holds its height in the danger color, then drains as usual
+    if let Some(break_flash) = &mut fill.break_flash {
+        if !break_flash.tick(time.delta()).finished() {
+            sprite.custom_size = Some(Vec2::new(METER_WIDTH, METER_HEIGHT * fill.value));
+            sprite.color = theme.danger;
+            return;
+        }
+        fill.break_flash = None;
+    }
+
+    let step = (METER_SPEED * time.delta_seconds()).min(1.0);
+    fill.value += (target - fill.value) * step;
+    if target == 0.0 && fill.value < 0.005 {
+        fill.value = 0.0;
+    }
+
+    sprite.custom_size = Some(Vec2::new(METER_WIDTH, METER_HEIGHT * fill.value));
+    // green at low momentum shifting through yellow to red when full
+    sprite.color = Color::hsl(120.0 * (1.0 - fill.value), 0.85, 0.5);
+
+    *query_meter.single_mut() = if fill.value > 0.0 {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+}
+
+fn theme_apply(
+    theme: Res<Theme>,
+    mut query_meter: Query<(&mut Sprite, &Children), With<Meter>>,
+    mut query_sprite: Query<&mut Sprite, (Without<Meter>, Without<MeterFill>)>,
+) {
+    if !theme.is_changed() {
+        return;
+    }
+
+    for (mut sprite, children) in query_meter.iter_mut() {
+        sprite.color = theme.board_border;
+        for &child in children.iter() {
+            if let Ok(mut sprite) = query_sprite.get_mut(child) {
+                sprite.color = theme.board_background;
+            }
+        }
+    }
+}
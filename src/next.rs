@@ -0,0 +1,171 @@
+use bevy::prelude::*;
+
+use crate::{
+    app_state::AppState,
+    brick::{BRICK_WIDTH, GRID_WIDTH},
+    config::{GameConfig, PREVIEW_COUNT_MAX},
+    randomizer::PieceRandomizer,
+    texture::{brick_sprite_bundle, BrickTexture},
+    theme::Theme,
+};
+
+pub struct NextPlugin;
+
+impl Plugin for NextPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                next_layout_toggle,
+                next_count_toggle.run_if(not(in_state(AppState::Gaming))),
+                next_panel_update,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// the small panel mirrors the hold panel on the other side of the board
+const PANEL_X: f32 = 260.0;
+const PANEL_Y: f32 = 250.0;
+const PANEL_SIZE: f32 = 110.0;
+const PANEL_CELL_WIDTH: f32 = 22.0;
+const PANEL_CELL_PADDING: f32 = 1.5;
+
+/// the big box holds the brick at board cell size, a bit lower so it stays clear of the
+/// top of the window
+const BIG_PANEL_X: f32 = 270.0;
+const BIG_PANEL_Y: f32 = 220.0;
+const BIG_PANEL_SIZE: f32 = GRID_WIDTH as f32 * 5.0;
+/// space between the panels of a longer preview, which stack downwards in small panels
+const PANEL_GAP: f32 = 10.0;
+
+#[derive(Debug, Component)]
+pub struct NextPanel;
+
+#[derive(Debug, Component)]
+pub struct NextCell;
+
+fn next_layout_toggle(keys: Res<Input<KeyCode>>, mut config: ResMut<GameConfig>) {
+    if keys.just_pressed(KeyCode::L) {
+        config.big_next_preview = !config.big_next_preview;
+    }
+}
+
+/// fixed while playing, so a score is multiplied by the preview count it was earned with
+fn next_count_toggle(keys: Res<Input<KeyCode>>, mut config: ResMut<GameConfig>) {
+    if keys.just_pressed(KeyCode::Y) {
+        config.preview_count = (config.preview_count + 1) % (PREVIEW_COUNT_MAX + 1);
+    }
+}
+
+/// redraw the panels and the bricks that spawn next, the first in the small panel or the
+/// big box and the rest in small panels below it
+#[allow(clippy::type_complexity)]
+fn next_panel_update(
+    mut commands: Commands,
+    query_next: Query<Entity, Or<(With<NextCell>, With<NextPanel>)>>,
+    piece_randomizer: Res<PieceRandomizer>,
+    config: Res<GameConfig>,
+    theme: Res<Theme>,
+    brick_texture: Res<BrickTexture>,
+) {
+    if !piece_randomizer.is_changed() && !config.is_changed() && !theme.is_changed() {
+        return;
+    }
+
+    for entity in query_next.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    let (first_x, first_y, first_size) = if config.big_next_preview {
+        (BIG_PANEL_X, BIG_PANEL_Y, BIG_PANEL_SIZE)
+    } else {
+        (PANEL_X, PANEL_Y, PANEL_SIZE)
+    };
+    let mut brick_type_index_iter = piece_randomizer.upcoming();
+
+    for slot in 0..config.preview_count {
+        let (panel_x, panel_y, panel_size, cell_width, cell_size) =
+            if slot == 0 && config.big_next_preview {
+                (
+                    BIG_PANEL_X,
+                    BIG_PANEL_Y,
+                    BIG_PANEL_SIZE,
+                    GRID_WIDTH as f32,
+                    BRICK_WIDTH as f32,
+                )
+            } else {
+                let panel_y = if slot == 0 {
+                    first_y
+                } else {
+                    first_y - first_size / 2.0 + PANEL_SIZE / 2.0
+                        - slot as f32 * (PANEL_SIZE + PANEL_GAP)
+                };
+                (
+                    first_x,
+                    panel_y,
+                    PANEL_SIZE,
+                    PANEL_CELL_WIDTH,
+                    PANEL_CELL_WIDTH - PANEL_CELL_PADDING * 2.0,
+                )
+            };
+
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: theme.board_background,
+                    custom_size: Some(Vec2::new(panel_size, panel_size)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(panel_x, panel_y, 0.1),
+                ..default()
+            },
+            NextPanel,
+        ));
+
+        let Some(brick_type_index) = brick_type_index_iter.next() else {
+            continue;
+        };
+        spawn_next_brick(
+            &mut commands,
+            &brick_texture,
+            &theme,
+            &config,
+            brick_type_index,
+            Vec2::new(panel_x, panel_y),
+            cell_width,
+            cell_size,
+        );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn spawn_next_brick(
+    commands: &mut Commands,
+    brick_texture: &BrickTexture,
+    theme: &Theme,
+    config: &GameConfig,
+    brick_type_index: usize,
+    center: Vec2,
+    cell_width: f32,
+    cell_size: f32,
+) {
+    let brick_shape = &config.rotation_system.brick_type_arr()[brick_type_index].brick_shape_arr[0];
+    // shown the way it looks on the board
+    let mirror_sign = if config.is_mirrored() { -1.0 } else { 1.0 };
+
+    for offset in brick_shape.centered_arr() {
+        let x = center.x + offset.x * mirror_sign * cell_width;
+        let y = center.y + offset.y * cell_width;
+        commands.spawn((
+            brick_sprite_bundle(
+                brick_texture,
+                theme.brick_arr[brick_type_index],
+                cell_size,
+                Transform::from_xyz(x, y, 0.2),
+            ),
+            NextCell,
+        ));
+    }
+}
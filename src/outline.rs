@@ -0,0 +1,94 @@
+use bevy::{prelude::*, transform::TransformSystem};
+
+use crate::{
+    brick::{BrickMoveable, BrickPos},
+    root::OnBoard,
+    texture::BrickSprite,
+    theme::Theme,
+};
+
+pub struct OutlinePlugin;
+
+impl Plugin for OutlinePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, outline_spawn).add_systems(
+            PostUpdate,
+            outline_sync.before(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// outlines sit right behind the cell they frame
+const OUTLINE_Z_OFFSET: f32 = 0.01;
+
+/// a border drawn behind a brick cell, a separate entity rather than a child so a despawned
+/// cell never leaves it behind
+#[derive(Debug, Component)]
+pub struct Outline(Entity);
+
+/// an outline of a cell on the board goes on the board too, so it turns with the cell
+#[allow(clippy::type_complexity)]
+fn outline_spawn(
+    mut commands: Commands,
+    query_brick: Query<Entity, Added<BrickSprite>>,
+    query_on_board: Query<(), Or<(With<BrickPos>, With<OnBoard>)>>,
+) {
+    for entity in query_brick.iter() {
+        let mut entity_commands = commands.spawn((
+            SpriteBundle {
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            Outline(entity),
+        ));
+        if query_on_board.contains(entity) {
+            entity_commands.insert(OnBoard);
+        }
+    }
+}
+
+/// follow the framed cell wherever it goes, cleared lines falling and the ghost included,
+/// and fade with it so faded cells don't show their outline
+#[allow(clippy::type_complexity)]
+fn outline_sync(
+    mut commands: Commands,
+    mut query_outline: Query<(
+        Entity,
+        &Outline,
+        &mut Transform,
+        &mut Sprite,
+        &mut Visibility,
+    )>,
+    query_brick: Query<
+        (&Transform, &Sprite, &Visibility, Option<&BrickMoveable>),
+        Without<Outline>,
+    >,
+    theme: Res<Theme>,
+) {
+    for (entity, outline, mut transform, mut sprite, mut visibility) in query_outline.iter_mut() {
+        let Ok((brick_transform, brick_sprite, brick_visibility, brick_movable)) =
+            query_brick.get(outline.0)
+        else {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        };
+
+        if theme.outline_width <= 0.0 {
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+
+        let color = if brick_movable.is_some() {
+            theme.active_outline
+        } else {
+            theme.outline
+        };
+        sprite.color = color.with_a(color.a() * brick_sprite.color.a());
+        sprite.custom_size = brick_sprite
+            .custom_size
+            .map(|size| size + Vec2::splat(theme.outline_width * 2.0));
+        *transform = *brick_transform;
+        transform.translation.z -= OUTLINE_Z_OFFSET;
+        *visibility = *brick_visibility;
+    }
+}
@@ -0,0 +1,124 @@
+use bevy::prelude::*;
+
+use crate::{
+    app_state::AppState,
+    brick::{PieceLockEvent, RestartEvent},
+    hud::{StatsHud, StatsHudVisible},
+    score::Score,
+    theme::Theme,
+    transition::in_transition,
+};
+
+pub struct PacePlugin;
+
+impl Plugin for PacePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PaceStat>()
+            .add_systems(Startup, setup_ui)
+            .add_systems(Update, (pace_reset, theme_apply))
+            .add_systems(
+                Update,
+                (pace_time, pace_count)
+                    .chain()
+                    .run_if(in_state(AppState::Gaming).and_then(not(in_transition))),
+            )
+            .add_systems(Update, pace_text_update.after(pace_count).after(pace_reset));
+    }
+}
+
+#[derive(Debug, Component)]
+pub struct PaceText;
+
+/// locks and play time of this game, only counted while playing so it freezes on game over
+#[derive(Debug, Resource, Default)]
+pub struct PaceStat {
+    pub pieces: u32,
+    pub elapsed: f32,
+}
+
+impl PaceStat {
+    pub fn pieces_per_second(&self) -> f32 {
+        if self.elapsed <= 0.0 {
+            return 0.0;
+        }
+        self.pieces as f32 / self.elapsed
+    }
+
+    pub fn score_per_piece(&self, score: u32) -> f32 {
+        if self.pieces == 0 {
+            return 0.0;
+        }
+        score as f32 / self.pieces as f32
+    }
+
+    pub fn text(&self, score: u32) -> String {
+        format!(
+            "PPS: {:.2}  SPP: {:.1}",
+            self.pieces_per_second(),
+            self.score_per_piece(score)
+        )
+    }
+}
+
+fn setup_ui(mut commands: Commands, theme: Res<Theme>) {
+    commands.spawn((
+        TextBundle::from_section(
+            PaceStat::default().text(0),
+            TextStyle {
+                font_size: 30.0,
+                color: theme.text,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            top: Val::Px(460.0),
+            left: Val::Px(100.0),
+            ..default()
+        }),
+        PaceText,
+        StatsHud,
+    ));
+}
+
+fn pace_time(time: Res<Time>, mut pace_stat: ResMut<PaceStat>) {
+    pace_stat.elapsed += time.delta_seconds();
+}
+
+fn pace_count(mut event_reader: EventReader<PieceLockEvent>, mut pace_stat: ResMut<PaceStat>) {
+    pace_stat.pieces += event_reader.iter().count() as u32;
+}
+
+/// like the hud readout, only formatted while shown
+fn pace_text_update(
+    mut query_text: Query<&mut Text, With<PaceText>>,
+    pace_stat: Res<PaceStat>,
+    score: Res<Score>,
+    stats_hud_visible: Res<StatsHudVisible>,
+) {
+    if !stats_hud_visible.0
+        || (!pace_stat.is_changed() && !score.is_changed() && !stats_hud_visible.is_changed())
+    {
+        return;
+    }
+
+    query_text.single_mut().sections[0].value = pace_stat.text(score.get());
+}
+
+fn pace_reset(mut pace_stat: ResMut<PaceStat>, mut event_reader: EventReader<RestartEvent>) {
+    if event_reader.is_empty() {
+        return;
+    }
+    event_reader.clear();
+
+    *pace_stat = PaceStat::default();
+}
+
+fn theme_apply(theme: Res<Theme>, mut query_text: Query<&mut Text, With<PaceText>>) {
+    if !theme.is_changed() {
+        return;
+    }
+
+    for mut text in query_text.iter_mut() {
+        text.sections[0].style.color = theme.text;
+    }
+}
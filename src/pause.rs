@@ -0,0 +1,167 @@
+use bevy::prelude::*;
+
+use crate::{
+    action::{Action, ActionState},
+    app_state::AppState,
+    brick::{brick_gen, Paused},
+    config::GameConfig,
+    theme::Theme,
+    transition::in_transition,
+};
+
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ResumeCountdown>()
+            .add_systems(Startup, setup_overlay)
+            .add_systems(Update, (resume_countdown_toggle, theme_apply))
+            .add_systems(
+                Update,
+                (pause_toggle, resume_countdown_update)
+                    .chain()
+                    .before(brick_gen)
+                    .run_if(in_state(AppState::Gaming).and_then(not(in_transition))),
+            )
+            .add_systems(Update, pause_overlay_update.after(resume_countdown_update));
+    }
+}
+
+const PAUSE_DIM: f32 = 0.6;
+const RESUME_COUNTDOWN_MAX: u32 = 5;
+
+/// the seconds left before a resumed game plays again, unset while paused or playing
+#[derive(Debug, Resource, Default)]
+pub struct ResumeCountdown {
+    timer: Option<Timer>,
+}
+
+#[derive(Debug, Component)]
+pub struct PauseOverlay;
+
+#[derive(Debug, Component)]
+pub struct PauseText;
+
+fn setup_overlay(mut commands: Commands, theme: Res<Theme>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, PAUSE_DIM).into(),
+                visibility: Visibility::Hidden,
+                z_index: ZIndex::Global(50),
+                ..default()
+            },
+            PauseOverlay,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "Paused",
+                    TextStyle {
+                        font_size: 80.0,
+                        color: theme.text,
+                        ..default()
+                    },
+                ),
+                PauseText,
+            ));
+        });
+}
+
+fn resume_countdown_toggle(keys: Res<Input<KeyCode>>, mut config: ResMut<GameConfig>) {
+    if keys.just_pressed(KeyCode::F7) {
+        config.resume_countdown = (config.resume_countdown + 1) % (RESUME_COUNTDOWN_MAX + 1);
+    }
+}
+
+/// pausing is immediate, resuming counts down first unless the countdown is off, pressing
+/// pause again during the countdown stays paused
+fn pause_toggle(
+    action_state: Res<ActionState>,
+    mut paused: ResMut<Paused>,
+    mut resume_countdown: ResMut<ResumeCountdown>,
+    mut time: ResMut<Time>,
+    config: Res<GameConfig>,
+) {
+    if !action_state.just_pressed(Action::Pause) {
+        return;
+    }
+
+    if !paused.0 {
+        paused.0 = true;
+        time.pause();
+        return;
+    }
+
+    if resume_countdown.timer.take().is_some() {
+        return;
+    }
+
+    if config.resume_countdown == 0 {
+        paused.0 = false;
+        time.unpause();
+    } else {
+        resume_countdown.timer = Some(Timer::from_seconds(
+            config.resume_countdown as f32,
+            TimerMode::Once,
+        ));
+    }
+}
+
+fn resume_countdown_update(
+    mut paused: ResMut<Paused>,
+    mut resume_countdown: ResMut<ResumeCountdown>,
+    mut time: ResMut<Time>,
+) {
+    let Some(timer) = &mut resume_countdown.timer else {
+        return;
+    };
+
+    // game time stands still until the countdown is over, count on real time
+    timer.tick(time.raw_delta());
+    if timer.finished() {
+        resume_countdown.timer = None;
+        paused.0 = false;
+        time.unpause();
+    }
+}
+
+fn pause_overlay_update(
+    mut query_overlay: Query<&mut Visibility, With<PauseOverlay>>,
+    mut query_text: Query<&mut Text, With<PauseText>>,
+    paused: Res<Paused>,
+    resume_countdown: Res<ResumeCountdown>,
+) {
+    *query_overlay.single_mut() = if paused.0 {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+
+    let text = match &resume_countdown.timer {
+        Some(timer) => format!("{}", timer.remaining_secs().ceil() as u32),
+        None => "Paused".to_string(),
+    };
+    let mut pause_text = query_text.single_mut();
+    if pause_text.sections[0].value != text {
+        pause_text.sections[0].value = text;
+    }
+}
+
+fn theme_apply(theme: Res<Theme>, mut query_text: Query<&mut Text, With<PauseText>>) {
+    if !theme.is_changed() {
+        return;
+    }
+
+    for mut text in query_text.iter_mut() {
+        text.sections[0].style.color = theme.text;
+    }
+}
@@ -0,0 +1,79 @@
+use bevy::prelude::*;
+
+use crate::{
+    app_state::AppState,
+    board::{Board, BoardMetrics},
+    brick::{brick_gen, is_paused, BrickMoveable, BrickPos, SpawnEvent},
+    config::{BoardConfig, GameConfig},
+    quicksave::spawn_board_bricks,
+    texture::BrickTexture,
+    theme::Theme,
+    transition::in_transition,
+};
+
+/// loads a hardcoded position onto the board, only built with the `debug` feature
+pub struct PositionPlugin;
+
+impl Plugin for PositionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            position_load.after(brick_gen).run_if(
+                in_state(AppState::Gaming)
+                    .and_then(not(in_transition))
+                    .and_then(not(is_paused)),
+            ),
+        );
+    }
+}
+
+/// a t-spin double slot, the overhang keeps a dropped t out
+const DEBUG_POSITION: &str = "
+    ###.......
+    ##...#####
+    ###.######
+";
+
+/// replace the stack with the debug position and spawn a new brick at the top
+#[allow(clippy::too_many_arguments)]
+fn position_load(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    query_brick: Query<Entity, With<BrickPos>>,
+    query_brick_movable: Query<(), With<BrickMoveable>>,
+    mut board: ResMut<Board>,
+    mut board_metrics: ResMut<BoardMetrics>,
+    mut spawn_event_writer: EventWriter<SpawnEvent>,
+    brick_texture: Res<BrickTexture>,
+    theme: Res<Theme>,
+    board_config: Res<BoardConfig>,
+    config: Res<GameConfig>,
+) {
+    if !keys.just_pressed(KeyCode::F12) || query_brick_movable.is_empty() {
+        return;
+    }
+    let board_loaded = match Board::from_ascii(DEBUG_POSITION, &board_config) {
+        Ok(board_loaded) => board_loaded,
+        Err(err) => {
+            warn!("debug position not loaded: {}", err);
+            return;
+        }
+    };
+
+    for entity in query_brick.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    spawn_board_bricks(
+        &mut commands,
+        &board_loaded,
+        &brick_texture,
+        &theme,
+        &board_config,
+        &config,
+    );
+
+    *board = board_loaded;
+    *board_metrics = BoardMetrics::from_board(&board);
+
+    spawn_event_writer.send(SpawnEvent::default());
+}
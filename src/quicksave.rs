@@ -0,0 +1,150 @@
+use bevy::prelude::*;
+
+use crate::{
+    app_state::AppState,
+    board::{Board, BoardMetrics, Cell},
+    brick::{
+        brick_gen, get_brick_pos_xy, is_paused, BrickKind, BrickMoveable, BrickPos, BrickState,
+        GarbageBrick, RestartEvent, SpawnEvent, BRICK_WIDTH,
+    },
+    config::{BoardConfig, GameConfig},
+    texture::{brick_sprite_bundle, BrickTexture},
+    theme::Theme,
+    transition::in_transition,
+};
+
+pub struct QuickSavePlugin;
+
+impl Plugin for QuickSavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<QuickSave>()
+            .add_systems(Update, quick_save_reset)
+            .add_systems(
+                Update,
+                (quick_save, quick_load.after(brick_gen)).run_if(
+                    in_state(AppState::Gaming)
+                        .and_then(not(in_transition))
+                        .and_then(not(is_paused)),
+                ),
+            );
+    }
+}
+
+/// one in-memory slot for drilling a board, the stack and the brick that was falling
+#[derive(Debug, Resource, Default)]
+pub struct QuickSave {
+    slot: Option<(Board, usize)>,
+}
+
+fn quick_save(
+    keys: Res<Input<KeyCode>>,
+    query_brick_movable: Query<(), With<BrickMoveable>>,
+    board: Res<Board>,
+    brick_state: Res<BrickState>,
+    mut quick_save: ResMut<QuickSave>,
+) {
+    // between a lock and the next spawn the board is still settling
+    if !keys.just_pressed(KeyCode::F2) || query_brick_movable.is_empty() {
+        return;
+    }
+
+    quick_save.slot = Some((board.clone(), brick_state.brick_type_index));
+}
+
+/// put the saved stack back and spawn the saved brick at the top again
+#[allow(clippy::too_many_arguments)]
+fn quick_load(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    query_brick: Query<Entity, With<BrickPos>>,
+    query_brick_movable: Query<(), With<BrickMoveable>>,
+    quick_save: Res<QuickSave>,
+    mut board: ResMut<Board>,
+    mut board_metrics: ResMut<BoardMetrics>,
+    mut spawn_event_writer: EventWriter<SpawnEvent>,
+    brick_texture: Res<BrickTexture>,
+    theme: Res<Theme>,
+    board_config: Res<BoardConfig>,
+    config: Res<GameConfig>,
+) {
+    if !keys.just_pressed(KeyCode::F4) || query_brick_movable.is_empty() {
+        return;
+    }
+    let Some((board_saved, brick_type_index_saved)) = &quick_save.slot else {
+        return;
+    };
+
+    for entity in query_brick.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    spawn_board_bricks(
+        &mut commands,
+        board_saved,
+        &brick_texture,
+        &theme,
+        &board_config,
+        &config,
+    );
+
+    *board = board_saved.clone();
+    *board_metrics = BoardMetrics::from_board(&board);
+
+    spawn_event_writer.send(SpawnEvent {
+        brick_type_index: Some(*brick_type_index_saved),
+        ..default()
+    });
+}
+
+/// a stable brick for every filled cell, the bricks there before are despawned by the caller
+pub fn spawn_board_bricks(
+    commands: &mut Commands,
+    board: &Board,
+    brick_texture: &BrickTexture,
+    theme: &Theme,
+    board_config: &BoardConfig,
+    config: &GameConfig,
+) {
+    for y in 0..board.height() {
+        for x in 0..board.width() {
+            let Some(cell) = board.get(x, y) else {
+                continue;
+            };
+            let xy = get_brick_pos_xy(x, y, board_config, config.is_mirrored());
+            let transform = Transform::from_xyz(xy.0 as f32, xy.1 as f32, 1.0);
+            match cell {
+                Cell::Brick(brick_type_index) => commands.spawn((
+                    brick_sprite_bundle(
+                        brick_texture,
+                        theme.brick_arr[brick_type_index],
+                        BRICK_WIDTH as f32,
+                        transform,
+                    ),
+                    BrickPos::new(x, y),
+                    BrickKind(brick_type_index),
+                )),
+                Cell::Garbage => commands.spawn((
+                    brick_sprite_bundle(
+                        brick_texture,
+                        theme.garbage,
+                        BRICK_WIDTH as f32,
+                        transform,
+                    ),
+                    BrickPos::new(x, y),
+                    GarbageBrick,
+                )),
+            };
+        }
+    }
+}
+
+fn quick_save_reset(
+    mut quick_save: ResMut<QuickSave>,
+    mut event_reader: EventReader<RestartEvent>,
+) {
+    if event_reader.is_empty() {
+        return;
+    }
+    event_reader.clear();
+
+    *quick_save = QuickSave::default();
+}
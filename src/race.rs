@@ -0,0 +1,235 @@
+use bevy::prelude::*;
+
+use crate::{
+    app_state::AppState,
+    board::Cell,
+    brick::{get_brick_pos_xy, is_paused, restart, restart_input, ResetEvent, BRICK_WIDTH},
+    config::{BoardConfig, GameConfig, RaceReplay},
+    leaderboard::{best_run_file, BestRun, Leaderboard},
+    randomizer::GameSeed,
+    replay::{Replay, LAST_REPLAY_FILE},
+    root::OnBoard,
+    sim::{advance, SimState},
+    storage,
+    texture::{brick_sprite_bundle, BrickTexture},
+    theme::Theme,
+    transition::in_transition,
+};
+
+pub struct RacePlugin;
+
+impl Plugin for RacePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RaceGhost>()
+            .add_systems(
+                OnTransition {
+                    from: AppState::Menu,
+                    to: AppState::Gaming,
+                },
+                race_load.before(restart),
+            )
+            .add_systems(
+                OnTransition {
+                    from: AppState::GameOver,
+                    to: AppState::Gaming,
+                },
+                race_load.before(restart),
+            )
+            .add_systems(
+                Update,
+                race_load
+                    .after(restart_input)
+                    .before(restart)
+                    .run_if(on_event::<ResetEvent>()),
+            )
+            .add_systems(Update, (race_toggle, race_draw).chain())
+            .add_systems(
+                Update,
+                race_advance.before(race_draw).run_if(
+                    in_state(AppState::Gaming)
+                        .and_then(not(in_transition))
+                        .and_then(not(is_paused)),
+                ),
+            );
+    }
+}
+
+/// frames of a replay per second of play
+const REPLAY_FPS: f32 = 60.0;
+const RACE_ALPHA: f32 = 0.2;
+/// above the empty board, below the ghost and the live bricks
+const RACE_Z: f32 = 0.5;
+
+/// an earlier game played along with this one through `sim`, drawn faintly behind the live
+/// bricks, both deal the pieces of the replay's seed
+#[derive(Debug, Resource, Default)]
+pub struct RaceGhost {
+    replay: Option<Replay>,
+    sim_state: Option<SimState>,
+    action_index: usize,
+    /// replay frame of the next action
+    frame_next: u32,
+    elapsed: f32,
+    /// F5 hides or shows it for the current game
+    pub is_shown: bool,
+}
+
+/// one cell of the race ghost, not a `BrickSprite` so it gets no outline
+#[derive(Debug, Component)]
+pub struct RaceCell;
+
+/// every game starts the replay of `race_replay` over, a replay of another board size or
+/// randomizer is refused; before `restart` so the game is played, and recorded, with the
+/// seed of the replay and the player gets the pieces the ghost gets
+fn race_load(
+    mut race_ghost: ResMut<RaceGhost>,
+    mut game_seed: ResMut<GameSeed>,
+    board_config: Res<BoardConfig>,
+    config: Res<GameConfig>,
+    leaderboard: Res<Leaderboard>,
+) {
+    *race_ghost = RaceGhost::default();
+    let replay = match config.race_replay {
+        RaceReplay::Off => return,
+        RaceReplay::LastGame => Replay::load(LAST_REPLAY_FILE, &board_config),
+        RaceReplay::PersonalBest => personal_best_load(&leaderboard, &config, &board_config),
+    };
+    let replay = match replay {
+        Ok(replay) => replay,
+        Err(err) => {
+            warn!(
+                "{} race replay not loaded: {}",
+                config.race_replay.name(),
+                err
+            );
+            return;
+        }
+    };
+    if replay.randomizer != config.randomizer {
+        warn!(
+            "{} race replay not loaded: it was played with the {:?} randomizer, this game uses {:?}",
+            config.race_replay.name(),
+            replay.randomizer,
+            config.randomizer
+        );
+        return;
+    }
+
+    game_seed.replay = Some(replay.seed);
+    race_ghost.sim_state = Some(SimState::new(
+        replay.rotation_system,
+        replay.board_config,
+        replay.seed,
+        replay.randomizer,
+    ));
+    race_ghost.frame_next = replay.action_arr.first().map_or(0, |a| a.frame_delta);
+    race_ghost.replay = Some(replay);
+    race_ghost.is_shown = true;
+}
+
+/// the replay saved with the best score of the mode, of any assist profile
+fn personal_best_load(
+    leaderboard: &Leaderboard,
+    config: &GameConfig,
+    board_config: &BoardConfig,
+) -> Result<Replay, String> {
+    let best_score = leaderboard
+        .best_score(config.mode)
+        .ok_or_else(|| format!("no {} game on the leaderboard", config.mode.name()))?;
+    let name = best_run_file(config.mode, best_score);
+    storage::load::<BestRun>(&name)
+        .ok_or_else(|| format!("no best run saved as {}", name))?
+        .replay
+        .checked(board_config)
+}
+
+fn race_toggle(keys: Res<Input<KeyCode>>, mut race_ghost: ResMut<RaceGhost>) {
+    if keys.just_pressed(KeyCode::F5) && race_ghost.replay.is_some() {
+        race_ghost.is_shown = !race_ghost.is_shown;
+    }
+}
+
+/// run the actions up to the replay frame matching the play time of this game, only marked
+/// changed when an action ran so the cells aren't redrawn every frame
+fn race_advance(mut race_ghost_res: ResMut<RaceGhost>, time: Res<Time>) {
+    let race_ghost = race_ghost_res.bypass_change_detection();
+    let (Some(replay), Some(sim_state)) = (&race_ghost.replay, &mut race_ghost.sim_state) else {
+        return;
+    };
+
+    race_ghost.elapsed += time.delta_seconds();
+    let frame = (race_ghost.elapsed * REPLAY_FPS) as u32;
+    let mut is_advanced = false;
+    while let Some(replay_action) = replay.action_arr.get(race_ghost.action_index) {
+        if race_ghost.frame_next > frame {
+            break;
+        }
+        advance(sim_state, replay_action.action);
+        is_advanced = true;
+        race_ghost.action_index += 1;
+        race_ghost.frame_next += replay
+            .action_arr
+            .get(race_ghost.action_index)
+            .map_or(0, |a| a.frame_delta);
+    }
+
+    if is_advanced {
+        race_ghost_res.set_changed();
+    }
+}
+
+/// redraw the stack and the piece of the replay whenever it moved on
+fn race_draw(
+    mut commands: Commands,
+    query_cell: Query<Entity, With<RaceCell>>,
+    race_ghost: Res<RaceGhost>,
+    board_config: Res<BoardConfig>,
+    config: Res<GameConfig>,
+    theme: Res<Theme>,
+    brick_texture: Res<BrickTexture>,
+) {
+    if !race_ghost.is_changed() && !theme.is_changed() && !config.is_changed() {
+        return;
+    }
+
+    for entity in query_cell.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let Some(sim_state) = &race_ghost.sim_state else {
+        return;
+    };
+    if !race_ghost.is_shown {
+        return;
+    }
+
+    let mut cell_arr = vec![];
+    for y in 0..sim_state.board.height().min(board_config.height) {
+        for x in 0..sim_state.board.width() {
+            let color = match sim_state.board.get(x, y) {
+                Some(Cell::Brick(brick_type_index)) => theme.brick_arr[brick_type_index],
+                Some(Cell::Garbage) => theme.garbage,
+                None => continue,
+            };
+            cell_arr.push((x, y, color));
+        }
+    }
+    if let Some(piece) = sim_state.piece {
+        for pos in piece.brick_pos_arr(sim_state.rotation_system) {
+            if pos.y < board_config.height {
+                cell_arr.push((pos.x, pos.y, theme.brick_arr[piece.brick_type_index]));
+            }
+        }
+    }
+
+    for (x, y, color) in cell_arr {
+        let xy = get_brick_pos_xy(x, y, &board_config, config.is_mirrored());
+        let (sprite_bundle, _) = brick_sprite_bundle(
+            &brick_texture,
+            color.with_a(RACE_ALPHA),
+            BRICK_WIDTH as f32,
+            Transform::from_xyz(xy.0 as f32, xy.1 as f32, RACE_Z),
+        );
+        commands.spawn((sprite_bundle, RaceCell, OnBoard));
+    }
+}
@@ -0,0 +1,346 @@
+use std::{collections::VecDeque, fmt::Debug};
+
+use bevy::prelude::*;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    config::{GameConfig, GameMode},
+    storage,
+};
+
+pub struct RandomizerPlugin;
+
+impl Plugin for RandomizerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameRng>()
+            .init_resource::<GameSeed>()
+            .init_resource::<PieceRandomizer>()
+            .add_systems(PreStartup, last_game_load)
+            .add_systems(OnEnter(AppState::GameOver), last_game_record)
+            .add_systems(Update, randomizer_select);
+    }
+}
+
+const LAST_GAME_FILE: &str = "last_game.json";
+
+const BRICK_TYPE_COUNT: usize = 7;
+const S_INDEX: usize = 4;
+const Z_INDEX: usize = 5;
+/// line, J, L and T
+const TGM_FIRST_ARR: [usize; 4] = [1, 2, 3, 6];
+
+const HISTORY_LEN: usize = 4;
+const HISTORY_TRIES: u32 = 4;
+
+/// every random choice of the game but the pieces goes through this, seed it to replay the
+/// same game
+#[derive(Debug, Resource)]
+pub struct GameRng(pub StdRng);
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self(StdRng::from_entropy())
+    }
+}
+
+impl GameRng {
+    pub fn from_seed(seed: u64) -> Self {
+        Self(StdRng::seed_from_u64(seed))
+    }
+}
+
+/// seed of the game being played, `restart` reseeds `GameRng` and the pieces with `replay`
+/// when it is set and with a fresh one otherwise
+#[derive(Debug, Resource, Default)]
+pub struct GameSeed {
+    pub current: u64,
+    pub replay: Option<u64>,
+}
+
+/// seed and mode of the last finished game, saved so it can be replayed after a relaunch,
+/// only inserted once there is one
+#[derive(Debug, Clone, Copy, Resource, Serialize, Deserialize)]
+pub struct LastGame {
+    pub seed: u64,
+    pub mode: GameMode,
+}
+
+impl LastGame {
+    /// the next game deals the same pieces in the same mode
+    pub fn replay(&self, game_seed: &mut GameSeed, config: &mut GameConfig) {
+        game_seed.replay = Some(self.seed);
+        config.mode = self.mode;
+    }
+}
+
+/// picks the brick type of each spawn
+pub trait Randomizer: Debug + Send + Sync {
+    fn next(&mut self, rng: &mut StdRng) -> usize;
+
+    /// types left in the current bag, last one is dealt first, None without a bag
+    fn bag(&self) -> Option<&[usize]> {
+        None
+    }
+
+    /// a copy in the same state, so a simulation can be cloned mid-game
+    fn clone_box(&self) -> Box<dyn Randomizer>;
+}
+
+impl Clone for Box<dyn Randomizer> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// every type equally likely every time
+#[derive(Debug, Default, Clone)]
+pub struct PureRandom;
+
+impl Randomizer for PureRandom {
+    fn next(&mut self, rng: &mut StdRng) -> usize {
+        rng.gen_range(0..BRICK_TYPE_COUNT)
+    }
+
+    fn clone_box(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
+
+/// deals all seven types in a shuffled bag before refilling it
+#[derive(Debug, Default, Clone)]
+pub struct Bag7 {
+    bag: Vec<usize>,
+}
+
+impl Randomizer for Bag7 {
+    fn next(&mut self, rng: &mut StdRng) -> usize {
+        if self.bag.is_empty() {
+            self.bag = (0..BRICK_TYPE_COUNT).collect();
+            self.bag.shuffle(rng);
+        }
+        self.bag.pop().unwrap()
+    }
+
+    fn bag(&self) -> Option<&[usize]> {
+        Some(&self.bag)
+    }
+
+    fn clone_box(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
+
+/// rerolls a few times to avoid the last four types, and never opens with S, Z or quad
+#[derive(Debug, Clone)]
+pub struct TgmHistory {
+    history: [usize; HISTORY_LEN],
+    is_first: bool,
+}
+
+impl Default for TgmHistory {
+    fn default() -> Self {
+        Self {
+            history: [Z_INDEX, S_INDEX, Z_INDEX, S_INDEX],
+            is_first: true,
+        }
+    }
+}
+
+impl Randomizer for TgmHistory {
+    fn next(&mut self, rng: &mut StdRng) -> usize {
+        let brick_type_index = if self.is_first {
+            self.is_first = false;
+            *TGM_FIRST_ARR.choose(rng).unwrap()
+        } else {
+            let mut brick_type_index = rng.gen_range(0..BRICK_TYPE_COUNT);
+            for _ in 1..HISTORY_TRIES {
+                if !self.history.contains(&brick_type_index) {
+                    break;
+                }
+                brick_type_index = rng.gen_range(0..BRICK_TYPE_COUNT);
+            }
+            brick_type_index
+        };
+        self.history.rotate_right(1);
+        self.history[0] = brick_type_index;
+        brick_type_index
+    }
+
+    fn clone_box(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RandomizerKind {
+    #[default]
+    Bag7,
+    PureRandom,
+    TgmHistory,
+}
+
+impl RandomizerKind {
+    pub fn randomizer(self) -> Box<dyn Randomizer> {
+        match self {
+            RandomizerKind::Bag7 => Box::<Bag7>::default(),
+            RandomizerKind::PureRandom => Box::new(PureRandom),
+            RandomizerKind::TgmHistory => Box::<TgmHistory>::default(),
+        }
+    }
+}
+
+/// the randomizer selected in `GameConfig`, `restart` makes a new one for every game, `sim`
+/// deals its pieces through one too
+#[derive(Debug, Clone, Resource)]
+pub struct PieceRandomizer {
+    pub kind: RandomizerKind,
+    randomizer: Box<dyn Randomizer>,
+    /// the pieces have their own rng seeded with the game seed, so whatever else the game
+    /// draws a simulation of the same seed deals the same pieces
+    rng: StdRng,
+    /// drawn ahead for the next preview, dealt front first before anything new is drawn
+    upcoming: VecDeque<usize>,
+}
+
+impl Default for PieceRandomizer {
+    fn default() -> Self {
+        Self::new(RandomizerKind::default(), rand::random())
+    }
+}
+
+impl PieceRandomizer {
+    pub fn new(kind: RandomizerKind, seed: u64) -> Self {
+        Self {
+            kind,
+            randomizer: kind.randomizer(),
+            rng: StdRng::seed_from_u64(seed),
+            upcoming: VecDeque::new(),
+        }
+    }
+
+    pub fn next(&mut self) -> usize {
+        self.upcoming
+            .pop_front()
+            .unwrap_or_else(|| self.randomizer.next(&mut self.rng))
+    }
+
+    /// draw brick types ahead of time without dealing them, until `count` are waiting,
+    /// they come out of the same randomizer in the same order so a bag stays intact
+    pub fn fill(&mut self, count: usize) {
+        while self.upcoming.len() < count {
+            self.upcoming.push_back(self.randomizer.next(&mut self.rng));
+        }
+    }
+
+    /// brick types that spawn next, in order
+    pub fn upcoming(&self) -> impl Iterator<Item = usize> + '_ {
+        self.upcoming.iter().copied()
+    }
+
+    /// types left in the current bag in the order they are dealt, the ones drawn ahead for
+    /// the next preview are already out
+    pub fn bag_remaining(&self) -> Option<Vec<usize>> {
+        self.randomizer
+            .bag()
+            .map(|bag| bag.iter().rev().copied().collect())
+    }
+
+    /// start over with a fresh bag or history, the rng goes on
+    pub fn reset(&mut self, kind: RandomizerKind) {
+        self.kind = kind;
+        self.randomizer = kind.randomizer();
+        self.upcoming.clear();
+    }
+}
+
+fn last_game_load(mut commands: Commands) {
+    if let Some(last_game) = storage::load::<LastGame>(LAST_GAME_FILE) {
+        commands.insert_resource(last_game);
+    }
+}
+
+fn last_game_record(mut commands: Commands, game_seed: Res<GameSeed>, config: Res<GameConfig>) {
+    let last_game = LastGame {
+        seed: game_seed.current,
+        mode: config.mode,
+    };
+    storage::save(LAST_GAME_FILE, &last_game);
+    commands.insert_resource(last_game);
+}
+
+/// a randomizer picked mid-game takes over from the next spawn
+fn randomizer_select(config: Res<GameConfig>, mut piece_randomizer: ResMut<PieceRandomizer>) {
+    if piece_randomizer.kind != config.randomizer {
+        piece_randomizer.reset(config.randomizer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DRAW_COUNT: usize = 7000;
+
+    fn draw_arr(randomizer: &mut dyn Randomizer, seed: u64) -> Vec<usize> {
+        let mut game_rng = GameRng::from_seed(seed);
+        (0..DRAW_COUNT)
+            .map(|_| randomizer.next(&mut game_rng.0))
+            .collect()
+    }
+
+    /// share of draws that repeat one of the four before them
+    fn repeat_ratio(draw_arr: &[usize]) -> f32 {
+        let repeat_count = (HISTORY_LEN..draw_arr.len())
+            .filter(|&i| draw_arr[i - HISTORY_LEN..i].contains(&draw_arr[i]))
+            .count();
+        repeat_count as f32 / (draw_arr.len() - HISTORY_LEN) as f32
+    }
+
+    #[test]
+    fn bag7_deals_every_type_once_per_bag() {
+        for seed in 0..10 {
+            let draw_arr = draw_arr(&mut Bag7::default(), seed);
+            for bag in draw_arr.chunks(BRICK_TYPE_COUNT) {
+                let mut bag = bag.to_vec();
+                bag.sort();
+                assert_eq!(bag, (0..BRICK_TYPE_COUNT).collect::<Vec<usize>>());
+            }
+        }
+    }
+
+    #[test]
+    fn pure_random_deals_every_type_about_as_often() {
+        let draw_arr = draw_arr(&mut PureRandom, 1);
+        let expected = DRAW_COUNT / BRICK_TYPE_COUNT;
+        for brick_type_index in 0..BRICK_TYPE_COUNT {
+            let count = draw_arr.iter().filter(|&&i| i == brick_type_index).count();
+            assert!(
+                count.abs_diff(expected) < expected / 8,
+                "type {} dealt {} times",
+                brick_type_index,
+                count
+            );
+        }
+    }
+
+    #[test]
+    fn tgm_history_opens_without_s_z_or_quad() {
+        for seed in 0..50 {
+            let mut game_rng = GameRng::from_seed(seed);
+            let first = TgmHistory::default().next(&mut game_rng.0);
+            assert!(TGM_FIRST_ARR.contains(&first));
+        }
+    }
+
+    #[test]
+    fn tgm_history_avoids_the_recent_types() {
+        // a pure random draw repeats one of the last four about half the time, four tries
+        // bring that down to (4/7)^4 at most
+        let pure_random_ratio = repeat_ratio(&draw_arr(&mut PureRandom, 2));
+        let tgm_ratio = repeat_ratio(&draw_arr(&mut TgmHistory::default(), 2));
+        assert!(pure_random_ratio > 0.4);
+        assert!(tgm_ratio < 0.13);
+    }
+}
@@ -0,0 +1,361 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    action::{Action, ActionState},
+    app_state::AppState,
+    brick::{is_paused, restart, BrickMoveable, BrickPos, NewPosEvent, ResetEvent, StableEvent},
+    config::{BoardConfig, GameConfig, GameMode},
+    hold::Hold,
+    randomizer::{GameSeed, RandomizerKind},
+    rotation::RotationSystem,
+    sim::{advance, SimAction, SimState},
+    storage,
+    transition::in_transition,
+};
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ReplayRecorder>()
+            .add_systems(
+                OnTransition {
+                    from: AppState::Menu,
+                    to: AppState::Gaming,
+                },
+                replay_start.after(restart),
+            )
+            .add_systems(
+                OnTransition {
+                    from: AppState::GameOver,
+                    to: AppState::Gaming,
+                },
+                replay_start.after(restart),
+            )
+            .add_systems(
+                Update,
+                replay_start.after(restart).run_if(on_event::<ResetEvent>()),
+            )
+            // PostUpdate, so the brick has moved, locked or been held for the frame
+            .add_systems(
+                PostUpdate,
+                replay_record.run_if(
+                    in_state(AppState::Gaming)
+                        .and_then(not(in_transition))
+                        .and_then(not(is_paused)),
+                ),
+            )
+            .add_systems(OnEnter(AppState::GameOver), replay_save);
+    }
+}
+
+/// the replay of the last finished game
+pub const LAST_REPLAY_FILE: &str = "last_replay.json";
+
+/// bumped whenever the format or the rules it replays change, older replays are refused
+pub const REPLAY_VERSION: u32 = 2;
+
+/// an action and the frames since the one before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayAction {
+    pub frame_delta: u32,
+    pub action: SimAction,
+}
+
+/// everything needed to play a game again through `sim`: the header it was played with
+/// and every action in order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    pub seed: u64,
+    pub board_config: BoardConfig,
+    pub mode: GameMode,
+    pub rotation_system: RotationSystem,
+    pub randomizer: RandomizerKind,
+    pub action_arr: Vec<ReplayAction>,
+    /// frame of the last recorded action, only needed while recording
+    #[serde(skip)]
+    frame_last: u32,
+}
+
+impl Replay {
+    pub fn new(
+        seed: u64,
+        board_config: BoardConfig,
+        mode: GameMode,
+        rotation_system: RotationSystem,
+        randomizer: RandomizerKind,
+    ) -> Self {
+        Self {
+            version: REPLAY_VERSION,
+            seed,
+            board_config,
+            mode,
+            rotation_system,
+            randomizer,
+            action_arr: vec![],
+            frame_last: 0,
+        }
+    }
+
+    /// add an action done on `frame`, frames must not go backwards
+    pub fn record(&mut self, frame: u32, action: SimAction) {
+        let frame_delta = frame.saturating_sub(self.frame_last);
+        self.frame_last = self.frame_last.max(frame);
+        self.action_arr.push(ReplayAction {
+            frame_delta,
+            action,
+        });
+    }
+
+    /// saved like any other data, a file on desktop and a localStorage key on the web
+    pub fn save(&self, name: &str) {
+        storage::save(name, self);
+    }
+
+    /// read a replay and make sure it can be played on a board of `board_config`
+    pub fn load(name: &str, board_config: &BoardConfig) -> Result<Self, String> {
+        storage::load::<Replay>(name)
+            .ok_or_else(|| format!("no replay saved as {}", name))?
+            .checked(board_config)
+    }
+
+    /// refuse a replay of another version or board, and pick up recording where it ended
+    pub fn checked(mut self, board_config: &BoardConfig) -> Result<Self, String> {
+        if self.version != REPLAY_VERSION {
+            return Err(format!(
+                "replay version {} is not supported, expected {}",
+                self.version, REPLAY_VERSION
+            ));
+        }
+        if self.board_config != *board_config {
+            return Err(format!(
+                "replay was recorded on a {}x{} board with {} hidden rows, this board is {}x{} with {}",
+                self.board_config.width,
+                self.board_config.height,
+                self.board_config.vanish_height,
+                board_config.width,
+                board_config.height,
+                board_config.vanish_height
+            ));
+        }
+
+        self.frame_last = self
+            .action_arr
+            .iter()
+            .map(|replay_action| replay_action.frame_delta)
+            .sum();
+        Ok(self)
+    }
+
+    /// run every action through a fresh simulation and return where it ended
+    pub fn play(&self) -> SimState {
+        let mut sim_state = SimState::new(
+            self.rotation_system,
+            self.board_config,
+            self.seed,
+            self.randomizer,
+        );
+        for replay_action in self.action_arr.iter() {
+            advance(&mut sim_state, replay_action.action);
+        }
+        sim_state
+    }
+}
+
+/// the game being played, as the `sim` actions that lead to the same board
+#[derive(Debug, Resource, Default)]
+pub struct ReplayRecorder {
+    pub replay: Option<Replay>,
+    frame: u32,
+    /// cells of the moveable brick after its last step, None until a new brick shows up
+    brick_pos_last_arr: Option<[BrickPos; 4]>,
+    can_hold_last: bool,
+}
+
+/// after `restart` picked the seed of the game, so the replay deals the pieces the game dealt
+fn replay_start(
+    mut recorder: ResMut<ReplayRecorder>,
+    game_seed: Res<GameSeed>,
+    board_config: Res<BoardConfig>,
+    config: Res<GameConfig>,
+) {
+    *recorder = ReplayRecorder {
+        replay: Some(Replay::new(
+            game_seed.current,
+            *board_config,
+            config.mode,
+            config.rotation_system,
+            config.randomizer,
+        )),
+        can_hold_last: true,
+        ..ReplayRecorder::default()
+    };
+}
+
+fn sorted(mut brick_pos_arr: [BrickPos; 4]) -> [BrickPos; 4] {
+    brick_pos_arr.sort_by_key(|pos| (pos.x, pos.y));
+    brick_pos_arr
+}
+
+/// turn each step of the brick into actions: a turn is a rotation, each column a move and
+/// each row down a tick; a lock is a hard drop, the brick already rests where it locks
+#[allow(clippy::too_many_arguments)]
+fn replay_record(
+    query_movable: Query<&BrickPos, With<BrickMoveable>>,
+    action_state: Res<ActionState>,
+    hold: Res<Hold>,
+    mut new_pos_event_reader: EventReader<NewPosEvent>,
+    mut stable_event_reader: EventReader<StableEvent>,
+    mut recorder: ResMut<ReplayRecorder>,
+) {
+    let recorder = recorder.as_mut();
+    recorder.frame += 1;
+    let Some(replay) = recorder.replay.as_mut() else {
+        return;
+    };
+    let frame = recorder.frame;
+
+    for new_pos_event in new_pos_event_reader.iter() {
+        let brick_pos_arr = sorted(new_pos_event.0);
+        let Some(brick_pos_last_arr) = recorder.brick_pos_last_arr.replace(brick_pos_arr) else {
+            continue;
+        };
+
+        let offset_x = brick_pos_arr[0].x - brick_pos_last_arr[0].x;
+        let offset_y = brick_pos_arr[0].y - brick_pos_last_arr[0].y;
+        let is_shift =
+            brick_pos_arr
+                .iter()
+                .zip(brick_pos_last_arr.iter())
+                .all(|(pos, pos_last)| {
+                    pos.x - pos_last.x == offset_x && pos.y - pos_last.y == offset_y
+                });
+        if !is_shift {
+            // the sim turns one way only, three turns make a counter clockwise one
+            let turn_count = if action_state.just_pressed(Action::RotateCcw) {
+                3
+            } else {
+                1
+            };
+            for _ in 0..turn_count {
+                replay.record(frame, SimAction::Rotate);
+            }
+            continue;
+        }
+
+        let move_action = if offset_x < 0 {
+            SimAction::MoveLeft
+        } else {
+            SimAction::MoveRight
+        };
+        for _ in 0..offset_x.abs() {
+            replay.record(frame, move_action);
+        }
+        for _ in 0..(-offset_y).max(0) {
+            replay.record(frame, SimAction::Tick);
+        }
+    }
+
+    if stable_event_reader.iter().count() > 0 {
+        replay.record(frame, SimAction::HardDrop);
+        recorder.brick_pos_last_arr = None;
+    } else if recorder.can_hold_last && !hold.can_hold {
+        replay.record(frame, SimAction::Hold);
+        recorder.brick_pos_last_arr = None;
+    } else if recorder.brick_pos_last_arr.is_none() {
+        // a new brick can't move on the frame it spawns, it starts where it is
+        let brick_pos_arr = query_movable.iter().copied().collect::<Vec<BrickPos>>();
+        recorder.brick_pos_last_arr = brick_pos_arr.try_into().ok().map(sorted);
+    }
+    recorder.can_hold_last = hold.can_hold;
+}
+
+pub fn replay_save(mut recorder: ResMut<ReplayRecorder>) {
+    if let Some(replay) = recorder.replay.take() {
+        replay.save(LAST_REPLAY_FILE);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEED: u64 = 7;
+
+    /// play a few pieces through `sim` while recording them, like a game would
+    fn recorded_game() -> (Replay, SimState) {
+        let board_config = BoardConfig::default();
+        let mut replay = Replay::new(
+            SEED,
+            board_config,
+            GameMode::default(),
+            RotationSystem::Srs,
+            RandomizerKind::TgmHistory,
+        );
+        let mut sim_state = SimState::new(
+            RotationSystem::Srs,
+            board_config,
+            SEED,
+            RandomizerKind::TgmHistory,
+        );
+        let action_arr = [
+            SimAction::MoveLeft,
+            SimAction::MoveLeft,
+            SimAction::HardDrop,
+            SimAction::Rotate,
+            SimAction::MoveRight,
+            SimAction::Tick,
+            SimAction::HardDrop,
+            SimAction::Hold,
+            SimAction::SoftDrop,
+            SimAction::HardDrop,
+        ];
+        for (i, &action) in action_arr.iter().enumerate() {
+            advance(&mut sim_state, action);
+            replay.record(i as u32 * 10, action);
+        }
+        (replay, sim_state)
+    }
+
+    fn round_trip(replay: &Replay) -> Replay {
+        serde_json::from_str(&serde_json::to_string(replay).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn a_saved_replay_plays_the_same_game() {
+        let (replay, sim_state) = recorded_game();
+
+        let replay_loaded = round_trip(&replay)
+            .checked(&BoardConfig::default())
+            .unwrap();
+
+        assert_eq!(replay_loaded, replay);
+        let sim_state_played = replay_loaded.play();
+        assert!(!sim_state_played.board.is_empty());
+        assert_eq!(sim_state_played.board, sim_state.board);
+        assert_eq!(sim_state_played.hold, sim_state.hold);
+    }
+
+    #[test]
+    fn a_replay_of_another_version_is_refused() {
+        let (mut replay, _) = recorded_game();
+        replay.version = REPLAY_VERSION + 1;
+
+        assert!(round_trip(&replay)
+            .checked(&BoardConfig::default())
+            .is_err());
+    }
+
+    #[test]
+    fn a_replay_of_another_board_is_refused() {
+        let (replay, _) = recorded_game();
+        let board_config = BoardConfig {
+            width: BoardConfig::default().width + 2,
+            ..BoardConfig::default()
+        };
+
+        assert!(round_trip(&replay).checked(&board_config).is_err());
+    }
+}
@@ -0,0 +1,45 @@
+use bevy::{prelude::*, transform::TransformSystem};
+
+use crate::brick::BrickPos;
+
+pub struct RootPlugin;
+
+impl Plugin for RootPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PreStartup, setup_board_root).add_systems(
+            PostUpdate,
+            // adopted before the first frame they are drawn, so they never show unparented
+            (board_root_adopt, apply_deferred)
+                .chain()
+                .before(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// parent of everything drawn on the board, it stays at the origin so the children keep the
+/// coordinates of `get_brick_pos_xy`, moving or turning it takes the whole board along while
+/// `BrickPos` and collision never change
+#[derive(Debug, Component)]
+pub struct BoardRoot;
+
+/// drawn on the board and put under `BoardRoot` once spawned, bricks are found by their
+/// `BrickPos` and don't need it
+#[derive(Debug, Component)]
+pub struct OnBoard;
+
+fn setup_board_root(mut commands: Commands) {
+    commands.spawn((SpatialBundle::default(), BoardRoot));
+}
+
+/// children despawn with `despawn_recursive`, which also takes them off the root
+#[allow(clippy::type_complexity)]
+fn board_root_adopt(
+    mut commands: Commands,
+    query_root: Query<Entity, With<BoardRoot>>,
+    query_new: Query<Entity, (Without<Parent>, Or<(Added<BrickPos>, Added<OnBoard>)>)>,
+) {
+    let root = query_root.single();
+    for entity in query_new.iter() {
+        commands.entity(root).add_child(entity);
+    }
+}
@@ -0,0 +1,363 @@
+use lazy_static::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    brick::{BrickPos, BrickShape, BrickType},
+    config::BoardConfig,
+};
+
+/// which shapes, spawn orientations and wall kicks the rotation code uses
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RotationSystem {
+    /// super rotation system, four states per brick, spawns flat side down, with wall kicks
+    #[default]
+    Srs,
+    /// two states for S, Z and line, spawns pointing down, no kicks
+    Classic,
+}
+
+impl RotationSystem {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RotationSystem::Srs => "SRS",
+            RotationSystem::Classic => "Classic",
+        }
+    }
+
+    /// shapes of every brick type, index 0 is the spawn orientation
+    pub fn brick_type_arr(&self) -> &'static Vec<BrickType> {
+        match self {
+            RotationSystem::Srs => &SRS_BRICK_TYPE_ARRAY,
+            RotationSystem::Classic => &CLASSIC_BRICK_TYPE_ARRAY,
+        }
+    }
+
+    /// the point each brick type turns around
+    pub fn pivot(&self, brick_type_index: usize) -> Pivot {
+        match self {
+            RotationSystem::Srs => SRS_PIVOT_ARRAY[brick_type_index],
+            RotationSystem::Classic if brick_type_index == QUAD_INDEX => Pivot::Fixed,
+            // the classic shapes are listed by hand and only turn around the cell
+            RotationSystem::Classic => Pivot::Cell(1, 1),
+        }
+    }
+
+    /// origin offsets to try, in order, when rotating clockwise out of `brick_shape_index`
+    pub fn kick_arr(
+        &self,
+        brick_type_index: usize,
+        brick_shape_index: usize,
+    ) -> &'static [BrickPos] {
+        match self {
+            RotationSystem::Srs if brick_type_index == LINE_INDEX => {
+                &SRS_LINE_KICK_ARRAY[brick_shape_index]
+            }
+            RotationSystem::Srs if brick_type_index != QUAD_INDEX => {
+                &SRS_KICK_ARRAY[brick_shape_index]
+            }
+            _ => &NO_KICK_ARRAY,
+        }
+    }
+
+    /// kicks for turning counter clockwise into brick_shape_index, the clockwise kicks out of
+    /// it reversed
+    pub fn kick_ccw_arr(
+        &self,
+        brick_type_index: usize,
+        brick_shape_index: usize,
+    ) -> impl Iterator<Item = BrickPos> {
+        self.kick_arr(brick_type_index, brick_shape_index)
+            .iter()
+            .map(|&kick| BrickPos::default() - kick)
+    }
+
+    /// origin row a new brick spawns at, its top cells in the first row of the vanish zone
+    pub fn spawn_y(&self, board_config: &BoardConfig) -> i8 {
+        let top = board_config.height + board_config.vanish_height.min(1);
+        match self {
+            RotationSystem::Srs => top - 3,
+            RotationSystem::Classic => top - 2,
+        }
+    }
+}
+
+/// the point a brick type turns around, relative to the brick origin
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pivot {
+    /// the center of cell x, y
+    Cell(i8, i8),
+    /// the corner between four cells, top right of cell x, y, as for the line
+    Corner(i8, i8),
+    /// rotating doesn't move the brick, as for the quad
+    Fixed,
+}
+
+impl Pivot {
+    /// rotate a cell clockwise around the pivot, done in half cells so corners stay integer
+    pub fn rotate_cw(&self, brick_pos: BrickPos) -> BrickPos {
+        let (pivot_x2, pivot_y2) = match *self {
+            Pivot::Cell(x, y) => (x * 2, y * 2),
+            Pivot::Corner(x, y) => (x * 2 + 1, y * 2 + 1),
+            Pivot::Fixed => return brick_pos,
+        };
+        let x2 = brick_pos.x * 2 - pivot_x2;
+        let y2 = brick_pos.y * 2 - pivot_y2;
+        BrickPos::new((pivot_x2 + y2) / 2, (pivot_y2 - x2) / 2)
+    }
+}
+
+const QUAD_INDEX: usize = 0;
+const LINE_INDEX: usize = 1;
+
+/// quad, line, J, L, S, Z and T
+static SRS_PIVOT_ARRAY: [Pivot; 7] = [
+    Pivot::Fixed,
+    Pivot::Corner(1, 1),
+    Pivot::Cell(1, 1),
+    Pivot::Cell(1, 1),
+    Pivot::Cell(1, 1),
+    Pivot::Cell(1, 1),
+    Pivot::Cell(1, 1),
+];
+
+/// the spawn shape and its clockwise rotations around the pivot of the brick type
+fn srs_brick_type(brick_type_index: usize, brick_pos_arr: [(i8, i8); 4]) -> BrickType {
+    let pivot = SRS_PIVOT_ARRAY[brick_type_index];
+    let mut brick_shape_arr = vec![BrickShape::new(
+        brick_pos_arr.map(|(x, y)| BrickPos::new(x, y)),
+    )];
+    if pivot != Pivot::Fixed {
+        for _ in 0..3 {
+            let brick_pos_arr = brick_shape_arr.last().unwrap().brick_pos_arr;
+            brick_shape_arr.push(BrickShape::new(
+                brick_pos_arr.map(|pos| pivot.rotate_cw(pos)),
+            ));
+        }
+    }
+    BrickType::new(brick_shape_arr)
+}
+
+const fn kick(x: i8, y: i8) -> BrickPos {
+    BrickPos { x, y }
+}
+
+static NO_KICK_ARRAY: [BrickPos; 1] = [kick(0, 0)];
+
+/// J, L, S, Z and T kicks for 0->R, R->2, 2->L and L->0
+static SRS_KICK_ARRAY: [[BrickPos; 5]; 4] = [
+    [
+        kick(0, 0),
+        kick(-1, 0),
+        kick(-1, 1),
+        kick(0, -2),
+        kick(-1, -2),
+    ],
+    [kick(0, 0), kick(1, 0), kick(1, -1), kick(0, 2), kick(1, 2)],
+    [kick(0, 0), kick(1, 0), kick(1, 1), kick(0, -2), kick(1, -2)],
+    [
+        kick(0, 0),
+        kick(-1, 0),
+        kick(-1, -1),
+        kick(0, 2),
+        kick(-1, 2),
+    ],
+];
+
+/// line kicks for 0->R, R->2, 2->L and L->0
+static SRS_LINE_KICK_ARRAY: [[BrickPos; 5]; 4] = [
+    [
+        kick(0, 0),
+        kick(-2, 0),
+        kick(1, 0),
+        kick(-2, -1),
+        kick(1, 2),
+    ],
+    [
+        kick(0, 0),
+        kick(-1, 0),
+        kick(2, 0),
+        kick(-1, 2),
+        kick(2, -1),
+    ],
+    [
+        kick(0, 0),
+        kick(2, 0),
+        kick(-1, 0),
+        kick(2, 1),
+        kick(-1, -2),
+    ],
+    [
+        kick(0, 0),
+        kick(1, 0),
+        kick(-2, 0),
+        kick(1, -2),
+        kick(-2, 1),
+    ],
+];
+
+lazy_static! {
+    pub static ref SRS_BRICK_TYPE_ARRAY: Vec<BrickType> = vec![
+        // quard
+        srs_brick_type(0, [(1, 1), (1, 2), (2, 1), (2, 2)]),
+        // line
+        srs_brick_type(1, [(0, 2), (1, 2), (2, 2), (3, 2)]),
+        // J
+        srs_brick_type(2, [(0, 2), (0, 1), (1, 1), (2, 1)]),
+        // L
+        srs_brick_type(3, [(2, 2), (0, 1), (1, 1), (2, 1)]),
+        // S
+        srs_brick_type(4, [(0, 1), (1, 1), (1, 2), (2, 2)]),
+        // Z
+        srs_brick_type(5, [(0, 2), (1, 2), (1, 1), (2, 1)]),
+        // T
+        srs_brick_type(6, [(0, 1), (1, 1), (2, 1), (1, 2)]),
+    ];
+
+    pub static ref CLASSIC_BRICK_TYPE_ARRAY: Vec<BrickType> = vec![
+        // quard
+        BrickType::new(vec![BrickShape::new([BrickPos::new(1, 0), BrickPos::new(1, 1), BrickPos::new(2, 0), BrickPos::new(2, 1)])]),
+        // line
+        BrickType::new(vec![
+           BrickShape::new([BrickPos::new(0, 1), BrickPos::new(1, 1), BrickPos::new(2, 1), BrickPos::new(3, 1)]),
+           BrickShape::new([BrickPos::new(2, 0), BrickPos::new(2, 1), BrickPos::new(2, 2), BrickPos::new(2, 3)]),
+        ]),
+
+        // J
+        BrickType::new(vec![
+           BrickShape::new([BrickPos::new(0, 1), BrickPos::new(1, 1), BrickPos::new(2, 1), BrickPos::new(2, 0)]),
+           BrickShape::new([BrickPos::new(1, 0), BrickPos::new(1, 1), BrickPos::new(1, 2), BrickPos::new(0, 0)]),
+           BrickShape::new([BrickPos::new(0, 1), BrickPos::new(1, 1), BrickPos::new(2, 1), BrickPos::new(0, 2)]),
+           BrickShape::new([BrickPos::new(1, 0), BrickPos::new(1, 1), BrickPos::new(1, 2), BrickPos::new(2, 2)]),
+        ]),
+
+        // L
+        BrickType::new(vec![
+           BrickShape::new([BrickPos::new(0, 1), BrickPos::new(1, 1), BrickPos::new(2, 1), BrickPos::new(0, 0)]),
+           BrickShape::new([BrickPos::new(1, 0), BrickPos::new(1, 1), BrickPos::new(1, 2), BrickPos::new(0, 2)]),
+           BrickShape::new([BrickPos::new(0, 1), BrickPos::new(1, 1), BrickPos::new(2, 1), BrickPos::new(2, 2)]),
+           BrickShape::new([BrickPos::new(1, 0), BrickPos::new(1, 1), BrickPos::new(1, 2), BrickPos::new(2, 0)]),
+        ]),
+
+        // S
+        BrickType::new(vec![
+           BrickShape::new([BrickPos::new(0, 0), BrickPos::new(1, 0), BrickPos::new(1, 1), BrickPos::new(2, 1)]),
+           BrickShape::new([BrickPos::new(1, 2), BrickPos::new(1, 1), BrickPos::new(2, 1), BrickPos::new(2, 0)]),
+        ]),
+
+        // Z
+        BrickType::new(vec![
+           BrickShape::new([BrickPos::new(0, 1), BrickPos::new(1, 1), BrickPos::new(1, 0), BrickPos::new(2, 0)]),
+           BrickShape::new([BrickPos::new(2, 2), BrickPos::new(2, 1), BrickPos::new(1, 1), BrickPos::new(1, 0)]),
+        ]),
+
+        // T
+        BrickType::new(vec![
+           BrickShape::new([BrickPos::new(0, 1), BrickPos::new(1, 1), BrickPos::new(2, 1), BrickPos::new(1, 0)]),
+           BrickShape::new([BrickPos::new(1, 0), BrickPos::new(1, 1), BrickPos::new(1, 2), BrickPos::new(0, 1)]),
+           BrickShape::new([BrickPos::new(0, 1), BrickPos::new(1, 1), BrickPos::new(2, 1), BrickPos::new(1, 2)]),
+           BrickShape::new([BrickPos::new(1, 0), BrickPos::new(1, 1), BrickPos::new(1, 2), BrickPos::new(2, 1)]),
+        ]),
+
+
+    ];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        randomizer::RandomizerKind,
+        sim::{advance, Piece, SimAction, SimState},
+    };
+
+    /// the piece after one rotate on an empty board
+    fn rotated(piece: Piece) -> Piece {
+        let mut sim_state = SimState::new(
+            RotationSystem::Srs,
+            BoardConfig::default(),
+            0,
+            RandomizerKind::default(),
+        );
+        sim_state.piece = Some(piece);
+        advance(&mut sim_state, SimAction::Rotate);
+        sim_state.piece.unwrap()
+    }
+
+    fn cell_arr(piece: &Piece) -> Vec<(i8, i8)> {
+        let mut cell_arr = piece
+            .brick_pos_arr(RotationSystem::Srs)
+            .iter()
+            .map(|pos| (pos.x, pos.y))
+            .collect::<Vec<(i8, i8)>>();
+        cell_arr.sort();
+        cell_arr
+    }
+
+    #[test]
+    fn line_turns_around_the_corner_pivot() {
+        let brick_shape_arr = &RotationSystem::Srs.brick_type_arr()[LINE_INDEX].brick_shape_arr;
+
+        assert_eq!(brick_shape_arr.len(), 4);
+        // vertical in the third column, then flat in the second row
+        assert!(brick_shape_arr[1]
+            .brick_pos_arr
+            .iter()
+            .all(|pos| pos.x == 2));
+        assert!(brick_shape_arr[2]
+            .brick_pos_arr
+            .iter()
+            .all(|pos| pos.y == 1));
+    }
+
+    #[test]
+    fn line_spun_four_times_in_the_open_is_back_where_it_started() {
+        let piece = Piece {
+            brick_type_index: LINE_INDEX,
+            brick_shape_index: 0,
+            origin: BrickPos::new(3, 8),
+        };
+
+        let mut piece_new = piece;
+        for _ in 0..4 {
+            piece_new = rotated(piece_new);
+        }
+
+        assert_eq!(piece_new, piece);
+    }
+
+    #[test]
+    fn line_against_the_left_wall_is_kicked_back_onto_the_board() {
+        // vertical in column 0
+        let piece = Piece {
+            brick_type_index: LINE_INDEX,
+            brick_shape_index: 1,
+            origin: BrickPos::new(-2, 8),
+        };
+
+        let piece_new = rotated(piece);
+
+        // turning in place would stick out by two, the R->2 kicks try one left, then two right
+        assert_eq!(piece_new.brick_shape_index, 2);
+        assert_eq!(piece_new.origin, BrickPos::new(0, 8));
+        assert_eq!(cell_arr(&piece_new), vec![(0, 9), (1, 9), (2, 9), (3, 9)]);
+    }
+
+    #[test]
+    fn quad_against_the_wall_doesnt_turn_or_move() {
+        let piece = Piece {
+            brick_type_index: QUAD_INDEX,
+            brick_shape_index: 0,
+            origin: BrickPos::new(-1, 0),
+        };
+
+        assert_eq!(RotationSystem::Srs.pivot(QUAD_INDEX), Pivot::Fixed);
+        assert_eq!(
+            RotationSystem::Srs.brick_type_arr()[QUAD_INDEX]
+                .brick_shape_arr
+                .len(),
+            1
+        );
+        assert_eq!(cell_arr(&piece), vec![(0, 1), (0, 2), (1, 1), (1, 2)]);
+        assert_eq!(rotated(piece), piece);
+    }
+}
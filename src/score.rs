@@ -1,31 +1,126 @@
 use bevy::prelude::*;
 
-use crate::brick::FullLineRemoveEvent;
+use crate::{
+    app_state::AppState,
+    board::PerfectClearEvent,
+    brick::{FullLineRemoveEvent, LevelUpEvent, PieceLockEvent, RestartEvent, SoftDropEvent},
+    config::{GameConfig, GameMode},
+    hud::StatsHud,
+    theme::Theme,
+    transition::Transition,
+};
 
 pub struct ScorePlugin;
 
 impl Plugin for ScorePlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<Score>()
+            .init_resource::<Level>()
+            .init_resource::<Lines>()
+            .init_resource::<Combo>()
+            .init_resource::<BackToBack>()
+            .init_resource::<Cleared>()
+            .init_resource::<ClearStats>()
             .add_event::<FullLineRemoveEvent>()
+            .add_event::<ComboEvent>()
+            .add_event::<ComboBreakEvent>()
             .add_systems(Startup, setup_ui)
-            .add_systems(Update, score_up);
+            .add_systems(
+                Update,
+                (
+                    scoring_mode_toggle,
+                    score_up.before(lines_up),
+                    soft_drop_score_up,
+                    lines_up,
+                    combo_update,
+                    perfect_clear_count,
+                    score_reset,
+                    theme_apply,
+                ),
+            )
+            .add_systems(
+                Update,
+                (score_text_update, level_text_update, lines_text_update)
+                    .after(score_up)
+                    .after(soft_drop_score_up)
+                    .after(lines_up)
+                    .after(score_reset),
+            );
     }
 }
 
+const LINES_PER_LEVEL: u32 = 10;
+
 #[derive(Debug, Component)]
 pub struct ScoreText;
 
+#[derive(Debug, Component)]
+pub struct LevelText;
+
+#[derive(Debug, Component)]
+pub struct LinesText;
+
 #[derive(Debug, Resource, Default)]
 pub struct Score(u32);
 
-pub fn setup_ui(mut commands: Commands) {
+impl Score {
+    pub fn get(&self) -> u32 {
+        self.0
+    }
+}
+
+#[derive(Debug, Resource)]
+pub struct Level(pub u32);
+
+impl Default for Level {
+    fn default() -> Self {
+        Self(1)
+    }
+}
+
+#[derive(Debug, Resource, Default)]
+pub struct Lines(pub u32);
+
+/// number of consecutive locks that cleared at least one line
+#[derive(Debug, Resource, Default)]
+pub struct Combo(pub u32);
+
+/// number of tetrises in a row, a smaller line clear breaks the chain, a lock without
+/// clears keeps it
+#[derive(Debug, Resource, Default)]
+pub struct BackToBack(pub u32);
+
+/// set when target mode reached its line count, the game ends as a win
+#[derive(Debug, Resource, Default)]
+pub struct Cleared(pub bool);
+
+/// count and points of each line clear size this game, index 0 for singles
+#[derive(Debug, Resource, Default)]
+pub struct ClearStats {
+    pub count_arr: [u32; 4],
+    pub points_arr: [u32; 4],
+    pub perfect_clears: u32,
+}
+
+impl ClearStats {
+    pub const NAME_ARR: [&'static str; 4] = ["Single", "Double", "Triple", "Tetris"];
+}
+
+/// sent when a combo extends, with the combo count (1 for the second clear in a row)
+#[derive(Event)]
+pub struct ComboEvent(pub u32);
+
+/// sent when a lock without clears ends a combo, with the combo count it reached
+#[derive(Event)]
+pub struct ComboBreakEvent(pub u32);
+
+pub fn setup_ui(mut commands: Commands, theme: Res<Theme>) {
     commands.spawn((
         TextBundle::from_section(
             "Score: 0",
             TextStyle {
                 font_size: 50.0,
-                color: Color::WHITE,
+                color: theme.text,
                 ..default()
             },
         )
@@ -36,24 +131,261 @@ pub fn setup_ui(mut commands: Commands) {
         }),
         ScoreText,
     ));
+
+    commands.spawn((
+        TextBundle::from_section(
+            "Level: 1",
+            TextStyle {
+                font_size: 40.0,
+                color: theme.text,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            top: Val::Px(360.0),
+            left: Val::Px(100.0),
+            ..default()
+        }),
+        LevelText,
+        StatsHud,
+    ));
+
+    commands.spawn((
+        TextBundle::from_section(
+            "Lines: 0",
+            TextStyle {
+                font_size: 40.0,
+                color: theme.text,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            top: Val::Px(410.0),
+            left: Val::Px(100.0),
+            ..default()
+        }),
+        LinesText,
+        StatsHud,
+    ));
 }
 
+fn scoring_mode_toggle(keys: Res<Input<KeyCode>>, mut config: ResMut<GameConfig>) {
+    if keys.just_pressed(KeyCode::N) {
+        config.scoring_mode = config.scoring_mode.next();
+    }
+}
+
+/// clears score at the level they were made on, so this runs before `lines_up`
 pub fn score_up(
-    mut score_text_query: Query<&mut Text, With<ScoreText>>,
     mut event_reader: EventReader<FullLineRemoveEvent>,
     mut score: ResMut<Score>,
+    mut clear_stats: ResMut<ClearStats>,
+    level: Res<Level>,
+    config: Res<GameConfig>,
 ) {
     if event_reader.is_empty() {
         return;
     }
-    let fullline_remove_event = event_reader.iter().next().unwrap();
 
-    score.0 += if fullline_remove_event.0 == 4 {
-        2_u32.pow(fullline_remove_event.0 as u32) as u32
+    for fullline_remove_event in event_reader.iter() {
+        let points = config
+            .scoring_mode
+            .line_clear_points(fullline_remove_event.0, level.0)
+            * config.preview_multiplier_percent()
+            / 100;
+        score.0 += points;
+
+        let index = (fullline_remove_event.0 as usize).clamp(1, 4) - 1;
+        clear_stats.count_arr[index] += 1;
+        clear_stats.points_arr[index] += points;
+    }
+}
+
+/// one point for each cell soft dropped by the player
+pub fn soft_drop_score_up(mut event_reader: EventReader<SoftDropEvent>, mut score: ResMut<Score>) {
+    for soft_drop_event in event_reader.iter() {
+        score.0 += soft_drop_event.0 as u32;
+    }
+}
+
+fn lines_text(lines: u32, config: &GameConfig) -> String {
+    if config.mode == GameMode::Target {
+        format!("Lines: {} / {}", lines, config.line_target)
     } else {
-        2_u32.pow((fullline_remove_event.0 - 1) as u32) as u32
-    };
+        format!("Lines: {}", lines)
+    }
+}
+
+pub fn lines_up(
+    mut event_reader: EventReader<FullLineRemoveEvent>,
+    mut event_writer_level_up: EventWriter<LevelUpEvent>,
+    mut lines: ResMut<Lines>,
+    mut level: ResMut<Level>,
+    mut cleared: ResMut<Cleared>,
+    mut transition: ResMut<Transition>,
+    config: Res<GameConfig>,
+) {
+    if event_reader.is_empty() {
+        return;
+    }
+    for fullline_remove_event in event_reader.iter() {
+        lines.0 += fullline_remove_event.0 as u32;
+    }
+
+    let level_new = lines.0 / LINES_PER_LEVEL + config.start_level;
+    if level_new > level.0 {
+        level.0 = level_new;
+        event_writer_level_up.send(LevelUpEvent(level_new));
+    }
+
+    if config.mode == GameMode::Target && lines.0 >= config.line_target && !cleared.0 {
+        cleared.0 = true;
+        transition.start(AppState::GameOver, "line target reached");
+    }
+}
+
+pub fn combo_update(
+    mut event_reader: EventReader<PieceLockEvent>,
+    mut event_writer_combo: EventWriter<ComboEvent>,
+    mut event_writer_combo_break: EventWriter<ComboBreakEvent>,
+    mut combo: ResMut<Combo>,
+    mut back_to_back: ResMut<BackToBack>,
+) {
+    for piece_lock_event in event_reader.iter() {
+        if piece_lock_event.lines == 0 {
+            // a single clear on its own was never a combo
+            if combo.0 > 1 {
+                event_writer_combo_break.send(ComboBreakEvent(combo.0 - 1));
+            }
+            combo.0 = 0;
+            continue;
+        }
+
+        if piece_lock_event.lines == 4 {
+            back_to_back.0 += 1;
+        } else {
+            back_to_back.0 = 0;
+        }
 
-    let mut text = score_text_query.single_mut();
-    text.sections[0].value = format!("Score: {}", score.0);
+        combo.0 += 1;
+        if combo.0 > 1 {
+            event_writer_combo.send(ComboEvent(combo.0 - 1));
+        }
+    }
+}
+
+fn perfect_clear_count(
+    mut event_reader: EventReader<PerfectClearEvent>,
+    mut clear_stats: ResMut<ClearStats>,
+) {
+    clear_stats.perfect_clears += event_reader.iter().count() as u32;
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn score_reset(
+    mut event_reader: EventReader<RestartEvent>,
+    mut score: ResMut<Score>,
+    mut level: ResMut<Level>,
+    mut lines: ResMut<Lines>,
+    mut combo: ResMut<Combo>,
+    mut back_to_back: ResMut<BackToBack>,
+    mut cleared: ResMut<Cleared>,
+    mut clear_stats: ResMut<ClearStats>,
+    config: Res<GameConfig>,
+) {
+    if event_reader.is_empty() {
+        return;
+    }
+    event_reader.clear();
+
+    *score = Score::default();
+    *level = Level(config.start_level);
+    *lines = Lines::default();
+    *combo = Combo::default();
+    *back_to_back = BackToBack::default();
+    *cleared = Cleared::default();
+    *clear_stats = ClearStats::default();
+}
+
+/// the labels are only formatted again on the frames their value changed
+fn score_text_update(mut query_text: Query<&mut Text, With<ScoreText>>, score: Res<Score>) {
+    if !score.is_changed() {
+        return;
+    }
+
+    query_text.single_mut().sections[0].value = format!("Score: {}", score.0);
+}
+
+fn level_text_update(mut query_text: Query<&mut Text, With<LevelText>>, level: Res<Level>) {
+    if !level.is_changed() {
+        return;
+    }
+
+    query_text.single_mut().sections[0].value = format!("Level: {}", level.0);
+}
+
+/// target mode shows the goal too, so a change of mode or target redraws it
+fn lines_text_update(
+    mut query_text: Query<&mut Text, With<LinesText>>,
+    lines: Res<Lines>,
+    config: Res<GameConfig>,
+) {
+    if !lines.is_changed() && !config.is_changed() {
+        return;
+    }
+
+    query_text.single_mut().sections[0].value = lines_text(lines.0, &config);
+}
+
+#[allow(clippy::type_complexity)]
+pub fn theme_apply(
+    theme: Res<Theme>,
+    mut query_text: Query<&mut Text, Or<(With<ScoreText>, With<LevelText>, With<LinesText>)>>,
+) {
+    if !theme.is_changed() {
+        return;
+    }
+
+    for mut text in query_text.iter_mut() {
+        for section in text.sections.iter_mut() {
+            section.style.color = theme.text;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// score of a double at level 1 with `preview_count` bricks shown ahead
+    fn double_score(preview_count: usize) -> (u32, u32) {
+        let mut app = App::new();
+        app.init_resource::<Score>()
+            .init_resource::<ClearStats>()
+            .init_resource::<Level>()
+            .insert_resource(GameConfig {
+                preview_count,
+                ..GameConfig::default()
+            })
+            .add_event::<FullLineRemoveEvent>()
+            .add_systems(Update, score_up);
+
+        app.world
+            .resource_mut::<Events<FullLineRemoveEvent>>()
+            .send(FullLineRemoveEvent(2));
+        app.update();
+
+        (
+            app.world.resource::<Score>().get(),
+            app.world.resource::<ClearStats>().points_arr[1],
+        )
+    }
+
+    #[test]
+    fn line_clear_points_are_scaled_by_the_preview_multiplier() {
+        // 300 for a double, 150% without a preview, 100% with one and 80% with five
+        assert_eq!(double_score(0), (450, 450));
+        assert_eq!(double_score(1), (300, 300));
+        assert_eq!(double_score(5), (240, 240));
+    }
 }
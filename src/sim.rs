@@ -0,0 +1,420 @@
+use std::collections::{HashSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    board::{Board, Cell},
+    brick::BrickPos,
+    config::BoardConfig,
+    randomizer::{PieceRandomizer, RandomizerKind},
+    rotation::{Pivot, RotationSystem},
+};
+
+/// one input to the simulation, `Tick` is a gravity step of one row
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SimAction {
+    MoveLeft,
+    MoveRight,
+    Rotate,
+    SoftDrop,
+    HardDrop,
+    /// to the floor without locking
+    SonicDrop,
+    Hold,
+    Tick,
+}
+
+/// what an action led to, in the order it happened
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameEvent {
+    Spawned { brick_type_index: usize },
+    Locked { lines: u8 },
+    LinesCleared(u8),
+    GameOver,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Piece {
+    pub brick_type_index: usize,
+    pub brick_shape_index: usize,
+    pub origin: BrickPos,
+}
+
+impl Piece {
+    pub fn brick_pos_arr(&self, rotation_system: RotationSystem) -> [BrickPos; 4] {
+        let brick_shape = &rotation_system.brick_type_arr()[self.brick_type_index].brick_shape_arr
+            [self.brick_shape_index];
+        brick_shape.brick_pos_arr.map(|pos| pos + self.origin)
+    }
+}
+
+/// the rules of the game without bevy, a bot or test runner drives it through `advance`;
+/// a resting piece locks on the next tick or soft drop, there is no lock delay
+#[derive(Debug, Clone)]
+pub struct SimState {
+    pub board: Board,
+    pub piece: Option<Piece>,
+    pub hold: Option<usize>,
+    pub can_hold: bool,
+    pub rotation_system: RotationSystem,
+    pub board_config: BoardConfig,
+    pub is_game_over: bool,
+    piece_randomizer: PieceRandomizer,
+}
+
+impl SimState {
+    /// an empty board with the first piece spawned, the pieces are those of a live game with
+    /// the same seed and randomizer
+    pub fn new(
+        rotation_system: RotationSystem,
+        board_config: BoardConfig,
+        seed: u64,
+        randomizer: RandomizerKind,
+    ) -> Self {
+        let mut sim_state = Self {
+            board: Board::new(board_config.width, board_config.total_height()),
+            piece: None,
+            hold: None,
+            can_hold: true,
+            rotation_system,
+            board_config,
+            is_game_over: false,
+            piece_randomizer: PieceRandomizer::new(randomizer, seed),
+        };
+        let brick_type_index = sim_state.next_brick_type_index();
+        sim_state.spawn(brick_type_index, &mut vec![]);
+        sim_state
+    }
+
+    fn next_brick_type_index(&mut self) -> usize {
+        self.piece_randomizer.next()
+    }
+
+    fn fits(&self, piece: &Piece) -> bool {
+        fits(&self.board, self.rotation_system, piece)
+    }
+
+    /// move the piece if the new place is free
+    fn try_move(&mut self, offset: BrickPos) -> bool {
+        let Some(piece) = self.piece else {
+            return false;
+        };
+        let Some(piece_new) = moved(&self.board, self.rotation_system, &piece, offset) else {
+            return false;
+        };
+        self.piece = Some(piece_new);
+        true
+    }
+
+    fn rotate(&mut self) {
+        let Some(piece) = self.piece else {
+            return;
+        };
+        if let Some(piece_new) = rotated(&self.board, self.rotation_system, &piece) {
+            self.piece = Some(piece_new);
+        }
+    }
+
+    /// spawn reaching into the vanish zone and drop a row if there's room, like `brick_gen`
+    fn spawn(&mut self, brick_type_index: usize, event_arr: &mut Vec<GameEvent>) {
+        let mut piece = Piece {
+            brick_type_index,
+            brick_shape_index: 0,
+            origin: BrickPos::new(
+                self.board_config.spawn_x(),
+                self.board_config.spawn_y(self.rotation_system),
+            ),
+        };
+        if !self.fits(&piece) {
+            self.piece = None;
+            self.is_game_over = true;
+            event_arr.push(GameEvent::GameOver);
+            return;
+        }
+
+        let piece_down = Piece {
+            origin: piece.origin + BrickPos::new(0, -1),
+            ..piece
+        };
+        if self.fits(&piece_down) {
+            piece = piece_down;
+        }
+
+        self.piece = Some(piece);
+        event_arr.push(GameEvent::Spawned { brick_type_index });
+    }
+
+    fn lock(&mut self, event_arr: &mut Vec<GameEvent>) {
+        let Some(piece) = self.piece.take() else {
+            return;
+        };
+        let brick_pos_arr = piece.brick_pos_arr(self.rotation_system);
+        for pos in brick_pos_arr {
+            self.board
+                .set(pos.x, pos.y, Some(Cell::Brick(piece.brick_type_index)));
+        }
+
+        let topout_row = self.board_config.topout_row();
+        let is_lock_out = brick_pos_arr.iter().all(|pos| pos.y >= topout_row);
+
+        let lines = self.board.clear_full_lines();
+        event_arr.push(GameEvent::Locked { lines });
+        if lines > 0 {
+            event_arr.push(GameEvent::LinesCleared(lines));
+        }
+
+        // like `brick_stable`, the lock still counts before the game ends
+        if is_lock_out {
+            self.is_game_over = true;
+            event_arr.push(GameEvent::GameOver);
+            return;
+        }
+
+        self.can_hold = true;
+        let brick_type_index = self.next_brick_type_index();
+        self.spawn(brick_type_index, event_arr);
+    }
+
+    fn hold(&mut self, event_arr: &mut Vec<GameEvent>) {
+        let Some(piece) = self.piece else {
+            return;
+        };
+        if !self.can_hold {
+            return;
+        }
+
+        let brick_type_index = match self.hold.replace(piece.brick_type_index) {
+            Some(brick_type_index) => brick_type_index,
+            None => self.next_brick_type_index(),
+        };
+        self.can_hold = false;
+        self.spawn(brick_type_index, event_arr);
+    }
+}
+
+fn fits(board: &Board, rotation_system: RotationSystem, piece: &Piece) -> bool {
+    piece
+        .brick_pos_arr(rotation_system)
+        .iter()
+        .all(|pos| board.is_inside(pos.x, pos.y) && !board.is_filled(pos.x, pos.y))
+}
+
+/// the piece moved by the offset, if the new place is free
+pub fn moved(
+    board: &Board,
+    rotation_system: RotationSystem,
+    piece: &Piece,
+    offset: BrickPos,
+) -> Option<Piece> {
+    let piece_new = Piece {
+        origin: piece.origin + offset,
+        ..*piece
+    };
+    fits(board, rotation_system, &piece_new).then_some(piece_new)
+}
+
+/// the piece rotated clockwise with the first kick that fits
+pub fn rotated(board: &Board, rotation_system: RotationSystem, piece: &Piece) -> Option<Piece> {
+    if rotation_system.pivot(piece.brick_type_index) == Pivot::Fixed {
+        return None;
+    }
+    let brick_shape_count = rotation_system.brick_type_arr()[piece.brick_type_index]
+        .brick_shape_arr
+        .len();
+    rotation_system
+        .kick_arr(piece.brick_type_index, piece.brick_shape_index)
+        .iter()
+        .map(|&kick| Piece {
+            brick_type_index: piece.brick_type_index,
+            brick_shape_index: (piece.brick_shape_index + 1) % brick_shape_count,
+            origin: piece.origin + kick,
+        })
+        .find(|piece_new| fits(board, rotation_system, piece_new))
+}
+
+/// every place the piece can get to by moves, rotations and soft drops, found by BFS, with
+/// whether it rests on something there and could lock, for a bot picking a placement
+pub fn reachable_arr(
+    board: &Board,
+    rotation_system: RotationSystem,
+    piece: &Piece,
+) -> Vec<(Piece, bool)> {
+    if !fits(board, rotation_system, piece) {
+        return vec![];
+    }
+
+    let mut seen = HashSet::from([*piece]);
+    let mut queue = VecDeque::from([*piece]);
+    let mut reachable_arr = vec![];
+    while let Some(piece) = queue.pop_front() {
+        let down = moved(board, rotation_system, &piece, BrickPos::new(0, -1));
+        reachable_arr.push((piece, down.is_none()));
+
+        let next_arr = [
+            moved(board, rotation_system, &piece, BrickPos::new(-1, 0)),
+            moved(board, rotation_system, &piece, BrickPos::new(1, 0)),
+            down,
+            rotated(board, rotation_system, &piece),
+        ];
+        for piece_next in next_arr.into_iter().flatten() {
+            if seen.insert(piece_next) {
+                queue.push_back(piece_next);
+            }
+        }
+    }
+    reachable_arr
+}
+
+/// apply one action and return the events it caused, nothing happens after game over
+pub fn advance(sim_state: &mut SimState, action: SimAction) -> Vec<GameEvent> {
+    let mut event_arr = vec![];
+    if sim_state.is_game_over {
+        return event_arr;
+    }
+
+    match action {
+        SimAction::MoveLeft => {
+            sim_state.try_move(BrickPos::new(-1, 0));
+        }
+        SimAction::MoveRight => {
+            sim_state.try_move(BrickPos::new(1, 0));
+        }
+        SimAction::Rotate => sim_state.rotate(),
+        SimAction::SoftDrop | SimAction::Tick => {
+            if !sim_state.try_move(BrickPos::new(0, -1)) {
+                sim_state.lock(&mut event_arr);
+            }
+        }
+        SimAction::HardDrop => {
+            while sim_state.try_move(BrickPos::new(0, -1)) {}
+            sim_state.lock(&mut event_arr);
+        }
+        SimAction::SonicDrop => while sim_state.try_move(BrickPos::new(0, -1)) {},
+        SimAction::Hold => sim_state.hold(&mut event_arr),
+    }
+
+    event_arr
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINE_INDEX: usize = 1;
+
+    fn sim_state(seed: u64) -> SimState {
+        SimState::new(
+            RotationSystem::Srs,
+            BoardConfig::default(),
+            seed,
+            RandomizerKind::default(),
+        )
+    }
+
+    #[test]
+    fn hard_drop_locks_and_spawns_the_next_piece() {
+        let mut sim_state = sim_state(1);
+
+        let event_arr = advance(&mut sim_state, SimAction::HardDrop);
+
+        assert_eq!(event_arr.len(), 2);
+        assert_eq!(event_arr[0], GameEvent::Locked { lines: 0 });
+        assert!(matches!(event_arr[1], GameEvent::Spawned { .. }));
+        assert!(!sim_state.board.is_empty());
+    }
+
+    #[test]
+    fn ticks_fall_until_the_piece_locks() {
+        let mut sim_state = sim_state(2);
+
+        let mut tick_count = 0;
+        let event_arr = loop {
+            let event_arr = advance(&mut sim_state, SimAction::Tick);
+            if !event_arr.is_empty() {
+                break event_arr;
+            }
+            tick_count += 1;
+            assert!(tick_count < 100);
+        };
+
+        assert!(tick_count > 0);
+        assert_eq!(event_arr[0], GameEvent::Locked { lines: 0 });
+        assert!(matches!(event_arr[1], GameEvent::Spawned { .. }));
+        assert!((0..sim_state.board.width()).any(|x| sim_state.board.is_filled(x, 0)));
+    }
+
+    #[test]
+    fn filling_a_row_clears_it() {
+        let mut sim_state = sim_state(3);
+        for x in 0..6 {
+            sim_state.board.set(x, 0, Some(Cell::Brick(0)));
+        }
+        sim_state.piece = Some(Piece {
+            brick_type_index: LINE_INDEX,
+            brick_shape_index: 0,
+            origin: BrickPos::new(6, 10),
+        });
+
+        let event_arr = advance(&mut sim_state, SimAction::HardDrop);
+
+        assert_eq!(event_arr.len(), 3);
+        assert_eq!(event_arr[0], GameEvent::Locked { lines: 1 });
+        assert_eq!(event_arr[1], GameEvent::LinesCleared(1));
+        assert!(matches!(event_arr[2], GameEvent::Spawned { .. }));
+        assert!(sim_state.board.is_empty());
+    }
+
+    #[test]
+    fn stacking_in_the_middle_ends_in_game_over() {
+        let mut sim_state = sim_state(4);
+
+        let mut event_arr = vec![];
+        for _ in 0..100 {
+            event_arr = advance(&mut sim_state, SimAction::HardDrop);
+            if sim_state.is_game_over {
+                break;
+            }
+            // the spawn columns never fill a row, so nothing clears on the way up
+            assert_eq!(event_arr[0], GameEvent::Locked { lines: 0 });
+            assert!(matches!(event_arr[1], GameEvent::Spawned { .. }));
+        }
+
+        assert!(sim_state.is_game_over);
+        assert_eq!(
+            event_arr,
+            vec![GameEvent::Locked { lines: 0 }, GameEvent::GameOver]
+        );
+        assert!(sim_state.piece.is_none());
+        assert!(advance(&mut sim_state, SimAction::HardDrop).is_empty());
+    }
+
+    #[test]
+    fn hold_swaps_once_per_piece() {
+        let mut sim_state = sim_state(5);
+        let brick_type_index = sim_state.piece.unwrap().brick_type_index;
+
+        let event_arr = advance(&mut sim_state, SimAction::Hold);
+        assert!(matches!(event_arr[..], [GameEvent::Spawned { .. }]));
+        assert_eq!(sim_state.hold, Some(brick_type_index));
+
+        assert!(advance(&mut sim_state, SimAction::Hold).is_empty());
+
+        advance(&mut sim_state, SimAction::HardDrop);
+        let event_arr = advance(&mut sim_state, SimAction::Hold);
+        assert_eq!(event_arr, vec![GameEvent::Spawned { brick_type_index }]);
+    }
+
+    #[test]
+    fn the_same_seed_deals_the_same_pieces() {
+        let mut sim_state_a = sim_state(6);
+        let mut sim_state_b = sim_state(6);
+
+        for _ in 0..10 {
+            assert_eq!(
+                advance(&mut sim_state_a, SimAction::HardDrop),
+                advance(&mut sim_state_b, SimAction::HardDrop)
+            );
+        }
+        assert_eq!(sim_state_a.board, sim_state_b.board);
+    }
+}
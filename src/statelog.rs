@@ -0,0 +1,39 @@
+use bevy::prelude::*;
+
+use crate::{app_state::AppState, transition::Transition};
+
+/// logs every change of `AppState` with the time and the reason the transition was started
+/// for, only built with the `debug` feature
+pub struct StateLogPlugin;
+
+impl Plugin for StateLogPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, state_log);
+    }
+}
+
+/// states are switched before `Update`, so a change shows up on the frame it happened, the
+/// first run logs the starting state
+fn state_log(
+    state: Res<State<AppState>>,
+    transition: Res<Transition>,
+    time: Res<Time>,
+    mut state_last: Local<Option<AppState>>,
+) {
+    let state_new = *state.get();
+    if *state_last == Some(state_new) {
+        return;
+    }
+
+    match *state_last {
+        Some(state_old) => info!(
+            "{:.3}s {:?} -> {:?}: {}",
+            time.elapsed_seconds(),
+            state_old,
+            state_new,
+            transition.reason()
+        ),
+        None => info!("{:.3}s start in {:?}", time.elapsed_seconds(), state_new),
+    }
+    *state_last = Some(state_new);
+}
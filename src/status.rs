@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+
+use crate::{
+    app_state::AppState,
+    brick::{BrickMoveable, BrickState},
+    hold::Hold,
+    randomizer::PieceRandomizer,
+    score::{BackToBack, Combo, Level, Lines, Score},
+};
+
+pub struct StatusPlugin;
+
+impl Plugin for StatusPlugin {
+    fn build(&self, app: &mut App) {
+        // Last, so every event of the frame has been handled by the systems it feeds
+        app.init_resource::<GameStatus>()
+            .add_systems(Last, status_update);
+    }
+}
+
+/// read-only snapshot of the game for overlays and tests, rewritten every frame
+#[derive(Debug, Resource, Clone, PartialEq, Eq)]
+pub struct GameStatus {
+    pub state: AppState,
+    pub score: u32,
+    pub level: u32,
+    pub lines: u32,
+    pub combo: u32,
+    pub back_to_back: u32,
+    /// type of the moveable brick, None between a lock and the next spawn
+    pub current: Option<usize>,
+    /// types of the bricks that spawn next, in order
+    pub next: Vec<usize>,
+    pub hold: Option<usize>,
+    pub can_hold: bool,
+}
+
+impl Default for GameStatus {
+    fn default() -> Self {
+        Self {
+            state: AppState::default(),
+            score: 0,
+            level: 1,
+            lines: 0,
+            combo: 0,
+            back_to_back: 0,
+            current: None,
+            next: vec![],
+            hold: None,
+            can_hold: true,
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn status_update(
+    query_brick_movable: Query<(), With<BrickMoveable>>,
+    state: Res<State<AppState>>,
+    score: Res<Score>,
+    level: Res<Level>,
+    lines: Res<Lines>,
+    combo: Res<Combo>,
+    back_to_back: Res<BackToBack>,
+    brick_state: Res<BrickState>,
+    hold: Res<Hold>,
+    piece_randomizer: Res<PieceRandomizer>,
+    mut status: ResMut<GameStatus>,
+) {
+    let status_new = GameStatus {
+        state: *state.get(),
+        score: score.get(),
+        level: level.0,
+        lines: lines.0,
+        combo: combo.0,
+        back_to_back: back_to_back.0,
+        current: (!query_brick_movable.is_empty()).then_some(brick_state.brick_type_index),
+        next: piece_randomizer.upcoming().collect(),
+        hold: hold.brick_type_index,
+        can_hold: hold.can_hold,
+    };
+
+    // only touch the resource on a real change, so readers can rely on change detection
+    if *status != status_new {
+        *status = status_new;
+    }
+}
@@ -0,0 +1,134 @@
+use bevy::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// where saved data lives, a file per name on desktop and a localStorage key on the web
+pub trait Storage {
+    fn read(&self, name: &str) -> Option<String>;
+    fn write(&self, name: &str, content: &str) -> Result<(), String>;
+    /// a name that was never written is not an error
+    fn remove(&self, name: &str) -> Result<(), String>;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub struct FileStorage;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileStorage {
+    /// save files live next to the game, one json file per kind of data
+    const SAVE_DIR: &'static str = "save";
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Storage for FileStorage {
+    fn read(&self, name: &str) -> Option<String> {
+        std::fs::read_to_string(std::path::Path::new(Self::SAVE_DIR).join(name)).ok()
+    }
+
+    /// a name with a directory in it gets that directory under the save directory
+    fn write(&self, name: &str, content: &str) -> Result<(), String> {
+        let path = std::path::Path::new(Self::SAVE_DIR).join(name);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+        }
+        std::fs::write(path, content).map_err(|err| err.to_string())
+    }
+
+    fn remove(&self, name: &str) -> Result<(), String> {
+        match std::fs::remove_file(std::path::Path::new(Self::SAVE_DIR).join(name)) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err.to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub struct LocalStorage;
+
+#[cfg(target_arch = "wasm32")]
+impl LocalStorage {
+    const KEY_PREFIX: &'static str = "bevy-tetris/";
+
+    fn local_storage() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Storage for LocalStorage {
+    fn read(&self, name: &str) -> Option<String> {
+        Self::local_storage()?
+            .get_item(&format!("{}{}", Self::KEY_PREFIX, name))
+            .ok()?
+    }
+
+    fn write(&self, name: &str, content: &str) -> Result<(), String> {
+        Self::local_storage()
+            .ok_or_else(|| "localStorage is not available".to_string())?
+            .set_item(&format!("{}{}", Self::KEY_PREFIX, name), content)
+            .map_err(|err| format!("{:?}", err))
+    }
+
+    fn remove(&self, name: &str) -> Result<(), String> {
+        Self::local_storage()
+            .ok_or_else(|| "localStorage is not available".to_string())?
+            .remove_item(&format!("{}{}", Self::KEY_PREFIX, name))
+            .map_err(|err| format!("{:?}", err))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn platform_storage() -> impl Storage {
+    FileStorage
+}
+
+#[cfg(target_arch = "wasm32")]
+fn platform_storage() -> impl Storage {
+    LocalStorage
+}
+
+/// read a saved value, None if it was never saved or can't be read
+pub fn load<T: DeserializeOwned>(name: &str) -> Option<T> {
+    let content = platform_storage().read(name)?;
+    match serde_json::from_str(&content) {
+        Ok(value) => Some(value),
+        Err(err) => {
+            warn!("ignore broken save {}: {}", name, err);
+            None
+        }
+    }
+}
+
+/// add a line at the end of a text file, starting it with `header` when it doesn't exist
+/// yet, failures are logged but never stop the game
+pub fn append(name: &str, header: Option<&str>, line: &str) {
+    let storage = platform_storage();
+    let mut content = match storage.read(name) {
+        Some(content) => content,
+        None => header
+            .map(|header| format!("{}\n", header))
+            .unwrap_or_default(),
+    };
+    content.push_str(line);
+    content.push('\n');
+    if let Err(err) = storage.write(name, &content) {
+        warn!("failed to append to {}: {}", name, err);
+    }
+}
+
+/// delete a saved value, failures are logged but never stop the game
+pub fn remove(name: &str) {
+    if let Err(err) = platform_storage().remove(name) {
+        warn!("failed to remove {}: {}", name, err);
+    }
+}
+
+/// write a value, failures are logged but never stop the game, true when it was written
+pub fn save<T: Serialize>(name: &str, value: &T) -> bool {
+    let result = serde_json::to_string_pretty(value)
+        .map_err(|err| err.to_string())
+        .and_then(|content| platform_storage().write(name, &content));
+    if let Err(err) = &result {
+        warn!("failed to save {}: {}", name, err);
+    }
+    result.is_ok()
+}
@@ -0,0 +1,84 @@
+use bevy::{asset::LoadState, prelude::*, render::texture::DEFAULT_IMAGE_HANDLE};
+
+pub struct TexturePlugin;
+
+impl Plugin for TexturePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BrickTexture>()
+            .add_systems(PreStartup, setup_texture)
+            .add_systems(Update, texture_load_check);
+    }
+}
+
+const BRICK_TEXTURE_PATH: &str = "textures/brick.png";
+
+/// marker for sprites that should use the brick texture once it is loaded
+#[derive(Debug, Component)]
+pub struct BrickSprite;
+
+#[derive(Debug, Resource, Default)]
+pub struct BrickTexture {
+    loading: Option<Handle<Image>>,
+    loaded: Option<Handle<Image>>,
+}
+
+impl BrickTexture {
+    /// the beveled block texture, or the default white image (flat color) if it's not loaded
+    pub fn image(&self) -> Handle<Image> {
+        self.loaded
+            .clone()
+            .unwrap_or_else(|| DEFAULT_IMAGE_HANDLE.typed())
+    }
+}
+
+/// sprite for a single brick cell, tinted by color
+pub fn brick_sprite_bundle(
+    brick_texture: &BrickTexture,
+    color: Color,
+    size: f32,
+    transform: Transform,
+) -> (SpriteBundle, BrickSprite) {
+    (
+        SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(Vec2::new(size, size)),
+                ..default()
+            },
+            texture: brick_texture.image(),
+            transform,
+            ..default()
+        },
+        BrickSprite,
+    )
+}
+
+fn setup_texture(asset_server: Res<AssetServer>, mut brick_texture: ResMut<BrickTexture>) {
+    brick_texture.loading = Some(asset_server.load(BRICK_TEXTURE_PATH));
+}
+
+fn texture_load_check(
+    asset_server: Res<AssetServer>,
+    mut brick_texture: ResMut<BrickTexture>,
+    mut query_sprite: Query<&mut Handle<Image>, With<BrickSprite>>,
+) {
+    let Some(handle) = brick_texture.loading.clone() else {
+        return;
+    };
+
+    match asset_server.get_load_state(&handle) {
+        LoadState::Loaded => {
+            for mut texture in query_sprite.iter_mut() {
+                *texture = handle.clone();
+            }
+            brick_texture.loading = None;
+            brick_texture.loaded = Some(handle);
+        }
+        LoadState::Failed => {
+            // keep flat color bricks
+            warn!("brick texture {} failed to load", BRICK_TEXTURE_PATH);
+            brick_texture.loading = None;
+        }
+        _ => {}
+    }
+}
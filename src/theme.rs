@@ -0,0 +1,204 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{config::GameConfig, storage};
+
+pub struct ThemePlugin;
+
+impl Plugin for ThemePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Theme>()
+            .add_systems(PreStartup, theme_load)
+            .add_systems(Update, (theme_select, theme_update).chain());
+    }
+}
+
+const THEME_FILE: &str = "theme.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ThemeKind {
+    #[default]
+    Classic,
+    Dark,
+    Light,
+    /// strongly distinct colors on black, every cell framed by a thick outline
+    HighContrast,
+}
+
+impl ThemeKind {
+    pub fn next(self) -> Self {
+        match self {
+            ThemeKind::Classic => ThemeKind::Dark,
+            ThemeKind::Dark => ThemeKind::Light,
+            ThemeKind::Light => ThemeKind::HighContrast,
+            ThemeKind::HighContrast => ThemeKind::Classic,
+        }
+    }
+
+    pub fn theme(self) -> Theme {
+        match self {
+            ThemeKind::Classic => Theme {
+                kind: self,
+                board_border: Color::rgb(0.8, 0.8, 0.8),
+                board_background: Color::rgb(0.2, 0.2, 0.2),
+                grid_line: Color::rgba(1.0, 1.0, 1.0, 0.08),
+                background_brick: Color::rgba(0.2, 0.8, 0.1, 0.1),
+                trajectory: Color::rgba(0.6, 1.0, 0.5, 0.2),
+                brick_arr: [
+                    Color::rgb(0.95, 0.85, 0.2),
+                    Color::rgb(0.2, 0.85, 0.9),
+                    Color::rgb(0.2, 0.35, 0.95),
+                    Color::rgb(0.95, 0.55, 0.1),
+                    Color::rgb(0.4, 0.9, 0.2),
+                    Color::rgb(0.95, 0.2, 0.2),
+                    Color::rgb(0.65, 0.25, 0.9),
+                ],
+                garbage: Color::rgb(0.5, 0.5, 0.5),
+                hold_disabled: Color::rgb(0.4, 0.4, 0.4),
+                text: Color::WHITE,
+                gameover_text: Color::rgb(1.0, 0.5, 0.0),
+                ceiling: Color::rgba(1.0, 1.0, 1.0, 0.35),
+                danger: Color::rgb(1.0, 0.2, 0.2),
+                accent: Color::rgb(1.0, 0.9, 0.3),
+                outline: Color::NONE,
+                active_outline: Color::NONE,
+                outline_width: 0.0,
+            },
+            ThemeKind::Dark => Theme {
+                kind: self,
+                board_border: Color::rgb(0.3, 0.3, 0.35),
+                board_background: Color::rgb(0.05, 0.05, 0.07),
+                grid_line: Color::rgba(1.0, 1.0, 1.0, 0.05),
+                background_brick: Color::rgba(0.3, 0.3, 0.4, 0.08),
+                trajectory: Color::rgba(0.6, 0.6, 0.8, 0.16),
+                brick_arr: [
+                    Color::rgb(0.75, 0.65, 0.15),
+                    Color::rgb(0.15, 0.6, 0.65),
+                    Color::rgb(0.2, 0.3, 0.75),
+                    Color::rgb(0.75, 0.4, 0.1),
+                    Color::rgb(0.3, 0.65, 0.15),
+                    Color::rgb(0.7, 0.15, 0.15),
+                    Color::rgb(0.5, 0.2, 0.7),
+                ],
+                garbage: Color::rgb(0.3, 0.3, 0.32),
+                hold_disabled: Color::rgb(0.2, 0.2, 0.22),
+                text: Color::rgb(0.8, 0.8, 0.85),
+                gameover_text: Color::rgb(0.9, 0.3, 0.2),
+                ceiling: Color::rgba(0.8, 0.8, 0.9, 0.25),
+                danger: Color::rgb(0.85, 0.2, 0.15),
+                accent: Color::rgb(0.5, 0.9, 1.0),
+                outline: Color::NONE,
+                active_outline: Color::NONE,
+                outline_width: 0.0,
+            },
+            ThemeKind::Light => Theme {
+                kind: self,
+                board_border: Color::rgb(0.3, 0.3, 0.3),
+                board_background: Color::rgb(0.92, 0.92, 0.9),
+                grid_line: Color::rgba(0.0, 0.0, 0.0, 0.08),
+                background_brick: Color::rgba(0.0, 0.0, 0.0, 0.03),
+                trajectory: Color::rgba(0.0, 0.0, 0.0, 0.08),
+                brick_arr: [
+                    Color::rgb(0.9, 0.75, 0.0),
+                    Color::rgb(0.0, 0.7, 0.8),
+                    Color::rgb(0.1, 0.25, 0.85),
+                    Color::rgb(0.9, 0.45, 0.0),
+                    Color::rgb(0.2, 0.7, 0.1),
+                    Color::rgb(0.85, 0.1, 0.1),
+                    Color::rgb(0.55, 0.15, 0.8),
+                ],
+                garbage: Color::rgb(0.55, 0.55, 0.55),
+                hold_disabled: Color::rgb(0.75, 0.75, 0.75),
+                text: Color::rgb(0.1, 0.1, 0.1),
+                gameover_text: Color::rgb(0.8, 0.2, 0.0),
+                ceiling: Color::rgba(0.0, 0.0, 0.0, 0.35),
+                danger: Color::rgb(0.9, 0.1, 0.1),
+                accent: Color::rgb(1.0, 0.6, 0.0),
+                outline: Color::NONE,
+                active_outline: Color::NONE,
+                outline_width: 0.0,
+            },
+            ThemeKind::HighContrast => Theme {
+                kind: self,
+                board_border: Color::WHITE,
+                board_background: Color::BLACK,
+                grid_line: Color::rgba(1.0, 1.0, 1.0, 0.15),
+                background_brick: Color::NONE,
+                trajectory: Color::rgba(1.0, 1.0, 1.0, 0.2),
+                brick_arr: [
+                    Color::rgb(1.0, 1.0, 0.0),
+                    Color::rgb(0.0, 1.0, 1.0),
+                    Color::rgb(0.2, 0.4, 1.0),
+                    Color::rgb(1.0, 0.5, 0.0),
+                    Color::rgb(0.0, 1.0, 0.0),
+                    Color::rgb(1.0, 0.0, 0.0),
+                    Color::rgb(1.0, 0.0, 1.0),
+                ],
+                garbage: Color::rgb(0.6, 0.6, 0.6),
+                hold_disabled: Color::rgb(0.3, 0.3, 0.3),
+                text: Color::WHITE,
+                gameover_text: Color::rgb(1.0, 1.0, 0.0),
+                ceiling: Color::WHITE,
+                danger: Color::rgb(1.0, 0.0, 0.0),
+                accent: Color::WHITE,
+                outline: Color::rgb(0.85, 0.85, 0.85),
+                active_outline: Color::WHITE,
+                outline_width: 3.0,
+            },
+        }
+    }
+}
+
+/// all colors used for rendering, one per brick type in `brick_arr`
+#[derive(Debug, Resource, Clone)]
+pub struct Theme {
+    pub kind: ThemeKind,
+    pub board_border: Color,
+    pub board_background: Color,
+    pub grid_line: Color,
+    pub background_brick: Color,
+    /// background cells under the moveable brick when the trajectory is shown
+    pub trajectory: Color,
+    pub brick_arr: [Color; 7],
+    pub garbage: Color,
+    pub hold_disabled: Color,
+    pub text: Color,
+    pub gameover_text: Color,
+    pub ceiling: Color,
+    /// warning color, e.g. for a stack above the ceiling
+    pub danger: Color,
+    /// bright color for celebrations, e.g. the border pulse on a perfect clear
+    pub accent: Color,
+    /// border around every brick cell, only drawn when `outline_width` is above 0
+    pub outline: Color,
+    /// border around the cells of the moveable brick
+    pub active_outline: Color,
+    pub outline_width: f32,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        ThemeKind::default().theme()
+    }
+}
+
+fn theme_select(keys: Res<Input<KeyCode>>, mut config: ResMut<GameConfig>) {
+    if keys.just_pressed(KeyCode::T) {
+        config.theme = config.theme.next();
+    }
+}
+
+/// the last picked theme is saved, so it's back on the next start
+fn theme_load(mut config: ResMut<GameConfig>, mut theme: ResMut<Theme>) {
+    if let Some(theme_kind) = storage::load::<ThemeKind>(THEME_FILE) {
+        config.theme = theme_kind;
+        *theme = theme_kind.theme();
+    }
+}
+
+fn theme_update(config: Res<GameConfig>, mut theme: ResMut<Theme>) {
+    if config.is_changed() && theme.kind != config.theme {
+        *theme = config.theme.theme();
+        storage::save(THEME_FILE, &config.theme);
+    }
+}
@@ -0,0 +1,90 @@
+use bevy::prelude::*;
+
+use crate::{
+    brick::{LevelUpEvent, RestartEvent},
+    config::GameConfig,
+    root::BoardRoot,
+};
+
+pub struct TiltPlugin;
+
+impl Plugin for TiltPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Tilt>()
+            .add_systems(Update, (tilt_toggle, tilt_start, tilt_update).chain());
+    }
+}
+
+/// largest angle of the wobble, in degrees
+const TILT_ANGLE: f32 = 3.0;
+const TILT_DURATION: f32 = 0.8;
+/// swings back and forth while it settles
+const TILT_SWINGS: f32 = 2.5;
+
+/// the wobble after a level up, it turns the board root so the board and the bricks tilt
+/// together while their positions on the board stay untouched
+#[derive(Debug, Resource)]
+pub struct Tilt {
+    timer: Timer,
+    /// alternates every level so the board doesn't always lean the same way
+    sign: f32,
+}
+
+impl Default for Tilt {
+    fn default() -> Self {
+        let mut timer = Timer::from_seconds(TILT_DURATION, TimerMode::Once);
+        timer.tick(timer.duration());
+        Self { timer, sign: 1.0 }
+    }
+}
+
+fn tilt_toggle(keys: Res<Input<KeyCode>>, mut config: ResMut<GameConfig>) {
+    if keys.just_pressed(KeyCode::F1) {
+        config.level_up_tilt = !config.level_up_tilt;
+    }
+}
+
+fn tilt_start(
+    mut tilt: ResMut<Tilt>,
+    mut level_up_event_reader: EventReader<LevelUpEvent>,
+    mut restart_event_reader: EventReader<RestartEvent>,
+    config: Res<GameConfig>,
+) {
+    if !restart_event_reader.is_empty() {
+        restart_event_reader.clear();
+        *tilt = Tilt::default();
+    }
+
+    if level_up_event_reader.is_empty() {
+        return;
+    }
+    level_up_event_reader.clear();
+
+    if config.level_up_tilt {
+        tilt.timer.reset();
+        tilt.sign = -tilt.sign;
+    }
+}
+
+/// a damped swing that ends exactly level
+fn tilt_update(
+    mut query_root: Query<&mut Transform, With<BoardRoot>>,
+    mut tilt: ResMut<Tilt>,
+    time: Res<Time>,
+) {
+    if tilt.timer.finished() {
+        return;
+    }
+    tilt.timer.tick(time.delta());
+
+    let angle = if tilt.timer.finished() {
+        0.0
+    } else {
+        let t = tilt.timer.percent();
+        tilt.sign
+            * TILT_ANGLE.to_radians()
+            * (1.0 - t)
+            * (t * TILT_SWINGS * std::f32::consts::TAU).sin()
+    };
+    query_root.single_mut().rotation = Quat::from_rotation_z(angle);
+}
@@ -0,0 +1,109 @@
+use bevy::prelude::*;
+
+use crate::app_state::AppState;
+
+pub struct TransitionPlugin;
+
+impl Plugin for TransitionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Transition>()
+            .add_systems(Startup, setup_overlay)
+            .add_systems(Update, transition_update);
+    }
+}
+
+const TRANSITION_DURATION: f32 = 0.6;
+
+/// fades the screen to black, switches state at the midpoint, then fades back in
+#[derive(Debug, Resource)]
+pub struct Transition {
+    target: Option<AppState>,
+    timer: Timer,
+    switched: bool,
+    /// why the last transition started, for the state log of the `debug` feature
+    reason: &'static str,
+}
+
+impl Default for Transition {
+    fn default() -> Self {
+        Self {
+            target: None,
+            timer: Timer::from_seconds(TRANSITION_DURATION, TimerMode::Once),
+            switched: false,
+            reason: "startup",
+        }
+    }
+}
+
+impl Transition {
+    /// start fading to the target state, ignored while another transition runs
+    pub fn start(&mut self, target: AppState, reason: &'static str) {
+        if self.target.is_some() {
+            return;
+        }
+
+        self.target = Some(target);
+        self.timer.reset();
+        self.switched = false;
+        self.reason = reason;
+    }
+
+    pub fn reason(&self) -> &'static str {
+        self.reason
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.target.is_some()
+    }
+}
+
+/// run condition, true while a transition is fading
+pub fn in_transition(transition: Res<Transition>) -> bool {
+    transition.is_active()
+}
+
+#[derive(Debug, Component)]
+pub struct TransitionOverlay;
+
+fn setup_overlay(mut commands: Commands) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.0).into(),
+            z_index: ZIndex::Global(100),
+            ..default()
+        },
+        TransitionOverlay,
+    ));
+}
+
+fn transition_update(
+    mut query_overlay: Query<&mut BackgroundColor, With<TransitionOverlay>>,
+    mut transition: ResMut<Transition>,
+    mut state: ResMut<NextState<AppState>>,
+    time: Res<Time>,
+) {
+    let Some(target) = transition.target else {
+        return;
+    };
+
+    transition.timer.tick(time.delta());
+    let t = transition.timer.percent();
+
+    if t >= 0.5 && !transition.switched {
+        state.set(target);
+        transition.switched = true;
+    }
+
+    let alpha = if t < 0.5 { t * 2.0 } else { (1.0 - t) * 2.0 };
+    query_overlay.single_mut().0.set_a(alpha);
+
+    if transition.timer.finished() {
+        transition.target = None;
+    }
+}
@@ -0,0 +1,198 @@
+use bevy::prelude::*;
+
+use crate::{
+    app_state::AppState,
+    board::Board,
+    brick::{get_brick_pos_xy, RestartEvent, BRICK_WIDTH, GRID_WIDTH},
+    config::{BoardConfig, GameConfig, GameMode},
+    root::OnBoard,
+    theme::Theme,
+    transition::in_transition,
+};
+
+pub struct TutorialPlugin;
+
+impl Plugin for TutorialPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Tutorial>()
+            .add_systems(Update, tutorial_reset)
+            .add_systems(
+                Update,
+                tutorial_advance.run_if(in_state(AppState::Gaming).and_then(not(in_transition))),
+            )
+            .add_systems(
+                Update,
+                tutorial_overlay_update
+                    .after(tutorial_reset)
+                    .after(tutorial_advance),
+            );
+    }
+}
+
+const LABEL_FONT_SIZE: f32 = 16.0;
+const LESSON_FONT_SIZE: f32 = 28.0;
+/// text over the board and the live bricks
+const OVERLAY_Z: f32 = 5.0;
+/// highlighted cells under the ghost, so the bricks filling them cover them
+const HIGHLIGHT_Z: f32 = 0.7;
+const HIGHLIGHT_ALPHA: f32 = 0.25;
+
+/// one lesson, done once every cell in `cell_arr` is filled, or on enter when it has none
+#[derive(Debug)]
+pub struct TutorialStep {
+    pub text: &'static str,
+    pub cell_arr: &'static [(i8, i8)],
+}
+
+/// columns up to 5 only, so every lesson fits the narrowest board
+const TUTORIAL_STEP_ARR: [TutorialStep; 4] = [
+    TutorialStep {
+        text: "Columns are numbered along the bottom, rows up the side, enter to go on",
+        cell_arr: &[],
+    },
+    TutorialStep {
+        text: "Lay a brick flat in the highlighted cells",
+        cell_arr: &[(0, 0), (1, 0), (2, 0), (3, 0)],
+    },
+    TutorialStep {
+        text: "Now fill row 1 on top of it",
+        cell_arr: &[(0, 1), (1, 1), (2, 1), (3, 1)],
+    },
+    TutorialStep {
+        text: "Stand a brick up in column 5",
+        cell_arr: &[(5, 0), (5, 1), (5, 2), (5, 3)],
+    },
+];
+
+/// the lesson the player is on in tutorial mode, past the last one when they are all done
+#[derive(Debug, Resource, Default)]
+pub struct Tutorial {
+    pub step_index: usize,
+}
+
+impl Tutorial {
+    pub fn step(&self) -> Option<&'static TutorialStep> {
+        TUTORIAL_STEP_ARR.get(self.step_index)
+    }
+}
+
+/// column and row numbers, the step text and the highlighted cells
+#[derive(Debug, Component)]
+pub struct TutorialOverlay;
+
+fn tutorial_reset(mut tutorial: ResMut<Tutorial>, mut event_reader: EventReader<RestartEvent>) {
+    if event_reader.is_empty() {
+        return;
+    }
+    event_reader.clear();
+
+    *tutorial = Tutorial::default();
+}
+
+/// a step is done once the stack covers its cells, a step without cells waits for enter,
+/// which also skips any step
+fn tutorial_advance(
+    keys: Res<Input<KeyCode>>,
+    mut tutorial: ResMut<Tutorial>,
+    board: Res<Board>,
+    config: Res<GameConfig>,
+) {
+    if config.mode != GameMode::Tutorial {
+        return;
+    }
+    let Some(step) = tutorial.step() else {
+        return;
+    };
+
+    let is_filled = !step.cell_arr.is_empty()
+        && board.is_changed()
+        && step.cell_arr.iter().all(|&(x, y)| board.is_filled(x, y));
+    if is_filled || keys.just_pressed(KeyCode::Return) {
+        tutorial.step_index += 1;
+    }
+}
+
+/// rebuilt whenever the step, the board size or the look changes, gone outside tutorial mode
+fn tutorial_overlay_update(
+    mut commands: Commands,
+    query_overlay: Query<Entity, With<TutorialOverlay>>,
+    tutorial: Res<Tutorial>,
+    board_config: Res<BoardConfig>,
+    config: Res<GameConfig>,
+    theme: Res<Theme>,
+) {
+    if !tutorial.is_changed()
+        && !board_config.is_changed()
+        && !config.is_changed()
+        && !theme.is_changed()
+    {
+        return;
+    }
+
+    for entity in query_overlay.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    if config.mode != GameMode::Tutorial {
+        return;
+    }
+
+    let is_mirrored = config.is_mirrored();
+    let label_style = TextStyle {
+        font_size: LABEL_FONT_SIZE,
+        color: theme.text,
+        ..default()
+    };
+    let mut spawn_text = |value: String, xy: (i32, i32), style: TextStyle| {
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(value, style).with_alignment(TextAlignment::Center),
+                transform: Transform::from_xyz(xy.0 as f32, xy.1 as f32, OVERLAY_Z),
+                ..default()
+            },
+            TutorialOverlay,
+            OnBoard,
+        ));
+    };
+
+    // the labels sit one cell outside the board, under the columns and beside the rows
+    for x in 0..board_config.width {
+        let xy = get_brick_pos_xy(x, -1, &board_config, is_mirrored);
+        spawn_text(x.to_string(), xy, label_style.clone());
+    }
+    for y in 0..board_config.height {
+        let xy = get_brick_pos_xy(-1, y, &board_config, is_mirrored);
+        spawn_text(y.to_string(), xy, label_style.clone());
+    }
+
+    let text = tutorial.step().map_or("Tutorial done", |step| step.text);
+    let xy = get_brick_pos_xy(0, board_config.height, &board_config, is_mirrored);
+    spawn_text(
+        text.to_string(),
+        (0, xy.1 + GRID_WIDTH as i32),
+        TextStyle {
+            font_size: LESSON_FONT_SIZE,
+            color: theme.text,
+            ..default()
+        },
+    );
+
+    let Some(step) = tutorial.step() else {
+        return;
+    };
+    for &(x, y) in step.cell_arr {
+        let xy = get_brick_pos_xy(x, y, &board_config, is_mirrored);
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: theme.text.with_a(HIGHLIGHT_ALPHA),
+                    custom_size: Some(Vec2::splat(BRICK_WIDTH as f32)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(xy.0 as f32, xy.1 as f32, HIGHLIGHT_Z),
+                ..default()
+            },
+            TutorialOverlay,
+            OnBoard,
+        ));
+    }
+}
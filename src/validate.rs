@@ -0,0 +1,135 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    app_state::AppState,
+    board::{board_sync, Board, Cell},
+    brick::{BrickKind, BrickMoveable, BrickPos, ClearDelay, ToppedOut},
+    transition::Transition,
+};
+
+/// invariant checks that panic on the first broken frame, only built with the `debug` feature
+pub struct ValidatePlugin;
+
+impl Plugin for ValidatePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Last, board_validate.after(board_sync));
+    }
+}
+
+/// runs once everything of the frame is applied and the board is synced, the topped out
+/// brick overlaps the stack on purpose and is left out
+#[allow(clippy::type_complexity)]
+fn board_validate(
+    query_brick: Query<(&BrickPos, Option<&BrickKind>, Option<&BrickMoveable>), Without<ToppedOut>>,
+    board: Res<Board>,
+    clear_delay: Res<ClearDelay>,
+    state: Res<State<AppState>>,
+    transition: Res<Transition>,
+) {
+    let mut error_arr = vec![];
+
+    let mut count_map = HashMap::new();
+    for (brick_pos, _, _) in query_brick.iter() {
+        *count_map.entry(*brick_pos).or_insert(0) += 1;
+        if !board.is_inside(brick_pos.x, brick_pos.y) {
+            error_arr.push(format!(
+                "brick at ({}, {}) is out of the board",
+                brick_pos.x, brick_pos.y
+            ));
+        }
+    }
+    for (brick_pos, count) in count_map.iter().filter(|(_, &count)| count > 1) {
+        error_arr.push(format!(
+            "{} bricks share ({}, {})",
+            count, brick_pos.x, brick_pos.y
+        ));
+    }
+
+    // the board only catches up once delayed lines are gone, and on game over the stack
+    // falls off the screen while the board keeps the final position
+    if !clear_delay.is_active() && *state.get() != AppState::GameOver {
+        let mut board_expected = Board::new(board.width(), board.height());
+        for (brick_pos, brick_kind, _) in query_brick.iter().filter(|(_, _, m)| m.is_none()) {
+            let cell = match brick_kind {
+                Some(brick_kind) => Cell::Brick(brick_kind.0),
+                None => Cell::Garbage,
+            };
+            board_expected.set(brick_pos.x, brick_pos.y, Some(cell));
+        }
+        if board_expected != *board {
+            error_arr.push("Board doesn't match the stable bricks".to_string());
+        }
+    }
+
+    // none between a lock and the next spawn
+    let movable_count = query_brick.iter().filter(|(_, _, m)| m.is_some()).count();
+    if *state.get() == AppState::Gaming
+        && !transition.is_active()
+        && movable_count != 0
+        && movable_count != 4
+    {
+        error_arr.push(format!("{} moveable cells, expected 4", movable_count));
+    }
+
+    if error_arr.is_empty() {
+        return;
+    }
+
+    panic!(
+        "board invariants broken:\n  {}\nbricks:\n{}\nBoard:\n{}",
+        error_arr.join("\n  "),
+        dump_bricks(&query_brick, &board),
+        dump_board(&board)
+    );
+}
+
+/// '#' stable, 'G' garbage, '@' moveable, '!' shared by more than one brick, top row first
+#[allow(clippy::type_complexity)]
+fn dump_bricks(
+    query_brick: &Query<
+        (&BrickPos, Option<&BrickKind>, Option<&BrickMoveable>),
+        Without<ToppedOut>,
+    >,
+    board: &Board,
+) -> String {
+    let mut char_map = HashMap::new();
+    for (brick_pos, brick_kind, brick_movable) in query_brick.iter() {
+        let c = if brick_movable.is_some() {
+            '@'
+        } else if brick_kind.is_some() {
+            '#'
+        } else {
+            'G'
+        };
+        char_map
+            .entry(*brick_pos)
+            .and_modify(|existing| *existing = '!')
+            .or_insert(c);
+    }
+
+    (0..board.height())
+        .rev()
+        .map(|y| {
+            (0..board.width())
+                .map(|x| *char_map.get(&BrickPos::new(x, y)).unwrap_or(&'.'))
+                .collect::<String>()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn dump_board(board: &Board) -> String {
+    (0..board.height())
+        .rev()
+        .map(|y| {
+            (0..board.width())
+                .map(|x| match board.get(x, y) {
+                    Some(Cell::Brick(_)) => '#',
+                    Some(Cell::Garbage) => 'G',
+                    None => '.',
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{brick::GRID_WIDTH, letterbox::BarCamera, storage};
+
+pub struct ZoomPlugin;
+
+impl Plugin for ZoomPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(storage::load::<CellPixelSize>(ZOOM_FILE).unwrap_or_default())
+            .add_systems(Update, (zoom_input, zoom_apply).chain());
+    }
+}
+
+const ZOOM_FILE: &str = "zoom.json";
+
+/// world units of one board cell, everything is laid out in these
+const CELL_WORLD_SIZE: f32 = GRID_WIDTH as f32;
+const CELL_PIXEL_MIN: f32 = 16.0;
+const CELL_PIXEL_MAX: f32 = 64.0;
+const CELL_PIXEL_STEP: f32 = 4.0;
+
+/// on-screen pixels of one board cell, the camera scales the world to match, so the
+/// board, panels and meter zoom together while the logical board size stays the same,
+/// with the letterbox on it zooms relative to the game area fitted in the window
+#[derive(Debug, Resource, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CellPixelSize(pub f32);
+
+impl Default for CellPixelSize {
+    fn default() -> Self {
+        Self(CELL_WORLD_SIZE)
+    }
+}
+
+fn zoom_input(keys: Res<Input<KeyCode>>, mut cell_pixel_size: ResMut<CellPixelSize>) {
+    let step = if keys.any_just_pressed([KeyCode::Equals, KeyCode::NumpadAdd]) {
+        CELL_PIXEL_STEP
+    } else if keys.any_just_pressed([KeyCode::Minus, KeyCode::NumpadSubtract]) {
+        -CELL_PIXEL_STEP
+    } else {
+        return;
+    };
+
+    let size = (cell_pixel_size.0 + step).clamp(CELL_PIXEL_MIN, CELL_PIXEL_MAX);
+    if size != cell_pixel_size.0 {
+        cell_pixel_size.0 = size;
+        storage::save(ZOOM_FILE, cell_pixel_size.as_ref());
+    }
+}
+
+fn zoom_apply(
+    cell_pixel_size: Res<CellPixelSize>,
+    mut query_projection: Query<&mut OrthographicProjection, (With<Camera2d>, Without<BarCamera>)>,
+    mut is_applied: Local<bool>,
+) {
+    // the camera spawns in Startup, so the loaded zoom is applied on the first frame
+    if *is_applied && !cell_pixel_size.is_changed() {
+        return;
+    }
+
+    for mut projection in query_projection.iter_mut() {
+        projection.scale = CELL_WORLD_SIZE / cell_pixel_size.0;
+        *is_applied = true;
+    }
+}